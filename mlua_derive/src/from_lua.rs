@@ -1,9 +1,34 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
 pub fn from_lua(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, generics, .. } = parse_macro_input!(input as DeriveInput);
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match parse_from_table(&input) {
+        Ok(true) => from_table(input),
+        Ok(false) => from_userdata(input),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+// Checks whether the `#[mlua(from_table)]` attribute is set on the type
+fn parse_from_table(input: &DeriveInput) -> syn::Result<bool> {
+    let mut from_table = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("mlua")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("from_table") {
+                from_table = true;
+                return Ok(());
+            }
+            Err(meta.error("unsupported mlua attribute"))
+        })?;
+    }
+    Ok(from_table)
+}
+
+fn from_userdata(input: DeriveInput) -> TokenStream {
+    let DeriveInput { ident, generics, .. } = input;
 
     let ident_str = ident.to_string();
     let (impl_generics, ty_generics, _) = generics.split_for_impl();
@@ -29,3 +54,60 @@ pub fn from_lua(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+fn from_table(input: DeriveInput) -> TokenStream {
+    let DeriveInput {
+        ident,
+        generics,
+        data,
+        ..
+    } = input;
+
+    let fields = match data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => {
+                let msg = "`#[mlua(from_table)]` requires a struct with named fields";
+                return syn::Error::new_spanned(&ident, msg).to_compile_error().into();
+            }
+        },
+        _ => {
+            let msg = "`#[mlua(from_table)]` can only be used with structs";
+            return syn::Error::new_spanned(&ident, msg).to_compile_error().into();
+        }
+    };
+
+    let ident_str = ident.to_string();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let field_values = fields.iter().map(|field| {
+        let name = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let key = name.to_string();
+        let key = key.strip_prefix("r#").unwrap_or(&key).to_string();
+        quote! {
+            #name: ::mlua::ErrorContext::context(
+                table.get::<#ty>(#key),
+                concat!("failed to convert field '", #key, "' of ", #ident_str),
+            )?
+        }
+    });
+
+    quote! {
+      impl #impl_generics ::mlua::FromLua for #ident #ty_generics #where_clause {
+        fn from_lua(value: ::mlua::Value, _: &::mlua::Lua) -> ::mlua::Result<Self> {
+          match value {
+            ::mlua::Value::Table(table) => Ok(Self {
+              #(#field_values,)*
+            }),
+            _ => Err(::mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: #ident_str.to_string(),
+                message: Some("expected table".to_string()),
+            }),
+          }
+        }
+      }
+    }
+    .into()
+}
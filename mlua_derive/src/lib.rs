@@ -146,7 +146,7 @@ pub fn chunk(input: TokenStream) -> TokenStream {
 }
 
 #[cfg(feature = "macros")]
-#[proc_macro_derive(FromLua)]
+#[proc_macro_derive(FromLua, attributes(mlua))]
 pub fn from_lua(input: TokenStream) -> TokenStream {
     from_lua::from_lua(input)
 }
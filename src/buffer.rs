@@ -1,6 +1,7 @@
 #[cfg(feature = "serialize")]
 use serde::ser::{Serialize, Serializer};
 
+use crate::error::{Error, Result};
 use crate::types::ValueRef;
 
 /// A Luau buffer type.
@@ -53,6 +54,40 @@ impl Buffer {
         data[offset..offset + bytes.len()].copy_from_slice(bytes);
     }
 
+    /// Reads `len` bytes from the buffer at the given offset.
+    ///
+    /// Offset is 0-based. Unlike [`Buffer::read_bytes`], returns an error instead of panicking
+    /// if the range is out of bounds.
+    pub fn read_slice(&self, offset: usize, len: usize) -> Result<Vec<u8>> {
+        let data = unsafe { self.as_slice() };
+        let range = Self::check_range(offset, len, data.len())?;
+        Ok(data[range].to_vec())
+    }
+
+    /// Writes given bytes to the buffer at the given offset.
+    ///
+    /// Offset is 0-based. Unlike [`Buffer::write_bytes`], returns an error instead of panicking
+    /// if the bytes do not fit into the buffer.
+    pub fn write_slice(&self, offset: usize, bytes: &[u8]) -> Result<()> {
+        let data = unsafe {
+            let (buf, size) = self.as_raw_parts();
+            std::slice::from_raw_parts_mut(buf, size)
+        };
+        let range = Self::check_range(offset, bytes.len(), data.len())?;
+        data[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn check_range(offset: usize, len: usize, size: usize) -> Result<std::ops::Range<usize>> {
+        match offset.checked_add(len) {
+            Some(end) if end <= size => Ok(offset..end),
+            _ => Err(Error::runtime(format!(
+                "buffer range {offset}..{} out of bounds for buffer of length {size}",
+                offset.saturating_add(len)
+            ))),
+        }
+    }
+
     pub(crate) unsafe fn as_slice(&self) -> &[u8] {
         let (buf, size) = self.as_raw_parts();
         std::slice::from_raw_parts(buf, size)
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::ffi::CString;
+use std::fmt;
 use std::io::Result as IoResult;
 use std::panic::Location;
 use std::path::{Path, PathBuf};
@@ -8,8 +9,10 @@ use std::string::String as StdString;
 
 use crate::error::{Error, Result};
 use crate::function::Function;
+use crate::multi::MultiValue;
 use crate::state::{Lua, WeakLua};
 use crate::table::Table;
+use crate::thread::{Thread, ThreadStatus};
 use crate::traits::{FromLuaMulti, IntoLua, IntoLuaMulti};
 use crate::value::Value;
 
@@ -121,6 +124,7 @@ pub struct Chunk<'a> {
     pub(crate) source: IoResult<Cow<'a, [u8]>>,
     #[cfg(feature = "luau")]
     pub(crate) compiler: Option<Compiler>,
+    pub(crate) verify: bool,
 }
 
 /// Represents chunk mode (text or binary).
@@ -130,6 +134,126 @@ pub enum ChunkMode {
     Binary,
 }
 
+/// Describes how a chunk name is displayed by Lua in error messages and tracebacks.
+///
+/// Used by [`Chunk::set_name_as`] to choose the name prefix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChunkNameKind {
+    /// A file path (`@` prefix), displayed as `file.lua:10:`.
+    Source,
+    /// A custom name (`=` prefix), displayed as `name:10:`.
+    Literal,
+    /// A snippet of source code (no prefix), displayed as `[string "..."]:10:`.
+    #[default]
+    Snippet,
+}
+
+/// The result of compiling a line of interactive input using [`Lua::load_repl`].
+#[derive(Clone, Debug)]
+pub enum ReplLoadResult {
+    /// The input is a complete expression or statement, compiled into a function.
+    ///
+    /// For expressions, the function returns the expression values.
+    Complete(Function),
+    /// The input is an incomplete statement and more input is needed.
+    Incomplete,
+    /// The input has a syntax (or other) error.
+    Error(Error),
+}
+
+/// A chunk compiled once using [`Chunk::compile`].
+///
+/// Each call to [`CompiledChunk::to_function`] or [`CompiledChunk::to_function_with_env`] creates
+/// a new function from the compiled bytecode, without parsing the source again.
+pub struct CompiledChunk {
+    lua: WeakLua,
+    name: CString,
+    env: Option<Table>,
+    source: Vec<u8>,
+    mode: ChunkMode,
+    verify: bool,
+}
+
+impl CompiledChunk {
+    /// Creates a new function from the compiled chunk.
+    ///
+    /// The function uses the environment set by [`Chunk::set_environment`] before compiling,
+    /// or the global environment otherwise.
+    pub fn to_function(&self) -> Result<Function> {
+        self.load(self.env.as_ref())
+    }
+
+    /// Creates a new function from the compiled chunk, using `env` as its environment.
+    ///
+    /// See [`Chunk::set_environment`] for details.
+    pub fn to_function_with_env(&self, env: Table) -> Result<Function> {
+        self.load(Some(&env))
+    }
+
+    fn load(&self, env: Option<&Table>) -> Result<Function> {
+        let func = (self.lua.lock()).load_chunk(Some(&self.name), env, Some(self.mode), &self.source)?;
+        if self.verify {
+            Chunk::verify_function(&func)?;
+        }
+        Ok(func)
+    }
+}
+
+impl fmt::Debug for CompiledChunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CompiledChunk")
+            .field("name", &self.name)
+            .field("mode", &self.mode)
+            .field("size", &self.source.len())
+            .finish()
+    }
+}
+
+/// Iterator over the values produced by a chunk executed using [`Lua::exec_streaming`].
+///
+/// Yields the values passed to each `coroutine.yield` call in the chunk (one by one), followed by
+/// the values returned by the chunk. An error stops the iteration.
+#[derive(Debug)]
+pub struct ExecStream {
+    thread: Option<Result<Thread>>,
+    pending: MultiValue,
+}
+
+impl ExecStream {
+    pub(crate) fn new(thread: Result<Thread>) -> Self {
+        ExecStream {
+            thread: Some(thread),
+            pending: MultiValue::new(),
+        }
+    }
+}
+
+impl Iterator for ExecStream {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.pending.pop_front() {
+                return Some(Ok(value));
+            }
+            let thread = match self.thread.take()? {
+                Ok(thread) => thread,
+                Err(err) => return Some(Err(err)),
+            };
+            if thread.status() != ThreadStatus::Resumable {
+                return None;
+            }
+            match thread.resume::<MultiValue>(()) {
+                Ok(values) => {
+                    self.pending = values;
+                    self.thread = Some(Ok(thread));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
 /// Luau compiler
 #[cfg(any(feature = "luau", doc))]
 #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
@@ -217,21 +341,30 @@ impl Compiler {
         self
     }
 
-    #[doc(hidden)]
+    /// Sets the name of the library containing the vector constructor.
+    ///
+    /// Together with [`Compiler::set_vector_ctor`], allows the compiler to recognize calls like
+    /// `lib.ctor(x, y, z)` as vector construction (and emit the builtin call or constant vector).
+    /// When unset, the constructor is looked up as a global function.
     #[must_use]
     pub fn set_vector_lib(mut self, lib: impl Into<String>) -> Self {
         self.vector_lib = Some(lib.into());
         self
     }
 
-    #[doc(hidden)]
+    /// Sets the name of the function used to construct vectors, eg. `vec3` or `new`.
+    ///
+    /// The constructor must be available at runtime (as a global or in the vector library) and
+    /// behave like `vector.create`.
     #[must_use]
     pub fn set_vector_ctor(mut self, ctor: impl Into<String>) -> Self {
         self.vector_ctor = Some(ctor.into());
         self
     }
 
-    #[doc(hidden)]
+    /// Sets the name of the vector type used in type annotations.
+    ///
+    /// This enables type-directed optimizations of the code working with vectors.
     #[must_use]
     pub fn set_vector_type(mut self, r#type: impl Into<String>) -> Self {
         self.vector_type = Some(r#type.into());
@@ -331,6 +464,34 @@ impl Chunk<'_> {
         self
     }
 
+    /// Sets the name of this chunk, adding the prefix for the given [`ChunkNameKind`].
+    ///
+    /// The name must be plain (without a prefix), so tracebacks are rendered predictably
+    /// regardless of the name's origin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{ChunkNameKind, Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let err = lua.load("error('boom')")
+    ///     .set_name_as("script.lua", ChunkNameKind::Source)
+    ///     .exec()
+    ///     .unwrap_err();
+    /// assert!(err.to_string().contains("script.lua:1: boom"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_name_as(self, name: impl AsRef<str>, kind: ChunkNameKind) -> Self {
+        let name = name.as_ref();
+        match kind {
+            ChunkNameKind::Source => self.set_name(format!("@{name}")),
+            ChunkNameKind::Literal => self.set_name(format!("={name}")),
+            ChunkNameKind::Snippet => self.set_name(name),
+        }
+    }
+
     /// Sets the environment of the loaded chunk to the given value.
     ///
     /// In Lua >=5.2 main chunks always have exactly one upvalue, and this upvalue is used as the
@@ -349,6 +510,9 @@ impl Chunk<'_> {
 
     /// Sets whether the chunk is text or binary (autodetected by default).
     ///
+    /// If the mode is not set explicitly, it's chosen based on the chunk name extension
+    /// (`.luac` for binary) or otherwise detected from the source.
+    ///
     /// Be aware, Lua does not check the consistency of the code inside binary chunks.
     /// Running maliciously crafted bytecode can crash the interpreter.
     pub fn set_mode(mut self, mode: ChunkMode) -> Self {
@@ -356,6 +520,22 @@ impl Chunk<'_> {
         self
     }
 
+    /// Enables or disables an additional verification pass over the compiled chunk.
+    ///
+    /// When enabled, the function produced by the Lua VM is checked before being returned:
+    /// it must be a Lua (not C) function and must not capture more upvalues than a main chunk
+    /// can have (only `_ENV` on Lua 5.2+, none otherwise). This catches binary chunks that were
+    /// produced from something other than a top-level chunk.
+    ///
+    /// Luau does not expose a bytecode validator through its C API, so only the checks above are
+    /// performed there.
+    ///
+    /// Verification is disabled by default. It does not make running untrusted bytecode safe.
+    pub fn verify(mut self, enabled: bool) -> Self {
+        self.verify = enabled;
+        self
+    }
+
     /// Sets or overwrites a Luau compiler used for this chunk.
     ///
     /// See [`Compiler`] for details and possible options.
@@ -460,19 +640,89 @@ impl Chunk<'_> {
         #[cfg(feature = "luau")]
         if self.compiler.is_some() {
             // We don't need to compile source if no compiler set
-            self.compile();
+            self.compile_to_bytecode();
         }
 
+        let mode = self.mode.or_else(|| self.mode_from_name());
         let name = Self::convert_name(self.name)?;
-        self.lua
-            .lock()
-            .load_chunk(Some(&name), self.env?.as_ref(), self.mode, self.source?.as_ref())
+        let func =
+            self.lua
+                .lock()
+                .load_chunk(Some(&name), self.env?.as_ref(), mode, self.source?.as_ref())?;
+        if self.verify {
+            Self::verify_function(&func)?;
+        }
+        Ok(func)
+    }
+
+    /// Compiles the chunk into a [`CompiledChunk`] that can be instantiated many times.
+    ///
+    /// The source is parsed only once. Use [`CompiledChunk::to_function_with_env`] to create
+    /// functions running under different environments without loading the source again.
+    /// The environment (see [`Chunk::set_environment`]) and [verification] settings of the chunk
+    /// are applied to every function created from it.
+    ///
+    /// If loading binary chunks is disabled (see [`LuaOptions::allow_bytecode`]), the source is
+    /// checked once but kept as text.
+    ///
+    /// [`LuaOptions::allow_bytecode`]: crate::LuaOptions::allow_bytecode
+    /// [verification]: Chunk::verify
+    pub fn compile(self) -> Result<CompiledChunk> {
+        let mode = self.detect_mode();
+        let name = Self::convert_name(self.name)?;
+        let env = self.env?;
+        let source = self.source?;
+
+        #[cfg(feature = "luau")]
+        let (source, mode) = match mode {
+            ChunkMode::Text => {
+                let compiler = self.compiler.unwrap_or_default();
+                (compiler.compile(&source)?, ChunkMode::Binary)
+            }
+            ChunkMode::Binary => (source.into_owned(), mode),
+        };
+        #[cfg(not(feature = "luau"))]
+        let (source, mode) = {
+            let lua = self.lua.lock();
+            let func = lua.load_chunk(Some(&name), None, Some(mode), &source)?;
+            match mode {
+                ChunkMode::Text if lua.allow_bytecode() => (func.dump(false), ChunkMode::Binary),
+                _ => (source.into_owned(), mode),
+            }
+        };
+
+        Ok(CompiledChunk {
+            lua: self.lua,
+            name,
+            env,
+            source,
+            mode,
+            verify: self.verify,
+        })
+    }
+
+    fn verify_function(func: &Function) -> Result<()> {
+        if func.info().what == "C" {
+            return Err(Error::runtime("chunk verification failed: unexpected C function"));
+        }
+        let max_upvalues = if cfg!(any(feature = "lua51", feature = "luajit", feature = "luau")) {
+            0
+        } else {
+            1
+        };
+        let num_upvalues = func.num_upvalues();
+        if num_upvalues > max_upvalues {
+            return Err(Error::runtime(format!(
+                "chunk verification failed: {num_upvalues} upvalues (expected at most {max_upvalues})"
+            )));
+        }
+        Ok(())
     }
 
     /// Compiles the chunk and changes mode to binary.
     ///
     /// It does nothing if the chunk is already binary or invalid.
-    fn compile(&mut self) {
+    fn compile_to_bytecode(&mut self) {
         if let Ok(ref source) = self.source {
             if self.detect_mode() == ChunkMode::Text {
                 #[cfg(feature = "luau")]
@@ -496,6 +746,12 @@ impl Chunk<'_> {
     pub(crate) fn try_cache(mut self) -> Self {
         struct ChunksCache(HashMap<Vec<u8>, Vec<u8>>);
 
+        // Cached bytecode cannot be loaded if loading binary chunks is disabled
+        #[cfg(not(feature = "luau"))]
+        if !self.lua.lock().allow_bytecode() {
+            return self;
+        }
+
         // Try to fetch compiled chunk from cache
         let mut text_source = None;
         if let Ok(ref source) = self.source {
@@ -514,7 +770,7 @@ impl Chunk<'_> {
 
         // Compile and cache the chunk
         if let Some(text_source) = text_source {
-            self.compile();
+            self.compile_to_bytecode();
             if let Ok(ref binary_source) = self.source {
                 if self.detect_mode() == ChunkMode::Binary {
                     let lua = self.lua.lock();
@@ -552,11 +808,15 @@ impl Chunk<'_> {
             Ok(None) => None,
             Err(err) => return Err(err.clone()),
         };
-        self.lua.lock().load_chunk(Some(&name), env, None, &source)
+        let func = self.lua.lock().load_chunk(Some(&name), env, None, &source)?;
+        if self.verify {
+            Self::verify_function(&func)?;
+        }
+        Ok(func)
     }
 
     fn detect_mode(&self) -> ChunkMode {
-        if let Some(mode) = self.mode {
+        if let Some(mode) = self.mode.or_else(|| self.mode_from_name()) {
             return mode;
         }
         if let Ok(source) = &self.source {
@@ -572,6 +832,15 @@ impl Chunk<'_> {
         ChunkMode::Text
     }
 
+    /// Guesses the chunk mode from the file extension in the chunk name (if any).
+    fn mode_from_name(&self) -> Option<ChunkMode> {
+        let name = self.name.strip_prefix(['@', '=']).unwrap_or(&self.name);
+        match Path::new(name).extension()?.to_str()? {
+            "luac" => Some(ChunkMode::Binary),
+            _ => None,
+        }
+    }
+
     fn convert_name(name: String) -> Result<CString> {
         CString::new(name).map_err(|err| Error::runtime(format!("invalid name: {err}")))
     }
@@ -611,3 +880,86 @@ impl<T: AsChunk> IntoLua for WrappedChunk<T> {
             .map(Value::Function)
     }
 }
+
+/// Checks the header of a precompiled chunk before passing it to the Lua VM.
+#[cfg(not(feature = "luau"))]
+pub(crate) fn check_bytecode_header(source: &[u8]) -> Result<()> {
+    let header = bytecode_header();
+    let sig_len = ffi::LUA_SIGNATURE.len();
+    let n = source.len().min(sig_len);
+    if source[..n] != ffi::LUA_SIGNATURE[..n] {
+        return Err(Error::BytecodeError("invalid signature".to_string()));
+    }
+    if source.len() < header.len() {
+        return Err(Error::BytecodeError("truncated header".to_string()));
+    }
+    if source[sig_len] != header[sig_len] {
+        let msg = format!(
+            "version mismatch (expected {:#04x}, got {:#04x})",
+            header[sig_len], source[sig_len]
+        );
+        return Err(Error::BytecodeError(msg));
+    }
+    if source[..header.len()] != header[..] {
+        let msg = "incompatible format (produced by a different build or platform)";
+        return Err(Error::BytecodeError(msg.to_string()));
+    }
+    Ok(())
+}
+
+/// Returns the header of precompiled chunks produced by the current Lua VM.
+#[cfg(not(feature = "luau"))]
+fn bytecode_header() -> Vec<u8> {
+    #[cfg(not(feature = "luajit"))]
+    use std::mem::size_of;
+    #[cfg(any(feature = "lua53", feature = "lua52", feature = "lua51"))]
+    use std::os::raw::c_int;
+
+    #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52"))]
+    const LUAC_DATA: &[u8] = b"\x19\x93\r\n\x1a\n";
+
+    let mut header = ffi::LUA_SIGNATURE.to_vec();
+    #[cfg(feature = "lua54")]
+    {
+        header.extend([0x54, 0]);
+        header.extend(LUAC_DATA);
+        header.extend([
+            4,
+            size_of::<ffi::lua_Integer>() as u8,
+            size_of::<ffi::lua_Number>() as u8,
+        ]);
+        header.extend((0x5678 as ffi::lua_Integer).to_ne_bytes());
+        header.extend((370.5 as ffi::lua_Number).to_ne_bytes());
+    }
+    #[cfg(feature = "lua53")]
+    {
+        header.extend([0x53, 0]);
+        header.extend(LUAC_DATA);
+        header.extend([size_of::<c_int>() as u8, size_of::<usize>() as u8, 4]);
+        header.extend([
+            size_of::<ffi::lua_Integer>() as u8,
+            size_of::<ffi::lua_Number>() as u8,
+        ]);
+        header.extend((0x5678 as ffi::lua_Integer).to_ne_bytes());
+        header.extend((370.5 as ffi::lua_Number).to_ne_bytes());
+    }
+    #[cfg(any(feature = "lua52", feature = "lua51"))]
+    {
+        let version = if cfg!(feature = "lua52") { 0x52 } else { 0x51 };
+        let little_endian = cfg!(target_endian = "little") as u8;
+        header.extend([
+            version,
+            0,
+            little_endian,
+            size_of::<c_int>() as u8,
+            size_of::<usize>() as u8,
+            4,
+        ]);
+        header.extend([size_of::<ffi::lua_Number>() as u8, 0]);
+        #[cfg(feature = "lua52")]
+        header.extend(LUAC_DATA);
+    }
+    #[cfg(feature = "luajit")]
+    header.push(2);
+    header
+}
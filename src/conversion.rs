@@ -639,6 +639,10 @@ impl IntoLua for BString {
     }
 }
 
+/// Converts a Lua string (or a value coercible to string) to [`BString`].
+///
+/// This is the lossless path for arbitrary Lua string bytes: unlike the conversion to
+/// [`std::string::String`], the content is not required to be valid UTF-8.
 impl FromLua for BString {
     fn from_lua(value: Value, lua: &Lua) -> Result<Self> {
         let ty = value.type_name();
@@ -681,6 +685,13 @@ impl FromLua for BString {
     }
 }
 
+impl IntoLua for &BString {
+    #[inline]
+    fn into_lua(self, lua: &Lua) -> Result<Value> {
+        Ok(Value::String(lua.create_string(self)?))
+    }
+}
+
 impl IntoLua for &BStr {
     #[inline]
     fn into_lua(self, lua: &Lua) -> Result<Value> {
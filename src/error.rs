@@ -8,6 +8,10 @@ use std::string::String as StdString;
 use std::sync::Arc;
 
 use crate::private::Sealed;
+use crate::state::{Lua, RawLua};
+#[cfg(all(feature = "error-send", not(feature = "send")))]
+use crate::types::RegistryKey;
+use crate::value::Value;
 
 #[cfg(feature = "error-send")]
 type DynStdError = dyn StdError + Send + Sync;
@@ -35,6 +39,22 @@ pub enum Error {
     /// Among other things, this includes invoking operators on wrong types (such as calling or
     /// indexing a `nil` value).
     RuntimeError(StdString),
+    /// Lua runtime error raised with a non-string error object (eg. `error({code = 1})`).
+    ///
+    /// Any error value that is not a string or number and has no `__tostring` metamethod is
+    /// returned as this variant (previously such errors were stringified into
+    /// [`Error::RuntimeError`]). No traceback is attached to these errors, as the error object is
+    /// kept as is. Error objects having a `__tostring` metamethod are still converted to
+    /// [`Error::RuntimeError`].
+    ///
+    /// Returning this error from a Rust callback raises the contained value as is, so it can be
+    /// caught and inspected by `pcall` in Lua code. See [`Error::with_value`].
+    ErrorObject(ErrorValue),
+    /// Precompiled chunk (bytecode) has an invalid or incompatible header.
+    ///
+    /// Returned before passing the chunk to the Lua VM, eg. when the bytecode is truncated or was
+    /// produced by a different Lua version or for a platform with different type sizes.
+    BytecodeError(StdString),
     /// Lua memory error, aka `LUA_ERRMEM`
     ///
     /// The Lua VM returns this error when the allocator does not return the requested memory, aka
@@ -117,6 +137,12 @@ pub enum Error {
     /// [`Thread::resume`]: crate::Thread::resume
     /// [`Thread::status`]: crate::Thread::status
     CoroutineUnresumable,
+    /// Execution was aborted because its deadline has passed.
+    ///
+    /// Returned by [`Function::call_with_deadline`].
+    ///
+    /// [`Function::call_with_deadline`]: crate::Function::call_with_deadline
+    Timeout,
     /// An [`AnyUserData`] is not the expected type in a borrow.
     ///
     /// This error can only happen when manually using [`AnyUserData`], or when implementing
@@ -211,11 +237,115 @@ pub enum Error {
 pub type Result<T> = StdResult<T, Error>;
 
 #[cfg(not(tarpaulin_include))]
+/// Lua value raised as an error object, carried by [`Error::ErrorObject`].
+///
+/// Use [`ErrorValue::to_value`] to get the value back.
+#[derive(Debug, Clone)]
+pub struct ErrorValue {
+    type_name: &'static str,
+    inner: ErrorValueInner,
+}
+
+#[cfg(any(feature = "send", not(feature = "error-send")))]
+type ErrorValueInner = Value;
+
+// With `error-send` enabled (and `send` disabled) Lua values are not `Send`, so values referencing
+// Lua objects are kept in the registry instead.
+#[cfg(all(feature = "error-send", not(feature = "send")))]
+#[derive(Debug, Clone)]
+enum ErrorValueInner {
+    Plain(PlainValue),
+    Registry(Arc<RegistryKey>),
+}
+
+// Lua value that does not reference any Lua object
+#[cfg(all(feature = "error-send", not(feature = "send")))]
+#[derive(Debug, Clone)]
+struct PlainValue(Value);
+
+#[cfg(all(feature = "error-send", not(feature = "send")))]
+unsafe impl Send for PlainValue {}
+#[cfg(all(feature = "error-send", not(feature = "send")))]
+unsafe impl Sync for PlainValue {}
+
+impl ErrorValue {
+    #[cfg(any(feature = "send", not(feature = "error-send")))]
+    pub(crate) fn new(value: Value) -> Result<Self> {
+        let type_name = value.type_name();
+        Ok(ErrorValue {
+            type_name,
+            inner: value,
+        })
+    }
+
+    #[cfg(all(feature = "error-send", not(feature = "send")))]
+    pub(crate) fn new(value: Value) -> Result<Self> {
+        let type_name = value.type_name();
+        let vref = match &value {
+            Value::String(s) => &s.0,
+            Value::Table(t) => &t.0,
+            Value::Function(f) => &f.0,
+            Value::Thread(t) => &t.0,
+            Value::UserData(ud) => &ud.0,
+            #[cfg(feature = "luau")]
+            Value::Buffer(buf) => &buf.0,
+            Value::Other(vref) => vref,
+            _ => {
+                let inner = ErrorValueInner::Plain(PlainValue(value));
+                return Ok(ErrorValue { type_name, inner });
+            }
+        };
+        let key = vref.lua.upgrade().create_registry_value(value)?;
+        let inner = ErrorValueInner::Registry(Arc::new(key));
+        Ok(ErrorValue { type_name, inner })
+    }
+
+    /// Returns the type name of the error object.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the error object as a Lua [`Value`].
+    pub fn to_value(&self, lua: &Lua) -> Result<Value> {
+        #[cfg(any(feature = "send", not(feature = "error-send")))]
+        {
+            let _ = lua;
+            Ok(self.inner.clone())
+        }
+        #[cfg(all(feature = "error-send", not(feature = "send")))]
+        match &self.inner {
+            ErrorValueInner::Plain(value) => Ok(value.0.clone()),
+            ErrorValueInner::Registry(key) => lua.registry_value(key),
+        }
+    }
+
+    // Pushes the error object onto the Lua stack.
+    // Uses 3 stack spaces, does not call checkstack.
+    pub(crate) unsafe fn push_into_stack(&self, lua: &RawLua) -> Result<()> {
+        #[cfg(any(feature = "send", not(feature = "error-send")))]
+        return lua.push_value(&self.inner);
+
+        #[cfg(all(feature = "error-send", not(feature = "send")))]
+        match &self.inner {
+            ErrorValueInner::Plain(value) => lua.push_value(&value.0),
+            ErrorValueInner::Registry(key) if lua.owns_registry_value(key) => {
+                ffi::lua_rawgeti(lua.state(), ffi::LUA_REGISTRYINDEX, key.id() as _);
+                Ok(())
+            }
+            ErrorValueInner::Registry(_) => Err(Error::MismatchedRegistryKey),
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::SyntaxError { message, .. } => write!(fmt, "syntax error: {message}"),
             Error::RuntimeError(msg) => write!(fmt, "runtime error: {msg}"),
+            Error::ErrorObject(value) => {
+                write!(fmt, "runtime error: (error object is a {} value)", value.type_name)
+            }
+            Error::BytecodeError(msg) => write!(fmt, "bytecode error: {msg}"),
             Error::MemoryError(msg) => {
                 write!(fmt, "memory error: {msg}")
             }
@@ -268,6 +398,7 @@ impl fmt::Display for Error {
                 }
             }
             Error::CoroutineUnresumable => write!(fmt, "coroutine is non-resumable"),
+            Error::Timeout => write!(fmt, "execution deadline exceeded"),
             Error::UserDataTypeMismatch => write!(fmt, "userdata is not expected type"),
             Error::UserDataDestructed => write!(fmt, "userdata has been destructed"),
             Error::UserDataBorrowError => write!(fmt, "error borrowing userdata"),
@@ -349,6 +480,25 @@ impl Error {
         Error::RuntimeError(message.to_string())
     }
 
+    /// Creates a new [`Error::ErrorObject`] carrying an arbitrary Lua value as the error object.
+    ///
+    /// When returned from a Rust callback, the value is raised as a Lua error (without being
+    /// stringified or wrapped).
+    pub fn with_value(value: Value) -> Self {
+        match ErrorValue::new(value) {
+            Ok(value) => Error::ErrorObject(value),
+            Err(err) => err,
+        }
+    }
+
+    /// Returns the Lua error object carried by [`Error::ErrorObject`].
+    pub fn value(&self) -> Option<&ErrorValue> {
+        match self {
+            Error::ErrorObject(value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Wraps an external error object.
     #[inline]
     pub fn external<T: Into<Box<DynStdError>>>(err: T) -> Self {
@@ -435,6 +585,8 @@ where
 /// Provides the `context` method for [`Error`] and `Result<T, Error>`.
 pub trait ErrorContext: Sealed {
     /// Wraps the error value with additional context.
+    ///
+    /// The original error (including any existing context) is preserved as the cause.
     fn context<C: fmt::Display>(self, context: C) -> Self;
 
     /// Wrap the error value with additional context that is evaluated lazily
@@ -445,23 +597,17 @@ pub trait ErrorContext: Sealed {
 impl ErrorContext for Error {
     fn context<C: fmt::Display>(self, context: C) -> Self {
         let context = context.to_string();
-        match self {
-            Error::WithContext { cause, .. } => Error::WithContext { context, cause },
-            _ => Error::WithContext {
-                context,
-                cause: Arc::new(self),
-            },
+        Error::WithContext {
+            context,
+            cause: Arc::new(self),
         }
     }
 
     fn with_context<C: fmt::Display>(self, f: impl FnOnce(&Error) -> C) -> Self {
         let context = f(&self).to_string();
-        match self {
-            Error::WithContext { cause, .. } => Error::WithContext { context, cause },
-            _ => Error::WithContext {
-                context,
-                cause: Arc::new(self),
-            },
+        Error::WithContext {
+            context,
+            cause: Arc::new(self),
         }
     }
 }
@@ -5,7 +5,7 @@ use std::{mem, ptr, slice};
 use crate::error::{Error, Result};
 use crate::state::Lua;
 use crate::table::Table;
-use crate::traits::{FromLuaMulti, IntoLua, IntoLuaMulti, LuaNativeFn, LuaNativeFnMut};
+use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti, LuaNativeFn, LuaNativeFnMut};
 use crate::types::{Callback, LuaType, MaybeSend, ValueRef};
 use crate::util::{
     assert_stack, check_stack, linenumber_to_usize, pop_error, ptr_to_lossy_str, ptr_to_str, StackGuard,
@@ -20,7 +20,10 @@ use {
 };
 
 /// Handle to an internal Lua function.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Equality and hashing are based on the identity of the underlying Lua function, which allows
+/// using `Function` as a `HashMap` key. The hash is stable only while the function is alive.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Function(pub(crate) ValueRef);
 
 /// Contains information about a function.
@@ -125,6 +128,39 @@ impl Function {
         }
     }
 
+    /// Calls the function, aborting its execution with [`Error::Timeout`] once `deadline` passes.
+    ///
+    /// The current time is checked periodically using a temporary hook (an interrupt for Luau)
+    /// that replaces the one set using [`Lua::set_hook`] for the duration of the call. The previous
+    /// hook is restored when the call returns, so nested calls work as expected.
+    ///
+    /// Time spent inside Rust functions is not interrupted. For LuaJIT, JIT-compiled code does
+    /// not trigger hooks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::{Duration, Instant};
+    /// # use mlua::{Error, Function, Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let spin: Function = lua.load("function() while true do end end").eval()?;
+    /// let deadline = Instant::now() + Duration::from_millis(50);
+    /// assert!(matches!(spin.call_with_deadline::<()>((), deadline), Err(Error::Timeout)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Lua::set_hook`]: crate::Lua::set_hook
+    pub fn call_with_deadline<R: FromLuaMulti>(
+        &self,
+        args: impl IntoLuaMulti,
+        deadline: std::time::Instant,
+    ) -> Result<R> {
+        let lua = self.0.lua.lock();
+        lua.lua().with_deadline(deadline, || self.call(args))
+    }
+
     /// Returns a future that, when polled, calls `self`, passing `args` as function arguments,
     /// and drives the execution.
     ///
@@ -340,6 +376,81 @@ impl Function {
         }
     }
 
+    /// Returns the number of upvalues of the function.
+    ///
+    /// For Rust/C functions this is the number of (unnamed) upvalues they were created with, but
+    /// their values cannot be accessed using [`Function::upvalue`].
+    pub fn num_upvalues(&self) -> usize {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            assert_stack(state, 2);
+
+            lua.push_ref(&self.0);
+            let mut n = 0;
+            while !ffi::lua_getupvalue(state, -1, n + 1).is_null() {
+                ffi::lua_pop(state, 1);
+                n += 1;
+            }
+            n as usize
+        }
+    }
+
+    /// Returns the name and value of the `n`-th upvalue (1-based) of the Lua function.
+    ///
+    /// The name may be empty if debug information is not available (eg. stripped bytecode).
+    /// Returns `None` if `n` is out of range.
+    ///
+    /// This function always returns `None` for Rust/C functions.
+    pub fn upvalue<V: FromLua>(&self, n: usize) -> Result<Option<(String, V)>> {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+
+            lua.push_ref(&self.0);
+            if ffi::lua_iscfunction(state, -1) != 0 {
+                return Ok(None);
+            }
+
+            let n = match c_int::try_from(n) {
+                Ok(n) if n > 0 => n,
+                _ => return Ok(None),
+            };
+            let name = ffi::lua_getupvalue(state, -1, n);
+            if name.is_null() {
+                return Ok(None);
+            }
+            let name = std::ffi::CStr::from_ptr(name).to_string_lossy().into_owned();
+            Ok(Some((name, V::from_stack(-1, &lua)?)))
+        }
+    }
+
+    /// Sets the value of the `n`-th upvalue (1-based) of the Lua function.
+    ///
+    /// Returns an error if `n` is out of range or the function is a Rust/C function.
+    pub fn set_upvalue(&self, n: usize, value: impl IntoLua) -> Result<()> {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+
+            lua.push_ref(&self.0);
+            if ffi::lua_iscfunction(state, -1) != 0 {
+                return Err(Error::runtime("cannot set upvalue of a Rust/C function"));
+            }
+
+            lua.push(value)?;
+            match c_int::try_from(n) {
+                Ok(idx) if idx > 0 && !ffi::lua_setupvalue(state, -2, idx).is_null() => Ok(()),
+                _ => Err(Error::runtime(format!("upvalue index {n} out of range"))),
+            }
+        }
+    }
+
     /// Returns information about the function.
     ///
     /// Corresponds to the `>Sn` what mask for [`lua_getinfo`] when applied to the function.
@@ -384,6 +495,19 @@ impl Function {
         }
     }
 
+    /// Returns `true` if this function is the given C function.
+    ///
+    /// Compares the result of [`lua_tocfunction`] with `func`, so it's `false` for Lua functions
+    /// and for Rust functions created using [`Lua::create_function`].
+    ///
+    /// [`lua_tocfunction`]: https://www.lua.org/manual/5.4/manual.html#lua_tocfunction
+    /// [`Lua::create_function`]: crate::Lua::create_function
+    pub fn is_c_function(&self, func: ffi::lua_CFunction) -> bool {
+        let lua = self.0.lua.lock();
+        let cfunc = unsafe { ffi::lua_tocfunction(lua.ref_thread(), self.0.index) };
+        cfunc.is_some_and(|cfunc| cfunc as *const c_void == func as *const c_void)
+    }
+
     /// Dumps the function as a binary chunk.
     ///
     /// If `strip` is true, the binary representation may not include all debug information
@@ -486,6 +610,15 @@ impl Function {
         self.0.to_pointer()
     }
 
+    /// Returns `true` if both handles refer to the same Lua function.
+    ///
+    /// This is useful for checking whether a stored reference still points to a particular
+    /// function version, eg. when hot-reloading code.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Function) -> bool {
+        self.0 == other.0
+    }
+
     /// Creates a deep clone of the Lua function.
     ///
     /// Copies the function prototype and all its upvalues to the
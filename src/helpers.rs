@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use std::fmt::{self, Write as _};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
+
+use crate::error::Result;
+use crate::multi::Variadic;
+use crate::state::Lua;
+use crate::string::String as LuaString;
+use crate::value::Value;
+
+/// Flags describing the set of helper globals to install using [`Lua::install_helpers`].
+///
+/// Requires `feature = "helpers"`
+#[cfg_attr(docsrs, doc(cfg(feature = "helpers")))]
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct HelperSet(u32);
+
+impl HelperSet {
+    /// `dump(value) -> string`
+    ///
+    /// Returns a pretty-printed representation of `value`, recursively expanding tables.
+    /// Recursive references are printed as `table: 0x...` instead of being expanded again.
+    pub const DUMP: HelperSet = HelperSet(1);
+
+    /// `pretty_print(...)`
+    ///
+    /// Writes a pretty-printed representation of each argument (same format as `dump`) to the
+    /// standard output, one per line.
+    pub const PRETTY_PRINT: HelperSet = HelperSet(1 << 1);
+
+    /// `hexdump(s) -> string`
+    ///
+    /// Returns a canonical hex+ASCII dump of the bytes of string `s`, 16 bytes per line.
+    pub const HEXDUMP: HelperSet = HelperSet(1 << 2);
+
+    /// No helpers
+    pub const NONE: HelperSet = HelperSet(0);
+    /// All helpers
+    pub const ALL: HelperSet = HelperSet(u32::MAX);
+
+    /// Returns `true` if any of the helpers in `set` are also in `self`.
+    pub fn contains(self, set: Self) -> bool {
+        (self & set).0 != 0
+    }
+}
+
+impl BitAnd for HelperSet {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        HelperSet(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for HelperSet {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = HelperSet(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for HelperSet {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        HelperSet(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for HelperSet {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = HelperSet(self.0 | rhs.0)
+    }
+}
+
+pub(crate) fn install(lua: &Lua, set: HelperSet) -> Result<()> {
+    let globals = lua.globals();
+
+    if set.contains(HelperSet::DUMP) {
+        let dump = lua.create_function(|_, value: Value| Ok(format!("{:?}", Pretty(&value))))?;
+        globals.raw_set("dump", dump)?;
+    }
+
+    if set.contains(HelperSet::PRETTY_PRINT) {
+        let pretty_print = lua.create_function(|_, values: Variadic<Value>| {
+            for value in values {
+                println!("{:?}", Pretty(&value));
+            }
+            Ok(())
+        })?;
+        globals.raw_set("pretty_print", pretty_print)?;
+    }
+
+    if set.contains(HelperSet::HEXDUMP) {
+        let hexdump = lua.create_function(|_, s: LuaString| Ok(hexdump(&s.as_bytes())))?;
+        globals.raw_set("hexdump", hexdump)?;
+    }
+
+    Ok(())
+}
+
+/// Formats a value recursively expanding tables, same as `{:#?}` on [`Value`].
+struct Pretty<'a>(&'a Value);
+
+impl fmt::Debug for Pretty<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_pretty(fmt, true, 0, &mut HashSet::new())
+    }
+}
+
+fn hexdump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (i, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(out, "{:08x} ", i * 16);
+        for j in 0..16 {
+            if j == 8 {
+                out.push(' ');
+            }
+            match chunk.get(j) {
+                Some(b) => _ = write!(out, " {b:02x}"),
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str("  |");
+        for &b in chunk {
+            let printable = b.is_ascii_graphic() || b == b' ';
+            out.push(if printable { b as char } else { '.' });
+        }
+        out.push_str("|\n");
+    }
+    out
+}
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::cell::UnsafeCell;
+use std::fmt;
 use std::ops::Deref;
 #[cfg(not(feature = "luau"))]
 use std::ops::{BitOr, BitOrAssign};
@@ -7,7 +8,8 @@ use std::os::raw::c_int;
 
 use ffi::lua_Debug;
 
-use crate::state::RawLua;
+use crate::error::Result;
+use crate::state::{LuaGuard, RawLua, WeakLua};
 use crate::types::ReentrantMutexGuard;
 use crate::util::{linenumber_to_usize, ptr_to_lossy_str, ptr_to_str};
 
@@ -29,6 +31,8 @@ pub struct Debug<'a> {
 
 enum EitherLua<'a> {
     Owned(ReentrantMutexGuard<'a, RawLua>),
+    // Lua state of a (suspended) thread to inspect
+    Thread(LuaGuard, *mut ffi::lua_State),
     #[cfg(not(feature = "luau"))]
     Borrowed(&'a RawLua),
 }
@@ -39,6 +43,7 @@ impl Deref for EitherLua<'_> {
     fn deref(&self) -> &Self::Target {
         match self {
             EitherLua::Owned(guard) => guard,
+            EitherLua::Thread(guard, _) => guard,
             #[cfg(not(feature = "luau"))]
             EitherLua::Borrowed(lua) => lua,
         }
@@ -64,6 +69,29 @@ impl<'a> Debug<'a> {
         }
     }
 
+    // Activation record `ar` must belong to the (non-running) thread `state`.
+    pub(crate) fn new_thread(
+        guard: LuaGuard,
+        state: *mut ffi::lua_State,
+        _level: c_int,
+        ar: lua_Debug,
+    ) -> Self {
+        Debug {
+            lua: EitherLua::Thread(guard, state),
+            ar: ActivationRecord::Owned(UnsafeCell::new(ar)),
+            #[cfg(feature = "luau")]
+            level: _level,
+        }
+    }
+
+    #[inline]
+    fn state(&self) -> *mut ffi::lua_State {
+        match self.lua {
+            EitherLua::Thread(_, state) => state,
+            ref lua => lua.state(),
+        }
+    }
+
     /// Returns the specific event that triggered the hook.
     ///
     /// For [Lua 5.1] [`DebugEvent::TailCall`] is used for return events to indicate a return
@@ -90,12 +118,12 @@ impl<'a> Debug<'a> {
         unsafe {
             #[cfg(not(feature = "luau"))]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), cstr!("n"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), cstr!("n"), self.ar.get()) != 0,
                 "lua_getinfo failed with `n`"
             );
             #[cfg(feature = "luau")]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), self.level, cstr!("n"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), self.level, cstr!("n"), self.ar.get()) != 0,
                 "lua_getinfo failed with `n`"
             );
 
@@ -117,12 +145,12 @@ impl<'a> Debug<'a> {
         unsafe {
             #[cfg(not(feature = "luau"))]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), cstr!("S"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), cstr!("S"), self.ar.get()) != 0,
                 "lua_getinfo failed with `S`"
             );
             #[cfg(feature = "luau")]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), self.level, cstr!("s"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), self.level, cstr!("s"), self.ar.get()) != 0,
                 "lua_getinfo failed with `s`"
             );
 
@@ -147,12 +175,12 @@ impl<'a> Debug<'a> {
         unsafe {
             #[cfg(not(feature = "luau"))]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), cstr!("l"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), cstr!("l"), self.ar.get()) != 0,
                 "lua_getinfo failed with `l`"
             );
             #[cfg(feature = "luau")]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), self.level, cstr!("l"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), self.level, cstr!("l"), self.ar.get()) != 0,
                 "lua_getinfo failed with `l`"
             );
 
@@ -167,7 +195,7 @@ impl<'a> Debug<'a> {
     pub fn is_tail_call(&self) -> bool {
         unsafe {
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), cstr!("t"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), cstr!("t"), self.ar.get()) != 0,
                 "lua_getinfo failed with `t`"
             );
             (*self.ar.get()).currentline != 0
@@ -179,12 +207,12 @@ impl<'a> Debug<'a> {
         unsafe {
             #[cfg(not(feature = "luau"))]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), cstr!("u"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), cstr!("u"), self.ar.get()) != 0,
                 "lua_getinfo failed with `u`"
             );
             #[cfg(feature = "luau")]
             mlua_assert!(
-                ffi::lua_getinfo(self.lua.state(), self.level, cstr!("au"), self.ar.get()) != 0,
+                ffi::lua_getinfo(self.state(), self.level, cstr!("au"), self.ar.get()) != 0,
                 "lua_getinfo failed with `au`"
             );
 
@@ -375,6 +403,21 @@ impl HookTriggers {
             None => 0,
         }
     }
+
+    // Reconstructs `HookTriggers` from the `mask` and `count` values returned by
+    // `lua_gethookmask` and `lua_gethookcount`.
+    pub(crate) const fn from_mask(mask: c_int, count: c_int) -> Self {
+        HookTriggers {
+            on_calls: mask & ffi::LUA_MASKCALL != 0,
+            on_returns: mask & ffi::LUA_MASKRET != 0,
+            every_line: mask & ffi::LUA_MASKLINE != 0,
+            every_nth_instruction: if mask & ffi::LUA_MASKCOUNT != 0 {
+                Some(count as u32)
+            } else {
+                None
+            },
+        }
+    }
 }
 
 #[cfg(not(feature = "luau"))]
@@ -398,3 +441,41 @@ impl BitOrAssign for HookTriggers {
         *self = *self | rhs;
     }
 }
+
+/// A handle that lets long-running Rust functions cooperate with Lua preemption.
+///
+/// Hooks (interrupts for Luau) only fire while Lua code is running, so a Rust function doing heavy
+/// work cannot be stopped by a deadline. Functions created using
+/// [`Lua::create_yielding_function`] receive a `YieldPoint` and should call [`YieldPoint::check`]
+/// periodically.
+///
+/// [`Lua::create_yielding_function`]: crate::Lua::create_yielding_function
+#[derive(Clone)]
+pub struct YieldPoint {
+    lua: WeakLua,
+}
+
+impl YieldPoint {
+    pub(crate) fn new(lua: WeakLua) -> Self {
+        YieldPoint { lua }
+    }
+
+    /// Runs the currently installed instruction count hook (interrupt for Luau), if any.
+    ///
+    /// Returns the error raised by the hook, eg. when a deadline set using [`Lua::set_deadline`]
+    /// has passed. The error should be propagated to unwind the function.
+    ///
+    /// Requesting a yield from the hook has no effect here.
+    ///
+    /// [`Lua::set_deadline`]: crate::Lua::set_deadline
+    pub fn check(&self) -> Result<()> {
+        let lua = self.lua.lock();
+        unsafe { lua.check_yield_point() }
+    }
+}
+
+impl fmt::Debug for YieldPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("YieldPoint").finish()
+    }
+}
@@ -75,11 +75,14 @@ mod chunk;
 mod conversion;
 mod error;
 mod function;
+#[cfg(feature = "helpers")]
+mod helpers;
 mod hook;
 #[cfg(feature = "luau")]
 mod luau;
 mod memory;
 mod multi;
+mod profiler;
 mod scope;
 mod state;
 mod stdlib;
@@ -98,22 +101,25 @@ pub mod prelude;
 pub use bstr::BString;
 pub use ffi::{self, lua_CFunction, lua_State};
 
-pub use crate::chunk::{AsChunk, Chunk, ChunkMode};
-pub use crate::error::{Error, ErrorContext, ExternalError, ExternalResult, Result};
+pub use crate::chunk::{AsChunk, Chunk, ChunkMode, ChunkNameKind, CompiledChunk, ExecStream, ReplLoadResult};
+pub use crate::error::{Error, ErrorContext, ErrorValue, ExternalError, ExternalResult, Result};
 pub use crate::function::{Function, FunctionInfo};
-pub use crate::hook::{Debug, DebugEvent, DebugNames, DebugSource, DebugStack};
-pub use crate::multi::{MultiValue, Variadic};
+pub use crate::hook::{Debug, DebugEvent, DebugNames, DebugSource, DebugStack, YieldPoint};
+pub use crate::memory::AllocatorInfo;
+pub use crate::multi::{FixedArgs, MultiValue, Spread, Variadic};
+pub use crate::profiler::ProfilerHandle;
 pub use crate::scope::Scope;
-pub use crate::state::{GCMode, Lua, LuaOptions};
+pub use crate::state::{GCMode, GcState, Lua, LuaOptions};
 pub use crate::stdlib::StdLib;
 pub use crate::string::{BorrowedBytes, BorrowedStr, String};
-pub use crate::table::{Table, TablePairs, TableSequence};
-pub use crate::thread::{Thread, ThreadStatus};
+pub use crate::table::{SequenceView, Table, TableBuilder, TableDiff, TablePairs, TableSequence};
+pub use crate::thread::{PooledThread, Thread, ThreadPool, ThreadStatus};
 pub use crate::traits::{
-    FromLua, FromLuaMulti, IntoLua, IntoLuaMulti, LuaNativeFn, LuaNativeFnMut, ObjectLike,
+    FromLua, FromLuaMulti, IntoLua, IntoLuaMulti, LuaNativeFn, LuaNativeFnMut, NumericElement, ObjectLike,
 };
 pub use crate::types::{
-    AppDataRef, AppDataRefMut, Either, Integer, LightUserData, MaybeSend, Number, RegistryKey, VmState,
+    AppDataRef, AppDataRefMut, Either, Integer, LightUserData, MaybeSend, Number, RegistryKey, RegistryStats,
+    TypedRegistryKey, VmState,
 };
 pub use crate::userdata::{
     AnyUserData, MetaMethod, UserData, UserDataFields, UserDataMetatable, UserDataMethods, UserDataRef,
@@ -128,6 +134,9 @@ pub use crate::hook::HookTriggers;
 #[cfg_attr(docsrs, doc(cfg(feature = "luau")))]
 pub use crate::{buffer::Buffer, chunk::Compiler, function::CoverageInfo, vector::Vector};
 
+#[cfg(feature = "helpers")]
+pub use crate::helpers::HelperSet;
+
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub use crate::{thread::AsyncThread, traits::LuaNativeAsyncFn};
@@ -199,8 +208,32 @@ pub use mlua_derive::chunk;
 
 /// Derive [`FromLua`] for a Rust type.
 ///
-/// Current implementation generate code that takes [`UserData`] value, borrow it (of the Rust type)
-/// and clone.
+/// By default, the generated code takes [`UserData`] value, borrow it (of the Rust type) and clone.
+///
+/// With the `#[mlua(from_table)]` attribute, a struct with named fields is built from a Lua table
+/// instead: each field is read using [`Table::get`] with the field name as key.
+/// Conversion errors include the name of the failed field.
+///
+/// ```
+/// # use mlua::{FromLua, Lua, Result, Table};
+/// # fn main() -> Result<()> {
+/// #[derive(FromLua)]
+/// #[mlua(from_table)]
+/// struct Config {
+///     name: String,
+///     port: u16,
+///     debug: Option<bool>,
+/// }
+///
+/// let lua = Lua::new();
+/// let table: Table = lua.load("{name = 'server', port = 8080}").eval()?;
+/// let config: Config = table.into_struct()?;
+/// assert_eq!(config.name, "server");
+/// assert_eq!(config.port, 8080);
+/// assert_eq!(config.debug, None);
+/// # Ok(())
+/// # }
+/// ```
 #[cfg(feature = "macros")]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 pub use mlua_derive::FromLua;
@@ -222,6 +255,10 @@ pub use mlua_derive::FromLua;
 ///
 /// Internally in the code above the compiler defines C function `luaopen_my_module`.
 ///
+/// The module value is not required to be a table: the function can return any value that
+/// implements [`IntoLua`] (eg. a [`Function`] or [`AnyUserData`]), which is then returned by
+/// `require`.
+///
 /// You can also pass options to the attribute:
 ///
 /// * name - name of the module, defaults to the name of the function
@@ -263,4 +300,6 @@ pub(crate) mod private {
     impl Sealed for Lua {}
     impl Sealed for Table {}
     impl Sealed for AnyUserData {}
+    impl Sealed for Integer {}
+    impl Sealed for Number {}
 }
@@ -78,6 +78,49 @@ macro_rules! mlua_expect {
     };
 }
 
+/// Builds a [`MultiValue`] from a list of values of possibly different types.
+///
+/// The first argument is a reference to [`Lua`] used to convert the values, followed by `;` and
+/// the values themselves. Each value must implement [`IntoLua`].
+/// Evaluates to `Result<MultiValue>`. The values are evaluated in the enclosing scope, so `?` and
+/// `return` inside them apply to the surrounding function.
+///
+/// # Examples
+///
+/// ```
+/// # use mlua::{multi, Lua, Result};
+/// # fn main() -> Result<()> {
+/// let lua = Lua::new();
+/// let f = lua.create_function(|lua, ()| multi![lua; 1, "two", true])?;
+/// let (a, b, c): (i32, String, bool) = f.call(())?;
+/// assert_eq!((a, b.as_str(), c), (1, "two", true));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MultiValue`]: crate::MultiValue
+/// [`Lua`]: crate::Lua
+/// [`IntoLua`]: crate::IntoLua
+#[macro_export]
+macro_rules! multi {
+    ($lua:expr; $($value:expr),* $(,)?) => {
+        {
+            let lua: &$crate::Lua = $lua;
+            let mut multi = $crate::MultiValue::new();
+            let mut result: $crate::Result<()> = Ok(());
+            $(
+                if result.is_ok() {
+                    match $crate::IntoLua::into_lua($value, lua) {
+                        Ok(value) => multi.push_back(value),
+                        Err(err) => result = Err(err),
+                    }
+                }
+            )*
+            result.map(|()| multi)
+        }
+    };
+}
+
 #[cfg(feature = "module")]
 #[doc(hidden)]
 #[macro_export]
@@ -4,11 +4,26 @@ use std::ptr;
 
 pub(crate) static ALLOCATOR: ffi::lua_Alloc = allocator;
 
+/// Information about the memory allocator managed by mlua.
+///
+/// Returned by [`Lua::allocator_info`].
+///
+/// [`Lua::allocator_info`]: crate::Lua::allocator_info
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocatorInfo {
+    /// Amount of memory (in bytes) currently allocated.
+    pub used_memory: usize,
+    /// Memory limit (in bytes), if set.
+    pub memory_limit: Option<usize>,
+}
+
 #[repr(C)]
 #[derive(Default)]
 pub(crate) struct MemoryState {
     used_memory: isize,
     memory_limit: isize,
+    // High-water mark of `used_memory`
+    peak_memory: isize,
     // Can be set to temporary ignore the memory limit.
     // This is used when calling `lua_pushcfunction` for lua5.1/jit/luau.
     ignore_limit: bool,
@@ -38,6 +53,16 @@ impl MemoryState {
         self.used_memory as usize
     }
 
+    #[inline]
+    pub(crate) fn peak_memory(&self) -> usize {
+        self.peak_memory as usize
+    }
+
+    #[inline]
+    pub(crate) fn reset_peak_memory(&mut self) {
+        self.peak_memory = self.used_memory;
+    }
+
     #[inline]
     pub(crate) fn memory_limit(&self) -> usize {
         self.memory_limit as usize
@@ -123,6 +148,9 @@ unsafe extern "C-unwind" fn allocator(
         return ptr::null_mut();
     }
     mem_state.used_memory += mem_diff;
+    if mem_state.used_memory > mem_state.peak_memory {
+        mem_state.peak_memory = mem_state.used_memory;
+    }
 
     if ptr.is_null() {
         // Allocate new memory
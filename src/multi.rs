@@ -5,7 +5,7 @@ use std::ops::{Deref, DerefMut};
 use std::os::raw::c_int;
 use std::result::Result as StdResult;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::state::{Lua, RawLua};
 use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti};
 use crate::util::check_stack;
@@ -141,9 +141,14 @@ impl MultiValue {
         self.into()
     }
 
+    /// Creates a `MultiValue` by converting each item of the iterator into a Lua value.
+    ///
+    /// See also the [`multi!`] macro to build a `MultiValue` from values of different types.
+    ///
+    /// [`multi!`]: crate::multi
     #[inline]
-    pub(crate) fn from_lua_iter<T: IntoLua>(lua: &Lua, iter: impl IntoIterator<Item = T>) -> Result<Self> {
-        let iter = iter.into_iter();
+    pub fn pack<T: IntoLua>(values: impl IntoIterator<Item = T>, lua: &Lua) -> Result<Self> {
+        let iter = values.into_iter();
         let mut multi_value = MultiValue::with_capacity(iter.size_hint().0);
         for value in iter {
             multi_value.push_back(value.into_lua(lua)?);
@@ -295,7 +300,7 @@ impl<T> IntoIterator for Variadic<T> {
 impl<T: IntoLua> IntoLuaMulti for Variadic<T> {
     #[inline]
     fn into_lua_multi(self, lua: &Lua) -> Result<MultiValue> {
-        MultiValue::from_lua_iter(lua, self)
+        MultiValue::pack(self, lua)
     }
 }
 
@@ -310,6 +315,131 @@ impl<T: FromLua> FromLuaMulti for Variadic<T> {
     }
 }
 
+/// Wraps a vector to return its elements as multiple values instead of a table.
+///
+/// By default, `Vec<T>` is converted to a Lua table (an array). When wrapped in `Spread`, each
+/// element becomes a separate value instead, eg. when returned from a Rust function.
+///
+/// Lua limits the number of values that can be passed at once, so converting a vector with more
+/// than [`Spread::MAX_VALUES`] elements returns an error.
+///
+/// # Examples
+///
+/// ```
+/// # use mlua::{Lua, Result, Spread};
+/// # fn main() -> Result<()> {
+/// # let lua = Lua::new();
+/// let words = lua.create_function(|_, s: String| {
+///     Ok(Spread(s.split_whitespace().map(str::to_string).collect::<Vec<_>>()))
+/// })?;
+/// lua.globals().set("words", words)?;
+/// assert_eq!(lua.load("select('#', words('a b c'))").eval::<i64>()?, 3);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct Spread<T>(pub T);
+
+impl<T> Spread<T> {
+    /// Maximum number of elements returned as separate values.
+    ///
+    /// Leaves some room below the stack limit of C functions in Lua 5.1 and LuaJIT (8000 values).
+    pub const MAX_VALUES: usize = 7900;
+}
+
+impl<T: IntoLua> IntoLuaMulti for Spread<Vec<T>> {
+    fn into_lua_multi(self, lua: &Lua) -> Result<MultiValue> {
+        if self.0.len() > Self::MAX_VALUES {
+            let msg = format!(
+                "too many values to spread ({}, max {})",
+                self.0.len(),
+                Self::MAX_VALUES
+            );
+            return Err(Error::runtime(msg));
+        }
+        MultiValue::pack(self.0, lua)
+    }
+}
+
+/// Wraps function arguments, checking that exactly `N` arguments were passed.
+///
+/// Lua allows calling functions with any number of arguments, silently dropping the excess ones
+/// and filling the missing ones with `nil`. This wrapper returns an error (before converting the
+/// arguments to `T`) if the number of arguments is not `N`.
+///
+/// `T` defaults to [`MultiValue`] and can be any type implementing [`FromLuaMulti`].
+///
+/// # Examples
+///
+/// ```
+/// # use mlua::{FixedArgs, Lua, Result};
+/// # fn main() -> Result<()> {
+/// # let lua = Lua::new();
+/// let add = lua.create_function(|_, FixedArgs((a, b)): FixedArgs<2, (i64, i64)>| Ok(a + b))?;
+/// lua.globals().set("add", add)?;
+/// assert_eq!(lua.load("add(3, 2)").eval::<i64>()?, 5);
+/// assert!(lua.load("add(3, 2, 1)").exec().is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct FixedArgs<const N: usize, T = MultiValue>(pub T);
+
+impl<const N: usize, T> FixedArgs<N, T> {
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    fn check_count(nargs: usize) -> Result<()> {
+        if nargs != N {
+            let s = if N == 1 { "" } else { "s" };
+            return Err(Error::runtime(format!("expected {N} argument{s}, got {nargs}")));
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize, T> Deref for FixedArgs<N, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize, T> DerefMut for FixedArgs<N, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const N: usize, T: FromLuaMulti> FromLuaMulti for FixedArgs<N, T> {
+    #[inline]
+    fn from_lua_multi(values: MultiValue, lua: &Lua) -> Result<Self> {
+        Self::check_count(values.len())?;
+        T::from_lua_multi(values, lua).map(FixedArgs)
+    }
+
+    #[inline]
+    fn from_lua_args(args: MultiValue, i: usize, to: Option<&str>, lua: &Lua) -> Result<Self> {
+        Self::check_count(args.len())?;
+        T::from_lua_args(args, i, to, lua).map(FixedArgs)
+    }
+
+    #[inline]
+    unsafe fn from_stack_multi(nvals: c_int, lua: &RawLua) -> Result<Self> {
+        Self::check_count(nvals as usize)?;
+        T::from_stack_multi(nvals, lua).map(FixedArgs)
+    }
+
+    #[inline]
+    unsafe fn from_stack_args(nargs: c_int, i: usize, to: Option<&str>, lua: &RawLua) -> Result<Self> {
+        Self::check_count(nargs as usize)?;
+        T::from_stack_args(nargs, i, to, lua).map(FixedArgs)
+    }
+}
+
 macro_rules! impl_tuple {
     () => (
         impl IntoLuaMulti for () {
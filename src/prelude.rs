@@ -2,18 +2,23 @@
 
 #[doc(no_inline)]
 pub use crate::{
-    AnyUserData as LuaAnyUserData, Chunk as LuaChunk, Either as LuaEither, Error as LuaError,
-    ErrorContext as LuaErrorContext, ExternalError as LuaExternalError, ExternalResult as LuaExternalResult,
-    FromLua, FromLuaMulti, Function as LuaFunction, FunctionInfo as LuaFunctionInfo, GCMode as LuaGCMode,
-    Integer as LuaInteger, IntoLua, IntoLuaMulti, LightUserData as LuaLightUserData, Lua, LuaNativeFn,
-    LuaNativeFnMut, LuaOptions, MetaMethod as LuaMetaMethod, MultiValue as LuaMultiValue, Nil as LuaNil,
-    Number as LuaNumber, ObjectLike as LuaObjectLike, RegistryKey as LuaRegistryKey, Result as LuaResult,
-    StdLib as LuaStdLib, String as LuaString, Table as LuaTable, TablePairs as LuaTablePairs,
-    TableSequence as LuaTableSequence, Thread as LuaThread, ThreadStatus as LuaThreadStatus,
-    UserData as LuaUserData, UserDataFields as LuaUserDataFields, UserDataMetatable as LuaUserDataMetatable,
+    AllocatorInfo as LuaAllocatorInfo, AnyUserData as LuaAnyUserData, Chunk as LuaChunk,
+    CompiledChunk as LuaCompiledChunk, Either as LuaEither, Error as LuaError,
+    ErrorContext as LuaErrorContext, ErrorValue as LuaErrorValue, ExternalError as LuaExternalError,
+    ExternalResult as LuaExternalResult, FromLua, FromLuaMulti, Function as LuaFunction,
+    FunctionInfo as LuaFunctionInfo, GCMode as LuaGCMode, GcState as LuaGcState, Integer as LuaInteger,
+    IntoLua, IntoLuaMulti, LightUserData as LuaLightUserData, Lua, LuaNativeFn, LuaNativeFnMut, LuaOptions,
+    MetaMethod as LuaMetaMethod, MultiValue as LuaMultiValue, Nil as LuaNil, Number as LuaNumber,
+    NumericElement as LuaNumericElement, ObjectLike as LuaObjectLike, ProfilerHandle as LuaProfilerHandle,
+    RegistryKey as LuaRegistryKey, RegistryStats as LuaRegistryStats, Result as LuaResult,
+    SequenceView as LuaSequenceView, StdLib as LuaStdLib, String as LuaString, Table as LuaTable,
+    TableBuilder as LuaTableBuilder, TableDiff as LuaTableDiff, TablePairs as LuaTablePairs,
+    TableSequence as LuaTableSequence, Thread as LuaThread, ThreadPool as LuaThreadPool,
+    ThreadStatus as LuaThreadStatus, TypedRegistryKey as LuaTypedRegistryKey, UserData as LuaUserData,
+    UserDataFields as LuaUserDataFields, UserDataMetatable as LuaUserDataMetatable,
     UserDataMethods as LuaUserDataMethods, UserDataRef as LuaUserDataRef,
     UserDataRefMut as LuaUserDataRefMut, UserDataRegistry as LuaUserDataRegistry, Value as LuaValue,
-    VmState as LuaVmState,
+    VmState as LuaVmState, YieldPoint as LuaYieldPoint,
 };
 
 #[cfg(not(feature = "luau"))]
@@ -24,6 +29,10 @@ pub use crate::HookTriggers as LuaHookTriggers;
 #[doc(no_inline)]
 pub use crate::{CoverageInfo as LuaCoverageInfo, Vector as LuaVector};
 
+#[cfg(feature = "helpers")]
+#[doc(no_inline)]
+pub use crate::HelperSet as LuaHelperSet;
+
 #[cfg(feature = "async")]
 #[doc(no_inline)]
 pub use crate::{AsyncThread as LuaAsyncThread, LuaNativeAsyncFn};
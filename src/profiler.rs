@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::string::String as StdString;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::error::Result;
+use crate::hook::Debug;
+use crate::state::{Lua, WeakLua};
+use crate::types::VmState;
+
+type Samples = Arc<Mutex<HashMap<StdString, u64>>>;
+
+/// Handle to a sampling profiler started using [`Lua::start_profiler`].
+///
+/// The profiler is stopped when the handle is dropped, restoring the hook that was set before.
+pub struct ProfilerHandle {
+    lua: WeakLua,
+    samples: Samples,
+    hook_id: usize,
+}
+
+impl ProfilerHandle {
+    pub(crate) fn start(lua: &Lua, sample_every: u32) -> Result<Self> {
+        let samples = Samples::default();
+        let samples2 = samples.clone();
+        let saved = unsafe { lua.lock().save_hook()? };
+
+        #[cfg(not(feature = "luau"))]
+        lua.set_hook(
+            crate::HookTriggers::new().every_nth_instruction(sample_every.max(1)),
+            move |_, debug| {
+                *samples2.lock().entry(function_key(&debug)).or_default() += 1;
+                Ok(VmState::Continue)
+            },
+        )?;
+
+        #[cfg(feature = "luau")]
+        {
+            let sample_every = sample_every.max(1);
+            let counter = std::sync::atomic::AtomicU32::new(0);
+            lua.set_interrupt(move |lua| {
+                let n = counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                if n % sample_every == 0 {
+                    if let Some(debug) = lua.inspect_stack(0) {
+                        *samples2.lock().entry(function_key(&debug)).or_default() += 1;
+                    }
+                }
+                Ok(VmState::Continue)
+            });
+        }
+
+        let hook_id = unsafe { lua.lock().save_profiler_hook(saved)? };
+        Ok(ProfilerHandle {
+            lua: lua.weak(),
+            samples,
+            hook_id,
+        })
+    }
+
+    /// Returns a flat profile: the number of samples collected for each function.
+    ///
+    /// Functions are identified by name (if known) and location, eg. `foo (main.lua:12)`.
+    pub fn report(&self) -> HashMap<StdString, u64> {
+        self.samples.lock().clone()
+    }
+
+    /// Discards all collected samples.
+    pub fn reset(&self) {
+        self.samples.lock().clear();
+    }
+}
+
+impl fmt::Debug for ProfilerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProfilerHandle")
+            .field("samples", &self.samples.lock().len())
+            .finish()
+    }
+}
+
+impl Drop for ProfilerHandle {
+    fn drop(&mut self) {
+        if let Some(lua) = self.lua.try_lock() {
+            // Errors cannot be reported from `drop`
+            let _ = unsafe { lua.restore_profiler_hook(self.hook_id) };
+        }
+    }
+}
+
+fn function_key(debug: &Debug) -> StdString {
+    let name = debug.names().name.map(|n| n.into_owned());
+    let name = name.as_deref().unwrap_or("?");
+    let source = debug.source();
+    match (source.what, source.short_src) {
+        ("C", _) | (_, None) => format!("{name} [C]"),
+        (_, Some(src)) => format!("{name} ({src}:{})", source.line_defined.unwrap_or(0)),
+    }
+}
@@ -12,11 +12,25 @@ use crate::util::{
     self, assert_stack, check_stack, get_metatable_ptr, get_userdata, take_userdata, StackGuard,
 };
 
+#[cfg(feature = "async")]
+use {
+    crate::types::{AsyncCallback, ScopedAsyncCallback},
+    futures_util::future::LocalBoxFuture,
+    std::future::{self, Future},
+    std::os::raw::c_int,
+    std::pin::Pin,
+    std::rc::{Rc, Weak},
+    std::task::{Context, Poll},
+};
+
 /// Constructed by the [`Lua::scope`] method, allows temporarily creating Lua userdata and
 /// callbacks that are not required to be `Send` or `'static`.
 ///
 /// See [`Lua::scope`] for more details.
 pub struct Scope<'scope, 'env: 'scope> {
+    // Async callbacks and their pending futures are dropped first (while the lock is held)
+    #[cfg(feature = "async")]
+    async_data: AsyncData,
     lua: LuaGuard,
     // Internal destructors run first, then user destructors (based on the declaration order)
     destructors: Destructors<'env>,
@@ -32,9 +46,33 @@ struct Destructors<'a>(RefCell<Vec<(ValueRef, DestructorCallback<'a>)>>);
 
 struct UserDestructors<'a>(RefCell<Vec<Box<dyn FnOnce() + 'a>>>);
 
+// Lifetimes of the scoped async callbacks and futures are erased, they are dropped on scope drop
+#[cfg(feature = "async")]
+type ScopedAsyncFn<'s> = Rc<dyn Fn(&RawLua, c_int) -> LocalBoxFuture<'s, Result<c_int>> + 's>;
+
+#[cfg(feature = "async")]
+type ScopedFutureSlot = RefCell<Option<LocalBoxFuture<'static, Result<c_int>>>>;
+
+#[cfg(feature = "async")]
+#[derive(Default)]
+struct AsyncData {
+    callbacks: RefCell<Vec<ScopedAsyncFn<'static>>>,
+    futures: Rc<RefCell<Vec<Rc<ScopedFutureSlot>>>>,
+}
+
+// Future returned to Lua by a scoped async function, polls the future owned by the scope
+#[cfg(feature = "async")]
+struct ScopedFuture(Weak<ScopedFutureSlot>);
+
+// The future can only be polled (or dropped) while the Lua lock is held
+#[cfg(feature = "async")]
+unsafe impl Send for ScopedFuture {}
+
 impl<'scope, 'env: 'scope> Scope<'scope, 'env> {
     pub(crate) fn new(lua: LuaGuard) -> Self {
         Scope {
+            #[cfg(feature = "async")]
+            async_data: AsyncData::default(),
             lua,
             destructors: Destructors(RefCell::new(Vec::new())),
             user_destructors: UserDestructors(RefCell::new(Vec::new())),
@@ -77,6 +115,55 @@ impl<'scope, 'env: 'scope> Scope<'scope, 'env> {
         })
     }
 
+    /// Wraps a Rust async function or closure, creating a callable Lua function handle to it.
+    ///
+    /// This is a version of [`Lua::create_async_function`] that creates a callback which expires
+    /// on scope drop, and does not require the function or the returned future to be `Send` or
+    /// `'static`. Futures that are still pending when the scope ends are dropped, and polling
+    /// them afterwards returns [`Error::CallbackDestructed`].
+    ///
+    /// See [`Lua::scope_async`] for more details.
+    ///
+    /// Requires `feature = "async"`
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub fn create_async_function<F, A, FR, R>(&'scope self, func: F) -> Result<Function>
+    where
+        F: Fn(Lua, A) -> FR + 'scope,
+        A: FromLuaMulti,
+        FR: Future<Output = Result<R>> + 'scope,
+        R: IntoLuaMulti,
+    {
+        let func: ScopedAsyncFn<'scope> = Rc::new(move |rawlua, nargs| unsafe {
+            let args = match A::from_stack_args(nargs, 1, None, rawlua) {
+                Ok(args) => args,
+                Err(e) => return Box::pin(future::ready(Err(e))),
+            };
+            let lua = rawlua.lua().clone();
+            let fut = func(lua.clone(), args);
+            Box::pin(async move { fut.await?.push_into_stack_multi(lua.raw_lua()) })
+        });
+        let func = unsafe { mem::transmute::<ScopedAsyncFn<'scope>, ScopedAsyncFn<'static>>(func) };
+        let weak_func = Rc::downgrade(&func);
+        let weak_futures = Rc::downgrade(&self.async_data.futures);
+        self.async_data.callbacks.borrow_mut().push(func);
+
+        let callback: ScopedAsyncCallback = Box::new(move |rawlua, nargs| {
+            let (Some(func), Some(futures)) = (weak_func.upgrade(), weak_futures.upgrade()) else {
+                return Box::pin(future::ready(Err(Error::CallbackDestructed)));
+            };
+            let slot = Rc::new(RefCell::new(Some(func(rawlua, nargs))));
+            let fut = ScopedFuture(Rc::downgrade(&slot));
+            let mut futures = futures.borrow_mut();
+            // Forget about completed futures
+            futures.retain(|slot| slot.try_borrow().map_or(true, |fut| fut.is_some()));
+            futures.push(slot);
+            Box::pin(fut)
+        });
+        let callback = unsafe { mem::transmute::<ScopedAsyncCallback, AsyncCallback>(callback) };
+        self.lua.create_async_callback(callback)
+    }
+
     /// Creates a Lua userdata object from a reference to custom userdata type.
     ///
     /// This is a version of [`Lua::create_userdata`] that creates a userdata which expires on
@@ -325,6 +412,37 @@ impl Drop for Destructors<'_> {
     }
 }
 
+#[cfg(feature = "async")]
+impl Future for ScopedFuture {
+    type Output = Result<c_int>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Some(slot) = self.0.upgrade() else {
+            return Poll::Ready(Err(Error::CallbackDestructed));
+        };
+        let mut slot = slot.borrow_mut();
+        let Some(fut) = slot.as_mut() else {
+            return Poll::Ready(Err(Error::CallbackDestructed));
+        };
+        let result = fut.as_mut().poll(cx);
+        if result.is_ready() {
+            *slot = None;
+        }
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for AsyncData {
+    fn drop(&mut self) {
+        // Drop pending futures first, outside of the `RefCell` borrow
+        let futures = mem::take(&mut *self.futures.borrow_mut());
+        drop(futures);
+        let callbacks = mem::take(&mut *self.callbacks.borrow_mut());
+        drop(callbacks);
+    }
+}
+
 impl Drop for UserDestructors<'_> {
     fn drop(&mut self) {
         let destructors = mem::take(&mut *self.0.borrow_mut());
@@ -49,6 +49,26 @@ pub struct Options {
     ///
     /// Default: **false**
     pub sort_keys: bool,
+
+    /// How an empty table without a metatable is deserialized when the target type does not
+    /// specify a shape (eg. `serde_json::Value`).
+    ///
+    /// Default: **[`EmptyTableEncoding::Object`]**
+    pub encode_empty_tables_as: EmptyTableEncoding,
+}
+
+/// Shape used when deserializing an empty Lua table into an untyped target.
+///
+/// See [`Options::encode_empty_tables_as`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyTableEncoding {
+    /// Deserialize as an empty sequence.
+    Array,
+    /// Deserialize as an empty map.
+    #[default]
+    Object,
+    /// Deserialize as a unit value.
+    Null,
 }
 
 impl Default for Options {
@@ -64,6 +84,7 @@ impl Options {
             deny_unsupported_types: true,
             deny_recursive_tables: true,
             sort_keys: false,
+            encode_empty_tables_as: EmptyTableEncoding::Object,
         }
     }
 
@@ -93,6 +114,15 @@ impl Options {
         self.sort_keys = enabled;
         self
     }
+
+    /// Sets [`encode_empty_tables_as`] option.
+    ///
+    /// [`encode_empty_tables_as`]: #structfield.encode_empty_tables_as
+    #[must_use]
+    pub const fn encode_empty_tables_as(mut self, encoding: EmptyTableEncoding) -> Self {
+        self.encode_empty_tables_as = encoding;
+        self
+    }
 }
 
 impl Deserializer {
@@ -141,6 +171,13 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
                 Err(_) => visitor.visit_bytes(&s.as_bytes()),
             },
             Value::Table(ref t) if t.raw_len() > 0 || t.is_array() => self.deserialize_seq(visitor),
+            Value::Table(ref t) if t.is_empty() && t.metatable().is_none() => {
+                match self.options.encode_empty_tables_as {
+                    EmptyTableEncoding::Array => self.deserialize_seq(visitor),
+                    EmptyTableEncoding::Object => self.deserialize_map(visitor),
+                    EmptyTableEncoding::Null => visitor.visit_unit(),
+                }
+            }
             Value::Table(_) => self.deserialize_map(visitor),
             Value::LightUserData(ud) if ud.0.is_null() => visitor.visit_none(),
             Value::UserData(ud) if ud.is_serializable() => {
@@ -252,6 +289,7 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
                 let len = t.raw_len();
                 let mut deserializer = SeqDeserializer {
                     seq: t.sequence_values(),
+                    remaining: len,
                     options: self.options,
                     visited: self.visited,
                 };
@@ -379,8 +417,10 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     }
 }
 
+// Elements are pulled from the table one at a time, so no intermediate collection is built.
 struct SeqDeserializer<'a> {
     seq: TableSequence<'a, Value>,
+    remaining: usize,
     options: Options,
     visited: Rc<RefCell<FxHashSet<*const c_void>>>,
 }
@@ -395,6 +435,7 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer<'_> {
         loop {
             match self.seq.next() {
                 Some(value) => {
+                    self.remaining = self.remaining.saturating_sub(1);
                     let value = value?;
                     let skip = check_value_for_skip(&value, self.options, &self.visited)
                         .map_err(|err| Error::DeserializeError(err.to_string()))?;
@@ -411,10 +452,8 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer<'_> {
     }
 
     fn size_hint(&self) -> Option<usize> {
-        match self.seq.size_hint() {
-            (lower, Some(upper)) if lower == upper => Some(upper),
-            _ => None,
-        }
+        // Based on the raw length of the table, skipped values can make it an overestimate
+        Some(self.remaining)
     }
 }
 
@@ -254,6 +254,11 @@ static ARRAY_METATABLE_REGISTRY_KEY: u8 = 0;
 pub mod de;
 pub mod ser;
 
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
 #[doc(inline)]
 pub use de::Deserializer;
 #[doc(inline)]
@@ -0,0 +1,55 @@
+use crate::error::{Error, Result};
+use crate::serde::LuaSerdeExt;
+use crate::state::Lua;
+use crate::traits::IntoLua;
+use crate::value::Value;
+
+impl Lua {
+    /// Converts a [`toml::Value`] into a Lua [`Value`].
+    ///
+    /// TOML arrays are converted to sequence tables with the [`array_metatable`] attached, the
+    /// same way as sequences are handled by [`LuaSerdeExt::to_value`].
+    /// Datetimes are converted to strings in RFC 3339 format.
+    ///
+    /// Requires `feature = "toml"`
+    ///
+    /// [`array_metatable`]: LuaSerdeExt::array_metatable
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn value_from_toml(&self, value: &toml::Value) -> Result<Value> {
+        Ok(match value {
+            toml::Value::String(s) => Value::String(self.create_string(s)?),
+            toml::Value::Integer(i) => i.into_lua(self)?,
+            toml::Value::Float(n) => Value::Number(*n),
+            toml::Value::Boolean(b) => Value::Boolean(*b),
+            toml::Value::Datetime(dt) => Value::String(self.create_string(dt.to_string())?),
+            toml::Value::Array(array) => {
+                let table = self.create_table_with_capacity(array.len(), 0)?;
+                for item in array {
+                    table.raw_push(self.value_from_toml(item)?)?;
+                }
+                table.set_metatable(Some(self.array_metatable()));
+                Value::Table(table)
+            }
+            toml::Value::Table(map) => {
+                let table = self.create_table_with_capacity(0, map.len())?;
+                for (key, item) in map {
+                    table.raw_set(key.as_str(), self.value_from_toml(item)?)?;
+                }
+                Value::Table(table)
+            }
+        })
+    }
+
+    /// Converts a Lua [`Value`] into a [`toml::Value`].
+    ///
+    /// Tables with the [`array_metatable`] attached (or sequence-like tables) become TOML arrays,
+    /// consistent with the serde serialization of [`Value`].
+    ///
+    /// Requires `feature = "toml"`
+    ///
+    /// [`array_metatable`]: LuaSerdeExt::array_metatable
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn value_to_toml(&self, value: &Value) -> Result<toml::Value> {
+        toml::Value::try_from(value).map_err(|err| Error::SerializeError(err.to_string()))
+    }
+}
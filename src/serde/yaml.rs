@@ -0,0 +1,64 @@
+use crate::error::{Error, Result};
+use crate::serde::LuaSerdeExt;
+use crate::state::Lua;
+use crate::traits::IntoLua;
+use crate::value::Value;
+
+impl Lua {
+    /// Converts a [`serde_yaml::Value`] into a Lua [`Value`].
+    ///
+    /// YAML sequences are converted to sequence tables with the [`array_metatable`] attached,
+    /// the same way as sequences are handled by [`LuaSerdeExt::to_value`].
+    /// `null` is converted to [`LuaSerdeExt::null`]. Tagged values are converted to strings
+    /// containing their YAML representation (eg. `!Tag value`).
+    ///
+    /// Requires `feature = "yaml"`
+    ///
+    /// [`array_metatable`]: LuaSerdeExt::array_metatable
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn value_from_yaml(&self, value: &serde_yaml::Value) -> Result<Value> {
+        Ok(match value {
+            serde_yaml::Value::Null => self.null(),
+            serde_yaml::Value::Bool(b) => Value::Boolean(*b),
+            serde_yaml::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => i.into_lua(self)?,
+                (None, Some(u)) => u.into_lua(self)?,
+                _ => Value::Number(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_yaml::Value::String(s) => Value::String(self.create_string(s)?),
+            serde_yaml::Value::Sequence(seq) => {
+                let table = self.create_table_with_capacity(seq.len(), 0)?;
+                for item in seq {
+                    table.raw_push(self.value_from_yaml(item)?)?;
+                }
+                table.set_metatable(Some(self.array_metatable()));
+                Value::Table(table)
+            }
+            serde_yaml::Value::Mapping(map) => {
+                let table = self.create_table_with_capacity(0, map.len())?;
+                for (key, item) in map {
+                    table.raw_set(self.value_from_yaml(key)?, self.value_from_yaml(item)?)?;
+                }
+                Value::Table(table)
+            }
+            serde_yaml::Value::Tagged(tagged) => {
+                let s =
+                    serde_yaml::to_string(tagged).map_err(|err| Error::SerializeError(err.to_string()))?;
+                Value::String(self.create_string(s.trim_end())?)
+            }
+        })
+    }
+
+    /// Converts a Lua [`Value`] into a [`serde_yaml::Value`].
+    ///
+    /// Tables with the [`array_metatable`] attached (or sequence-like tables) become YAML
+    /// sequences, consistent with the serde serialization of [`Value`].
+    ///
+    /// Requires `feature = "yaml"`
+    ///
+    /// [`array_metatable`]: LuaSerdeExt::array_metatable
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn value_to_yaml(&self, value: &Value) -> Result<serde_yaml::Value> {
+        serde_yaml::to_value(value).map_err(|err| Error::SerializeError(err.to_string()))
+    }
+}
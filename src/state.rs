@@ -7,21 +7,22 @@ use std::panic::Location;
 use std::result::Result as StdResult;
 use std::{fmt, mem, ptr};
 
-use crate::chunk::{AsChunk, Chunk};
-use crate::error::{Error, Result};
+use crate::chunk::{AsChunk, Chunk, ExecStream, ReplLoadResult};
+use crate::error::{Error, ErrorContext, Result};
 use crate::function::Function;
-use crate::hook::Debug;
-use crate::memory::MemoryState;
+use crate::hook::{Debug, YieldPoint};
+use crate::memory::{AllocatorInfo, MemoryState};
 use crate::multi::MultiValue;
+use crate::profiler::ProfilerHandle;
 use crate::scope::Scope;
 use crate::stdlib::StdLib;
 use crate::string::String;
-use crate::table::Table;
-use crate::thread::Thread;
-use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti};
+use crate::table::{Table, TableBuilder};
+use crate::thread::{Thread, ThreadPool};
+use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti, NumericElement};
 use crate::types::{
     AppDataRef, AppDataRefMut, ArcReentrantMutexGuard, Integer, LuaType, MaybeSend, Number, ReentrantMutex,
-    ReentrantMutexGuard, RegistryKey, VmState, XRc, XWeak,
+    ReentrantMutexGuard, RegistryKey, RegistryStats, TypedRegistryKey, VmState, XRc, XWeak,
 };
 use crate::userdata::{AnyUserData, UserData, UserDataProxy, UserDataRegistry, UserDataStorage};
 use crate::util::{
@@ -38,6 +39,8 @@ use crate::{buffer::Buffer, chunk::Compiler};
 #[cfg(feature = "async")]
 use {
     crate::types::LightUserData,
+    futures_util::future::LocalBoxFuture,
+    futures_util::stream::{Stream, StreamExt},
     std::future::{self, Future},
 };
 
@@ -78,6 +81,23 @@ pub enum GCMode {
     Generational,
 }
 
+/// A snapshot of the garbage collector state, returned by [`Lua::gc_state`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GcState {
+    /// Whether the collector is running (not stopped).
+    ///
+    /// Always `None` for Lua 5.1 and LuaJIT, where this information is not available.
+    pub is_running: Option<bool>,
+    /// Current collector mode.
+    pub mode: GCMode,
+    /// Amount of memory (in bytes) currently used inside the Lua state.
+    pub used_memory: usize,
+    /// Number of completed collection cycles observed using [`Lua::gc_collect`] or
+    /// [`Lua::gc_step`].
+    pub cycles: u64,
+}
+
 /// Controls Lua interpreter behavior such as Rust panics handling.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -104,6 +124,19 @@ pub struct LuaOptions {
     #[cfg(feature = "async")]
     #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
     pub thread_pool_size: usize,
+
+    /// Allow loading precompiled (binary) chunks.
+    ///
+    /// If disabled, all chunks are loaded in [`ChunkMode::Text`] mode regardless of the requested
+    /// mode, and any attempt to load bytecode results in a syntax error.
+    /// Loading untrusted bytecode is unsafe as malformed bytecode can crash the VM.
+    ///
+    /// Default: **true**
+    ///
+    /// [`ChunkMode::Text`]: crate::ChunkMode::Text
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub allow_bytecode: bool,
 }
 
 impl Default for LuaOptions {
@@ -119,6 +152,8 @@ impl LuaOptions {
             catch_rust_panics: true,
             #[cfg(feature = "async")]
             thread_pool_size: 0,
+            #[cfg(not(feature = "luau"))]
+            allow_bytecode: true,
         }
     }
 
@@ -141,6 +176,17 @@ impl LuaOptions {
         self.thread_pool_size = size;
         self
     }
+
+    /// Sets [`allow_bytecode`] option.
+    ///
+    /// [`allow_bytecode`]: #structfield.allow_bytecode
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    #[must_use]
+    pub const fn allow_bytecode(mut self, enabled: bool) -> Self {
+        self.allow_bytecode = enabled;
+        self
+    }
 }
 
 impl Drop for Lua {
@@ -319,6 +365,94 @@ impl Lua {
         R::from_stack_multi(nresults, &lua)
     }
 
+    /// Ensures that the current Lua stack has space for at least `n` extra slots.
+    ///
+    /// This is intended to be called before a sequence of manual pushes in [`Lua::exec_raw`] or
+    /// inside a raw [`lua_CFunction`], as Lua does not check for stack overflow on push.
+    /// Returns [`Error::StackError`] if the stack cannot grow to that size.
+    ///
+    /// [`lua_CFunction`]: crate::lua_CFunction
+    pub fn reserve_stack(&self, n: c_int) -> Result<()> {
+        if n <= 0 {
+            return Ok(());
+        }
+        let lua = self.lock();
+        unsafe { check_stack(lua.state(), n) }
+    }
+
+    /// Reads the value at the given stack index as an [`Integer`], in a manner consistent with
+    /// [`Lua::coerce_integer`].
+    ///
+    /// Operates on the stack of the current thread, so it is intended to be used together with
+    /// [`Lua::exec_raw`] or inside a raw [`lua_CFunction`].
+    /// Returns `None` if the value cannot be converted.
+    ///
+    /// # Safety
+    ///
+    /// `idx` must be a valid (or acceptable) index of the current Lua stack.
+    ///
+    /// [`lua_CFunction`]: crate::lua_CFunction
+    pub unsafe fn stack_to_integer(&self, idx: c_int) -> Option<Integer> {
+        let lua = self.lock();
+        let mut isint = 0;
+        let i = ffi::lua_tointegerx(lua.state(), idx, &mut isint);
+        if isint == 0 {
+            None
+        } else {
+            Some(i)
+        }
+    }
+
+    /// Reads the value at the given stack index as a [`Number`], in a manner consistent with
+    /// [`Lua::coerce_number`].
+    ///
+    /// See [`Lua::stack_to_integer`] for more details.
+    ///
+    /// # Safety
+    ///
+    /// `idx` must be a valid (or acceptable) index of the current Lua stack.
+    pub unsafe fn stack_to_number(&self, idx: c_int) -> Option<Number> {
+        let lua = self.lock();
+        let mut isnum = 0;
+        let n = ffi::lua_tonumberx(lua.state(), idx, &mut isnum);
+        if isnum == 0 {
+            None
+        } else {
+            Some(n)
+        }
+    }
+
+    /// Reads the value at the given stack index as a [`String`], in a manner consistent with
+    /// [`Lua::coerce_string`].
+    ///
+    /// Unlike `lua_tolstring`, the value on the stack is never modified (numbers are converted on
+    /// a copy). See [`Lua::stack_to_integer`] for more details.
+    ///
+    /// # Safety
+    ///
+    /// `idx` must be a valid (or acceptable) index of the current Lua stack.
+    pub unsafe fn stack_to_string(&self, idx: c_int) -> Result<Option<String>> {
+        let lua = self.lock();
+        let state = lua.state();
+        let idx = ffi::lua_absindex(state, idx);
+        let _sg = StackGuard::new(state);
+        check_stack(state, 4)?;
+
+        ffi::lua_pushvalue(state, idx);
+        let res = if lua.unlikely_memory_error() {
+            ffi::lua_tolstring(state, -1, ptr::null_mut())
+        } else {
+            protect_lua!(state, 1, 1, |state| {
+                ffi::lua_tolstring(state, -1, ptr::null_mut())
+            })?
+        };
+        if !res.is_null() {
+            Ok(Some(String(lua.pop_ref())))
+        } else {
+            Ok(None)
+        }
+    }
+
     #[doc(hidden)]
     #[deprecated(since = "0.10.0", note = "please use `load_std_libs` instead")]
     pub fn load_from_std_lib(&self, libs: StdLib) -> Result<()> {
@@ -332,6 +466,50 @@ impl Lua {
         unsafe { self.lock().load_std_libs(libs) }
     }
 
+    /// Loads a standard library by its name (eg. `"table"`, `"string"`, `"os"`) into an existing
+    /// Lua state.
+    ///
+    /// This is useful when the set of libraries to load is data-driven rather than known at
+    /// compile time. Returns an error if the library is unknown or not available in the current
+    /// Lua backend. The same safety restrictions as in [`Lua::load_std_libs`] apply.
+    pub fn open_library_by_name(&self, name: &str) -> Result<()> {
+        match StdLib::from_name(name) {
+            Some(lib) => self.load_std_libs(lib),
+            None => Err(Error::runtime(format!("unknown standard library '{name}'"))),
+        }
+    }
+
+    /// Opens a single standard library and returns its table without setting a global.
+    ///
+    /// This calls `luaL_requiref` with `glb = 0`, so the library is not exposed to scripts unless
+    /// you do so explicitly. It allows building restricted environments with only a subset of a
+    /// library's functions. The library is still registered in `package.loaded` (if present).
+    ///
+    /// The returned table is always a new (shallow) copy of the library table, so modifying it
+    /// does not affect the global library or `package.loaded`, even if the library is already
+    /// loaded. As no global is set, the library is not added to the set of loaded libraries
+    /// tracked by this Lua instance. For the same reason, in safe mode the `package` library can
+    /// be opened this way only if it is already loaded (with C modules disabled).
+    ///
+    /// `lib` must be a single library flag. The same safety restrictions as in
+    /// [`Lua::load_std_libs`] apply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result, StdLib};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new_with(StdLib::NONE, Default::default())?;
+    /// let os = lua.open_library_into(StdLib::OS)?;
+    /// lua.globals().set("clock", os.get::<mlua::Function>("clock")?)?;
+    /// assert!(lua.globals().get::<Option<mlua::Table>>("os")?.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_library_into(&self, lib: StdLib) -> Result<Table> {
+        unsafe { self.lock().open_library_into(lib) }
+    }
+
     /// Loads module `modname` into an existing Lua state using the specified entrypoint
     /// function.
     ///
@@ -377,6 +555,50 @@ impl Lua {
         T::from_lua(value, self)
     }
 
+    /// Builds module `modname` using the `loader` function, running it in a fresh environment.
+    ///
+    /// Creates a new environment table for the module, which falls back to the global
+    /// environment for reads (using the `__index` metamethod) and has the `_NAME` field set to
+    /// `modname` and `_M` set to the environment itself. If `loader` is a Lua function, the table
+    /// is set as its environment. Then `loader` is called with `modname` and the environment
+    /// table as arguments, and the result is stored to `package.loaded[modname]`.
+    ///
+    /// If `loader` returns `nil`, the environment table becomes the module value (similar to the
+    /// `module` function in Lua 5.1). If `package.loaded[modname]` value is not nil, returns
+    /// it without calling the loader.
+    pub fn build_module(&self, modname: &str, loader: Function) -> Result<Value> {
+        let loaded = unsafe {
+            let lua = self.lock();
+            let state = lua.state();
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+            protect_lua!(state, 0, 1, fn(state) {
+                ffi::luaL_getsubtable(state, ffi::LUA_REGISTRYINDEX, cstr!("_LOADED"));
+            })?;
+            Table(lua.pop_ref())
+        };
+
+        let modname = self.create_string(modname)?;
+        match loaded.raw_get(&modname)? {
+            Value::Nil => {}
+            value => return Ok(value),
+        }
+
+        let env = self.create_table()?;
+        env.raw_set("_NAME", &modname)?;
+        env.raw_set("_M", &env)?;
+        let meta = self.create_table_from([("__index", self.globals())])?;
+        env.set_metatable(Some(meta));
+        loader.set_environment(env.clone())?;
+
+        let result = match loader.call((&modname, &env))? {
+            Value::Nil => Value::Table(env),
+            res => res,
+        };
+        loaded.raw_set(modname, &result)?;
+        Ok(result)
+    }
+
     /// Unloads module `modname`.
     ///
     /// Removes module from the [`package.loaded`] table which allows to load it again.
@@ -399,6 +621,179 @@ impl Lua {
         loaded.raw_set(modname, Nil)
     }
 
+    /// Reloads module `modname`.
+    ///
+    /// Removes module from the [`package.loaded`] table and loads it again using the Lua
+    /// [`require`] function, returning the new module value.
+    ///
+    /// If loading fails, the previously loaded module value is kept in [`package.loaded`].
+    ///
+    /// Returns an error if the module is provided by a binary (C) library, found using the C
+    /// searchers (the third and fourth entries of [`package.searchers`]), since such modules are
+    /// internally cached and cannot be reloaded. Loaders returned by other searchers (including
+    /// Rust functions) are allowed.
+    ///
+    /// [`package.loaded`]: https://www.lua.org/manual/5.4/manual.html#pdf-package.loaded
+    /// [`package.searchers`]: https://www.lua.org/manual/5.4/manual.html#pdf-package.searchers
+    /// [`require`]: https://www.lua.org/manual/5.4/manual.html#pdf-require
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub fn reload_module(&self, modname: &str) -> Result<Value> {
+        let loaded = unsafe {
+            let lua = self.lock();
+            let state = lua.state();
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+            protect_lua!(state, 0, 1, fn(state) {
+                ffi::luaL_getsubtable(state, ffi::LUA_REGISTRYINDEX, cstr!("_LOADED"));
+            })?;
+            Table(lua.pop_ref())
+        };
+
+        #[cfg(any(feature = "lua51", feature = "luajit"))]
+        const SEARCHERS: &str = "loaders";
+        #[cfg(not(any(feature = "lua51", feature = "luajit")))]
+        const SEARCHERS: &str = "searchers";
+
+        // Temporarily replace the module searchers with a single one that runs the original
+        // searchers (the same way as `require` does) and rejects loaders from binary libraries
+        let package = loaded.raw_get::<Table>("package")?;
+        let searchers = package.raw_get::<Table>(SEARCHERS)?;
+        let original = searchers
+            .sequence_values::<Function>()
+            .collect::<Result<Vec<_>>>()?;
+        // C modules cannot be loaded in safe mode, and the C searchers are replaced
+        let safe = unsafe { (*self.lock().extra.get()).safe };
+        let checked_searcher = self.create_function(move |lua, name: String| {
+            let mut not_found = Vec::new();
+            for (i, searcher) in original.iter().enumerate() {
+                match searcher.call::<(Value, Value)>(&name)? {
+                    // The third and fourth searchers look for a loader in C libraries
+                    (Value::Function(loader), _)
+                        if !safe && matches!(i, 2 | 3) && loader.info().what == "C" =>
+                    {
+                        let msg = format!("cannot reload module '{}' from a binary library", name.display());
+                        return Err(Error::runtime(msg));
+                    }
+                    (loader @ Value::Function(_), data) => return Ok((loader, data)),
+                    (Value::String(msg), _) => not_found.push(msg.to_string_lossy()),
+                    _ => {}
+                }
+            }
+            // Lua 5.4 `require` prefixes each message itself
+            #[cfg(feature = "lua54")]
+            let msg = not_found.join("\n\t");
+            #[cfg(not(feature = "lua54"))]
+            let msg = not_found.concat();
+            Ok((Value::String(lua.create_string(msg)?), Nil))
+        })?;
+        package.raw_set(SEARCHERS, [checked_searcher])?;
+
+        let prev_module = loaded.raw_get::<Value>(modname)?;
+        let result = (|| {
+            loaded.raw_set(modname, Nil)?;
+            let require = self.globals().get::<Function>("require")?;
+            require.call(modname)
+        })();
+        package.raw_set(SEARCHERS, searchers)?;
+        if result.is_err() {
+            loaded.raw_set(modname, prev_module)?;
+        }
+        result
+    }
+
+    /// Enables resolving `require` module names relative to the calling chunk.
+    ///
+    /// Wraps the global `require` function to handle module names starting with `./` or `../`.
+    /// Such names are resolved relative to the directory of the Lua file that calls `require`
+    /// (the chunk name must be in the `@path/to/file.lua` form, as set when loading a [`Path`]).
+    /// For `require("./sibling")` the files `sibling.lua` and `sibling/init.lua` in that directory
+    /// are tried. Other module names are passed to the original `require` function as is.
+    ///
+    /// On LuaJIT, `require` must not be tail called (eg. `return require("./mod")`), as the
+    /// calling chunk is not on the stack anymore in this case.
+    ///
+    /// Relative modules are cached in [`package.loaded`] by their resolved (lexically normalized)
+    /// file path, so the same relative name required from different directories refers to
+    /// different modules, and different names referring to the same file load it only once.
+    ///
+    /// Calling this method more than once has no effect.
+    ///
+    /// Requires `feature = "lua54/lua53/lua52/lua51/luajit"`
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`package.loaded`]: https://www.lua.org/manual/5.4/manual.html#pdf-package.loaded
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub fn enable_relative_require(&self) -> Result<()> {
+        use std::ffi::OsString;
+        use std::path::{Component, Path, PathBuf};
+        use std::string::String as StdString;
+
+        const REGISTRY_KEY: &str = "__mlua_relative_require";
+        if self.named_registry_value::<bool>(REGISTRY_KEY)? {
+            return Ok(());
+        }
+
+        // Lexically normalizes the path, keeping leading `..` components
+        fn normalize(path: &Path) -> PathBuf {
+            let mut normalized = PathBuf::new();
+            for component in path.components() {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir if normalized.file_name().is_some() => _ = normalized.pop(),
+                    component => normalized.push(component),
+                }
+            }
+            normalized
+        }
+
+        let require: Function = self.globals().get("require")?;
+        let relative_require = self.create_function(move |lua, name: String| {
+            let name = name.to_str()?;
+            if !(name.starts_with("./") || name.starts_with("../")) {
+                return require.call::<MultiValue>(&*name);
+            }
+
+            // Find the closest Lua function on the stack (the one that called `require`)
+            let source = (1..)
+                .map_while(|level| lua.inspect_stack(level))
+                .find_map(|debug| {
+                    let source = debug.source();
+                    (source.what != "C").then(|| source.source.map(|s| s.into_owned()))
+                })
+                .flatten();
+            let Some(path) = source.as_deref().and_then(|s| s.strip_prefix('@')) else {
+                let msg = format!("cannot resolve relative module '{name}' outside of a Lua file");
+                return Err(Error::runtime(msg));
+            };
+
+            let base = normalize(&Path::new(path).parent().unwrap_or(Path::new("")).join(&*name));
+            let mut file = OsString::from(base.as_os_str());
+            file.push(".lua");
+            let mut not_found = StdString::new();
+            for path in [PathBuf::from(file), base.join("init.lua")] {
+                if path.is_file() {
+                    // Load the module through `package.preload` to cache it by the resolved path
+                    let key = path.display().to_string();
+                    let package: Table = lua.globals().get("package")?;
+                    let preload: Table = package.get("preload")?;
+                    if package.get::<Table>("loaded")?.raw_get::<Value>(&*key)?.is_nil() {
+                        preload.raw_set(&*key, lua.load(path.as_path()).into_function()?)?;
+                    }
+                    let result = require.call::<MultiValue>(&*key);
+                    preload.raw_set(&*key, Nil)?;
+                    return result;
+                }
+                not_found.push_str(&format!("\n\tno file '{}'", path.display()));
+            }
+            Err(Error::runtime(format!("module '{name}' not found:{not_found}")))
+        })?;
+
+        self.globals().set("require", relative_require)?;
+        self.set_named_registry_value(REGISTRY_KEY, true)
+    }
+
     // Executes module entrypoint function, which returns only one Value.
     // The returned value then pushed onto the stack.
     #[doc(hidden)]
@@ -496,6 +891,7 @@ impl Lua {
                     }
                 })?;
                 (*lua.extra.get()).sandboxed = enabled;
+                lua.refresh_globals();
             }
             Ok(())
         }
@@ -517,6 +913,8 @@ impl Lua {
     ///
     /// Please note you cannot have more than one hook function set at a time for this Lua instance.
     ///
+    /// Returns an error if the hook callback cannot be stored (eg. on memory allocation failure).
+    ///
     /// # Example
     ///
     /// Shows each line number of code being executed by the Lua interpreter.
@@ -528,7 +926,7 @@ impl Lua {
     /// lua.set_hook(HookTriggers::EVERY_LINE, |_lua, debug| {
     ///     println!("line {}", debug.curr_line());
     ///     Ok(VmState::Continue)
-    /// });
+    /// })?;
     ///
     /// lua.load(r#"
     ///     local x = 2 + 3
@@ -541,16 +939,19 @@ impl Lua {
     /// [`HookTriggers.every_nth_instruction`]: crate::HookTriggers::every_nth_instruction
     #[cfg(not(feature = "luau"))]
     #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
-    pub fn set_hook<F>(&self, triggers: HookTriggers, callback: F)
+    pub fn set_hook<F>(&self, triggers: HookTriggers, callback: F) -> Result<()>
     where
         F: Fn(&Lua, Debug) -> Result<VmState> + MaybeSend + 'static,
     {
         let lua = self.lock();
-        unsafe { lua.set_thread_hook(lua.state(), triggers, callback) };
+        unsafe { lua.set_thread_hook(lua.state(), triggers, callback) }
     }
 
     /// Removes any hook previously set by [`Lua::set_hook`] or [`Thread::set_hook`].
     ///
+    /// Hooks set for all threads are removed. Use [`Thread::remove_hook`] to remove the hook of a
+    /// single thread.
+    ///
     /// This function has no effect if a hook was not previously set.
     #[cfg(not(feature = "luau"))]
     #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
@@ -566,8 +967,178 @@ impl Lua {
                 }
                 _ => {}
             };
-            (*lua.extra.get()).hook_callback = None;
-            (*lua.extra.get()).hook_thread = ptr::null_mut();
+            lua.clear_thread_hook_callbacks();
+        }
+    }
+
+    /// Starts a sampling profiler.
+    ///
+    /// Installs a hook that samples the currently running function every `sample_every` VM
+    /// instructions, accumulating the number of samples per function. Use
+    /// [`ProfilerHandle::report`] to get the collected profile.
+    /// The profiler is stopped when the returned handle is dropped.
+    ///
+    /// The profiler replaces any hook previously set using [`Lua::set_hook`] (for Luau, the
+    /// interrupt set using [`Lua::set_interrupt`], and `sample_every` counts interrupts instead of
+    /// instructions) until it's stopped, unless another hook has replaced the profiler meanwhile.
+    /// Similar to hooks, only the current thread is sampled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let profiler = lua.start_profiler(100)?;
+    /// lua.load("function busy() for i = 1, 10000 do end end; busy()").exec()?;
+    /// let report = profiler.report();
+    /// assert!(report.keys().any(|f| f.starts_with("busy")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn start_profiler(&self, sample_every: u32) -> Result<ProfilerHandle> {
+        ProfilerHandle::start(self, sample_every)
+    }
+
+    /// Sets a wall-clock deadline for Lua code execution.
+    ///
+    /// Once the deadline passes, any running Lua code is aborted with a runtime error
+    /// `deadline exceeded`. The current time is checked periodically (every 1000 VM instructions,
+    /// or on every interrupt for Luau), so execution can slightly overrun the deadline.
+    /// Time spent inside Rust functions is not interrupted. For LuaJIT, JIT-compiled code does not
+    /// trigger hooks.
+    ///
+    /// The deadline replaces any hook previously set using [`Lua::set_hook`] (for Luau, the
+    /// interrupt set using [`Lua::set_interrupt`]) until it's removed using
+    /// [`Lua::clear_deadline`], which restores the previous hook. Similar to hooks, only the
+    /// current thread is checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::{Duration, Instant};
+    /// # use mlua::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// lua.set_deadline(Instant::now() + Duration::from_millis(50))?;
+    /// let err = lua.load("while true do end").exec().unwrap_err();
+    /// assert!(err.to_string().contains("deadline exceeded"));
+    /// lua.clear_deadline()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_deadline(&self, at: std::time::Instant) -> Result<()> {
+        let lua = self.lock();
+        unsafe {
+            // Keep the hook set before the first deadline
+            let extra = lua.extra.get();
+            if (*extra).deadline_saved_hook.is_none() {
+                (*extra).deadline_saved_hook = Some(lua.save_hook()?);
+            }
+        }
+
+        let check = move |_: &Lua| {
+            if std::time::Instant::now() >= at {
+                return Err(Error::runtime("deadline exceeded"));
+            }
+            Ok(VmState::Continue)
+        };
+
+        #[cfg(not(feature = "luau"))]
+        unsafe {
+            let triggers = HookTriggers::new().every_nth_instruction(1000);
+            lua.set_thread_hook(lua.state(), triggers, move |lua, _| check(lua))?;
+        }
+        #[cfg(feature = "luau")]
+        self.set_interrupt(check);
+        Ok(())
+    }
+
+    /// Runs `f` with a temporary deadline, restoring the previously set hook (interrupt for Luau)
+    /// afterwards.
+    ///
+    /// Once the deadline passes, running Lua code is aborted with [`Error::Timeout`].
+    pub(crate) fn with_deadline<R>(
+        &self,
+        deadline: std::time::Instant,
+        f: impl FnOnce() -> Result<R>,
+    ) -> Result<R> {
+        let check = move |_: &Lua| {
+            if std::time::Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+            Ok(VmState::Continue)
+        };
+
+        // Restores the previous hook, even if `f` panics
+        struct HookGuard<'a>(&'a RawLua, Option<raw::SavedHook>);
+
+        impl Drop for HookGuard<'_> {
+            fn drop(&mut self) {
+                if let Some(saved) = self.1.take() {
+                    // Errors cannot be reported while unwinding
+                    let _ = unsafe { self.0.restore_hook(saved) };
+                }
+            }
+        }
+
+        let lua = self.lock();
+        let mut guard = HookGuard(&lua, Some(unsafe { lua.save_hook()? }));
+
+        #[cfg(not(feature = "luau"))]
+        unsafe {
+            let triggers = HookTriggers::new().every_nth_instruction(1000);
+            lua.set_thread_hook(lua.state(), triggers, move |lua, _| check(lua))?;
+        }
+        #[cfg(feature = "luau")]
+        self.set_interrupt(check);
+
+        let result = f();
+        if let Some(saved) = guard.1.take() {
+            unsafe { lua.restore_hook(saved)? };
+        }
+
+        // Report timeout raised by the hook directly
+        match result {
+            Err(Error::CallbackError { ref cause, .. }) if matches!(cause.as_ref(), Error::Timeout) => {
+                Err(Error::Timeout)
+            }
+            result => result,
+        }
+    }
+
+    /// Removes the deadline previously set using [`Lua::set_deadline`].
+    ///
+    /// The hook (interrupt for Luau) that was set before the deadline is restored.
+    /// This function has no effect if a deadline was not previously set.
+    pub fn clear_deadline(&self) -> Result<()> {
+        let lua = self.lock();
+        unsafe {
+            if let Some(saved) = (*lua.extra.get()).deadline_saved_hook.take() {
+                lua.restore_hook(saved)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the triggers of the hook function currently set for the current thread of this Lua
+    /// instance.
+    ///
+    /// Reads the hook mask and count using [`lua_gethookmask`] and [`lua_gethookcount`].
+    /// Returns `None` if no hook is set.
+    ///
+    /// [`lua_gethookmask`]: https://www.lua.org/manual/5.4/manual.html#lua_gethookmask
+    /// [`lua_gethookcount`]: https://www.lua.org/manual/5.4/manual.html#lua_gethookcount
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub fn hook_info(&self) -> Option<HookTriggers> {
+        let lua = self.lock();
+        unsafe {
+            let state = lua.state();
+            match ffi::lua_gethookmask(state) {
+                0 => None,
+                mask => Some(HookTriggers::from_mask(mask, ffi::lua_gethookcount(state))),
+            }
         }
     }
 
@@ -715,6 +1286,122 @@ impl Lua {
         }
     }
 
+    /// Sets a handler for warnings emitted by Lua scripts, on any Lua version.
+    ///
+    /// On Lua 5.4 this is equivalent to [`Lua::set_warning_function`]. On other versions, which
+    /// have no native warning system, a global `warn` function is installed (replacing any
+    /// existing one) that passes each of its arguments to the handler, the same way as Lua 5.4
+    /// `warn` does: all but the last piece have the `incomplete` flag set.
+    pub fn set_warn_handler<F>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(&Lua, &str, bool) -> Result<()> + MaybeSend + 'static,
+    {
+        #[cfg(feature = "lua54")]
+        self.set_warning_function(handler);
+
+        #[cfg(not(feature = "lua54"))]
+        {
+            use crate::multi::Variadic;
+            use std::string::String as StdString;
+
+            let warn = self.create_function(move |lua, pieces: Variadic<StdString>| {
+                if pieces.is_empty() {
+                    return Err(Error::runtime(
+                        "bad argument #1 to 'warn' (string expected, got no value)",
+                    ));
+                }
+                let last = pieces.len() - 1;
+                for (i, msg) in pieces.iter().enumerate() {
+                    handler(lua, msg, i < last)?;
+                }
+                Ok(())
+            })?;
+            self.globals().raw_set("warn", warn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets a function that is called with the name of every chunk successfully loaded using
+    /// [`Lua::load`] (or any other API that compiles Lua source, eg. Luau `require`).
+    ///
+    /// The name is passed as set by [`Chunk::set_name`], including the `@`/`=` prefix.
+    /// This can be used to collect a list of scripts a program depends on.
+    ///
+    /// Modules loaded from Lua files by `require` are reported too (as `@<filename>`), provided
+    /// the `package` library is loaded when the observer is set.
+    pub fn set_load_observer<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&str) + MaybeSend + 'static,
+    {
+        #[cfg(not(feature = "luau"))]
+        self.observe_lua_searcher()?;
+
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).load_observer = Some(std::rc::Rc::new(f)) };
+        Ok(())
+    }
+
+    // Wraps the standard Lua file searcher to report modules loaded by `require`
+    #[cfg(not(feature = "luau"))]
+    fn observe_lua_searcher(&self) -> Result<()> {
+        const REGISTRY_KEY: &str = "__mlua_load_observer_searcher";
+        if self.named_registry_value::<bool>(REGISTRY_KEY)? {
+            return Ok(());
+        }
+
+        let Some(package) = self.globals().raw_get::<Option<Table>>("package")? else {
+            return Ok(());
+        };
+        #[cfg(any(feature = "lua51", feature = "luajit"))]
+        let searchers = package.raw_get::<Option<Table>>("loaders")?;
+        #[cfg(not(any(feature = "lua51", feature = "luajit")))]
+        let searchers = package.raw_get::<Option<Table>>("searchers")?;
+        let Some(searchers) = searchers else {
+            return Ok(());
+        };
+        let Value::Function(searcher) = searchers.raw_get(2)? else {
+            return Ok(());
+        };
+
+        let observe = self.create_function(|lua, loader: Function| {
+            let observer = unsafe { (*lua.lock().extra.get()).load_observer.clone() };
+            if let Some(observer) = observer {
+                let info = loader.info();
+                if let ("main", Some(source)) = (info.what, info.source) {
+                    observer(&source);
+                }
+            }
+            Ok(())
+        })?;
+        let wrapper: Function = self
+            .load(
+                r#"
+                local searcher, observe, type = ...
+                local function report(loader, ...)
+                    if type(loader) == "function" then
+                        observe(loader)
+                    end
+                    return loader, ...
+                end
+                return function(...)
+                    return report(searcher(...))
+                end
+            "#,
+            )
+            .set_name("=__mlua_load_observer")
+            .call((searcher, observe, self.globals().raw_get::<Function>("type")?))?;
+        searchers.raw_set(2, wrapper)?;
+
+        self.set_named_registry_value(REGISTRY_KEY, true)
+    }
+
+    /// Removes a load observer previously set by [`Lua::set_load_observer`].
+    pub fn remove_load_observer(&self) {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).load_observer = None };
+    }
+
     /// Emits a warning with the given message.
     ///
     /// A message in a call with `incomplete` set to `true` should be continued in
@@ -760,6 +1447,55 @@ impl Lua {
         }
     }
 
+    /// Creates a runtime error with the given message, prefixed with the current position in the
+    /// Lua code.
+    ///
+    /// The position is reported in the same way as the Lua `error` function (and `luaL_error`)
+    /// does, eg. `chunkname:currentline: `. It refers to the Lua function that called the running
+    /// Rust callback. If there is no such function, the message is left as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let check = lua.create_function(|lua, n: i64| {
+    ///     if n < 0 {
+    ///         return Err(lua.raise_error(format_args!("expected non-negative number, got {n}")));
+    ///     }
+    ///     Ok(n)
+    /// })?;
+    /// lua.globals().set("check", check)?;
+    ///
+    /// let err = lua.load("check(-1)").set_name("=main").exec().unwrap_err();
+    /// assert!(err.to_string().contains("main:1: expected non-negative number, got -1"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raise_error(&self, args: fmt::Arguments) -> Error {
+        use std::string::String as StdString;
+
+        let lua = self.lock();
+        let state = lua.state();
+        let location = unsafe {
+            let _sg = StackGuard::new(state);
+            // Level 0 is the protected call itself and level 1 is the running Rust callback
+            match check_stack(state, 1)
+                .and_then(|_| protect_lua!(state, 0, 1, |state| ffi::luaL_where(state, 2)))
+            {
+                Ok(()) => {
+                    let mut size = 0;
+                    let data = ffi::lua_tolstring(state, -1, &mut size);
+                    let bytes = std::slice::from_raw_parts(data as *const u8, size);
+                    StdString::from_utf8_lossy(bytes).into_owned()
+                }
+                Err(_) => StdString::new(),
+            }
+        };
+        Error::runtime(format!("{location}{args}"))
+    }
+
     /// Returns the amount of memory (in bytes) currently used inside this Lua state.
     pub fn used_memory(&self) -> usize {
         let lua = self.lock();
@@ -777,6 +1513,71 @@ impl Lua {
         }
     }
 
+    /// Returns the maximum amount of memory (in bytes) used inside this Lua state.
+    ///
+    /// The peak is tracked since the Lua state was created or since the last call to
+    /// [`Lua::reset_peak_memory`].
+    ///
+    /// Returns `None` in module mode where Lua state is managed externally.
+    pub fn peak_memory(&self) -> Option<usize> {
+        let lua = self.lock();
+        unsafe {
+            match MemoryState::get(lua.main_state()) {
+                mem_state if !mem_state.is_null() => Some((*mem_state).peak_memory()),
+                _ => None,
+            }
+        }
+    }
+
+    /// Resets the peak memory usage reported by [`Lua::peak_memory`] to the current usage.
+    ///
+    /// Does nothing in module mode where Lua state is managed externally.
+    pub fn reset_peak_memory(&self) {
+        let lua = self.lock();
+        unsafe {
+            let mem_state = MemoryState::get(lua.main_state());
+            if !mem_state.is_null() {
+                (*mem_state).reset_peak_memory();
+            }
+        }
+    }
+
+    /// Returns the amount of memory (in bytes) in use as reported by the Lua garbage collector.
+    ///
+    /// Unlike [`Lua::used_memory`], this always queries the GC (`LUA_GCCOUNT`/`LUA_GCCOUNTB`),
+    /// even when mlua manages the allocator. The two figures may differ, which can be used to
+    /// spot memory allocated outside of the Lua GC accounting.
+    pub fn gc_count(&self) -> u64 {
+        let lua = self.lock();
+        let state = lua.main_state();
+        unsafe {
+            let used_kbytes = ffi::lua_gc(state, ffi::LUA_GCCOUNT, 0);
+            let used_kbytes_rem = ffi::lua_gc(state, ffi::LUA_GCCOUNTB, 0);
+            (used_kbytes as u64) * 1024 + (used_kbytes_rem as u64)
+        }
+    }
+
+    /// Returns information about the memory allocator if it's managed by mlua.
+    ///
+    /// Returns `None` if the Lua state uses a foreign allocator (eg. in module mode, or when
+    /// LuaJIT does not allow to use a custom allocator). In this case memory control
+    /// functions such as [`Lua::set_memory_limit`] are not available and return
+    /// [`Error::MemoryControlNotAvailable`].
+    pub fn allocator_info(&self) -> Option<AllocatorInfo> {
+        let lua = self.lock();
+        unsafe {
+            let mem_state = MemoryState::get(lua.main_state());
+            if mem_state.is_null() {
+                return None;
+            }
+            let memory_limit = (*mem_state).memory_limit();
+            Some(AllocatorInfo {
+                used_memory: (*mem_state).used_memory(),
+                memory_limit: (memory_limit > 0).then_some(memory_limit),
+            })
+        }
+    }
+
     /// Sets a memory limit (in bytes) on this Lua state.
     ///
     /// Once an allocation occurs that would pass this memory limit, a `Error::MemoryError` is
@@ -824,7 +1625,9 @@ impl Lua {
         let state = lua.main_state();
         unsafe {
             check_stack(state, 2)?;
-            protect_lua!(state, 0, 0, fn(state) ffi::lua_gc(state, ffi::LUA_GCCOLLECT, 0))
+            protect_lua!(state, 0, 0, fn(state) ffi::lua_gc(state, ffi::LUA_GCCOLLECT, 0))?;
+            (*lua.extra.get()).gc_cycles += 1;
+            Ok(())
         }
     }
 
@@ -844,9 +1647,13 @@ impl Lua {
         let state = lua.main_state();
         unsafe {
             check_stack(state, 3)?;
-            protect_lua!(state, 0, 0, |state| {
+            let finished = protect_lua!(state, 0, 0, |state| {
                 ffi::lua_gc(state, ffi::LUA_GCSTEP, kbytes) != 0
-            })
+            })?;
+            if finished {
+                (*lua.extra.get()).gc_cycles += 1;
+            }
+            Ok(finished)
         }
     }
 
@@ -922,6 +1729,10 @@ impl Lua {
         #[cfg(feature = "lua54")]
         let prev_mode = unsafe { ffi::lua_gc(state, ffi::LUA_GCINC, pause, step_multiplier, step_size) };
         #[cfg(feature = "lua54")]
+        unsafe {
+            (*lua.extra.get()).gc_mode = GCMode::Incremental
+        };
+        #[cfg(feature = "lua54")]
         match prev_mode {
             ffi::LUA_GCINC => GCMode::Incremental,
             ffi::LUA_GCGEN => GCMode::Generational,
@@ -943,6 +1754,7 @@ impl Lua {
         let lua = self.lock();
         let state = lua.main_state();
         let prev_mode = unsafe { ffi::lua_gc(state, ffi::LUA_GCGEN, minor_multiplier, major_multiplier) };
+        unsafe { (*lua.extra.get()).gc_mode = GCMode::Generational };
         match prev_mode {
             ffi::LUA_GCGEN => GCMode::Generational,
             ffi::LUA_GCINC => GCMode::Incremental,
@@ -950,6 +1762,49 @@ impl Lua {
         }
     }
 
+    /// Switches the collector to the given mode, resetting its parameters to the Lua defaults.
+    ///
+    /// Returns the previous mode (always `GCMode::Incremental` in Lua < 5.4).
+    pub fn gc_set_mode(&self, mode: GCMode) -> GCMode {
+        // Default parameters of the incremental collector (`pause`, `step multiplier`, `step size`)
+        #[cfg(feature = "lua54")]
+        const INC_DEFAULTS: (c_int, c_int, c_int) = (200, 100, 13);
+        #[cfg(any(feature = "lua53", feature = "lua52", feature = "lua51", feature = "luajit"))]
+        const INC_DEFAULTS: (c_int, c_int, c_int) = (200, 200, 0);
+        #[cfg(feature = "luau")]
+        const INC_DEFAULTS: (c_int, c_int, c_int) = (200, 200, 1);
+
+        match mode {
+            GCMode::Incremental => self.gc_inc(INC_DEFAULTS.0, INC_DEFAULTS.1, INC_DEFAULTS.2),
+            // Default minor and major multipliers of the generational collector
+            #[cfg(feature = "lua54")]
+            GCMode::Generational => self.gc_gen(20, 100),
+        }
+    }
+
+    /// Returns a snapshot of the garbage collector state.
+    ///
+    /// The GC mode is the one set using [`Lua::gc_inc`]/[`Lua::gc_gen`] (changes made from Lua
+    /// code using `collectgarbage` are not tracked).
+    pub fn gc_state(&self) -> GcState {
+        let used_memory = self.used_memory();
+        let lua = self.lock();
+        let extra = unsafe { &*lua.extra.get() };
+        #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52", feature = "luau"))]
+        let is_running = Some(unsafe { ffi::lua_gc(lua.main_state(), ffi::LUA_GCISRUNNING, 0) != 0 });
+        #[cfg(any(feature = "lua51", feature = "luajit"))]
+        let is_running = None;
+        GcState {
+            is_running,
+            #[cfg(feature = "lua54")]
+            mode: extra.gc_mode,
+            #[cfg(not(feature = "lua54"))]
+            mode: GCMode::Incremental,
+            used_memory,
+            cycles: extra.gc_cycles,
+        }
+    }
+
     /// Sets a default Luau compiler (with custom options).
     ///
     /// This compiler will be used by default to load all Lua chunks
@@ -1016,9 +1871,80 @@ impl Lua {
             source: chunk.source(),
             #[cfg(feature = "luau")]
             compiler: unsafe { (*self.lock().extra.get()).compiler.clone() },
+            verify: false,
+        }
+    }
+
+    /// Compiles a line (or several lines) of interactive input, mimicking the standalone Lua REPL.
+    ///
+    /// The input is first compiled as an expression (prefixed with `return`) and, if that fails,
+    /// as a statement. If the statement is incomplete (ie. the syntax error is at `<eof>`),
+    /// [`ReplLoadResult::Incomplete`] is returned and the caller should ask for more input,
+    /// appending it to the current one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, ReplLoadResult, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// assert!(matches!(lua.load_repl("function f()"), ReplLoadResult::Incomplete));
+    /// match lua.load_repl("function f() return 1 end\nf() + 1") {
+    ///     ReplLoadResult::Error(_) => {}
+    ///     r => panic!("unexpected result: {r:?}"),
+    /// }
+    /// match lua.load_repl("1 + 1") {
+    ///     ReplLoadResult::Complete(func) => assert_eq!(func.call::<i32>(())?, 2),
+    ///     r => panic!("unexpected result: {r:?}"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_repl(&self, input: &str) -> ReplLoadResult {
+        let expr = format!("return {input}");
+        if let Ok(func) = self.load(&expr).set_name("=stdin").into_function() {
+            return ReplLoadResult::Complete(func);
+        }
+        match self.load(input).set_name("=stdin").into_function() {
+            Ok(func) => ReplLoadResult::Complete(func),
+            Err(Error::SyntaxError {
+                incomplete_input: true,
+                ..
+            }) => ReplLoadResult::Incomplete,
+            Err(err) => ReplLoadResult::Error(err),
         }
     }
 
+    /// Executes a chunk as a coroutine, returning an iterator over the values it produces.
+    ///
+    /// The chunk is resumed lazily, each time the iterator runs out of values. The iterator yields
+    /// the values passed to each `coroutine.yield` call, followed by the values returned by the
+    /// chunk. Errors (including loading errors) are returned as the last item.
+    ///
+    /// This is useful for REPLs and notebooks to display incremental output of a running script.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result, Value};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let values = lua
+    ///     .exec_streaming("coroutine.yield(1); coroutine.yield(2, 3); return 4")
+    ///     .collect::<Result<Vec<_>>>()?;
+    /// assert_eq!(values, [1, 2, 3, 4].map(Value::Integer));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn exec_streaming<'a>(&self, chunk: impl AsChunk + 'a) -> ExecStream {
+        let thread = self
+            .load(chunk)
+            .into_function()
+            .and_then(|func| self.create_thread(func));
+        ExecStream::new(thread)
+    }
+
     /// Create and return an interned Lua string.
     ///
     /// Lua strings can be arbitrary `[u8]` data including embedded nulls, so in addition to `&str`
@@ -1067,8 +1993,102 @@ impl Lua {
         unsafe { self.lock().create_table_with_capacity(narr, nrec) }
     }
 
+    /// Returns a [`TableBuilder`] for assembling a table with fields, methods and a metatable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result, Table};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let counter = lua
+    ///     .table_builder()
+    ///     .field("count", 0)
+    ///     .method("inc", |_, this: Table, n: i64| {
+    ///         this.set("count", this.get::<i64>("count")? + n)
+    ///     })
+    ///     .build()?;
+    /// lua.globals().set("counter", counter)?;
+    /// assert_eq!(lua.load("counter:inc(2); return counter.count").eval::<i64>()?, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn table_builder(&self) -> TableBuilder<'_> {
+        TableBuilder::new(self)
+    }
+
     /// Creates a table and fills it with values from an iterator.
     pub fn create_table_from<K, V>(&self, iter: impl IntoIterator<Item = (K, V)>) -> Result<Table>
+    where
+        K: IntoLua,
+        V: IntoLua,
+    {
+        let iter = iter.into_iter();
+        let lower_bound = iter.size_hint().0;
+        self.create_table_from_with_capacity(0, lower_bound, iter)
+    }
+
+    /// Creates a table with the specified capacity and fills it with values from an iterator.
+    ///
+    /// This is a version of [`Lua::create_table_from`] that uses the given capacity hints instead
+    /// of the iterator's size hint, which is useful when the number of entries is known upfront
+    /// but the iterator does not report it. See [`Lua::create_table_with_capacity`] for the
+    /// meaning of `narr` and `nrec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use mlua::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let scores = HashMap::from([("alice", 10), ("bob", 7)]);
+    ///
+    /// let iter = scores.iter().filter(|(_, &score)| score > 5).map(|(&k, &v)| (k, v));
+    /// let table = lua.create_table_from_with_capacity(0, scores.len(), iter)?;
+    /// assert_eq!(table.get::<i32>("alice")?, 10);
+    /// assert_eq!(table.get::<i32>("bob")?, 7);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_table_from_with_capacity<K, V>(
+        &self,
+        narr: usize,
+        nrec: usize,
+        iter: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Table>
+    where
+        K: IntoLua,
+        V: IntoLua,
+    {
+        self.create_table_from_inner(narr, nrec, iter, false)
+    }
+
+    /// Creates a table and fills it with values from an iterator, reporting which entry failed to
+    /// convert.
+    ///
+    /// Works the same as [`Lua::create_table_from`], but conversion errors are wrapped with a
+    /// context noting the zero-based index of the failed entry in the iterator and, when the key
+    /// is a string or a number, the key itself.
+    pub fn try_create_table_from<K, V>(&self, iter: impl IntoIterator<Item = (K, V)>) -> Result<Table>
+    where
+        K: IntoLua,
+        V: IntoLua,
+    {
+        let iter = iter.into_iter();
+        let lower_bound = iter.size_hint().0;
+        self.create_table_from_inner(0, lower_bound, iter, true)
+    }
+
+    // Creates a table and fills it with values from an iterator, optionally wrapping conversion
+    // errors with the failed entry context
+    fn create_table_from_inner<K, V>(
+        &self,
+        narr: usize,
+        nrec: usize,
+        iter: impl IntoIterator<Item = (K, V)>,
+        entry_context: bool,
+    ) -> Result<Table>
     where
         K: IntoLua,
         V: IntoLua,
@@ -1079,13 +2099,26 @@ impl Lua {
             let _sg = StackGuard::new(state);
             check_stack(state, 6)?;
 
-            let iter = iter.into_iter();
-            let lower_bound = iter.size_hint().0;
             let protect = !lua.unlikely_memory_error();
-            push_table(state, 0, lower_bound, protect)?;
-            for (k, v) in iter {
-                lua.push(k)?;
-                lua.push(v)?;
+            push_table(state, narr, nrec, protect)?;
+            for (i, (k, v)) in iter.into_iter().enumerate() {
+                if entry_context {
+                    let key = k
+                        .into_lua(self)
+                        .with_context(|_| format!("failed to convert key at index {i}"))?;
+                    lua.push_value(&key)?;
+                    lua.push(v).with_context(|_| match key {
+                        Value::String(ref s) => {
+                            format!("failed to convert value at index {i} (key \"{}\")", s.display())
+                        }
+                        Value::Integer(n) => format!("failed to convert value at index {i} (key {n})"),
+                        Value::Number(n) => format!("failed to convert value at index {i} (key {n})"),
+                        _ => format!("failed to convert value at index {i}"),
+                    })?;
+                } else {
+                    lua.push(k)?;
+                    lua.push(v)?;
+                }
                 if protect {
                     protect_lua!(state, 3, 1, fn(state) ffi::lua_rawset(state, -3))?;
                 } else {
@@ -1105,6 +2138,49 @@ impl Lua {
         unsafe { self.lock().create_sequence_from(iter) }
     }
 
+    /// Creates a table from a slice of numbers, filling the sequence part of the table.
+    ///
+    /// This is faster than [`Lua::create_sequence_from`] for large numeric arrays as it does not
+    /// go through intermediate [`Value`]s.
+    pub fn create_sequence_from_slice<T: NumericElement>(&self, s: &[T]) -> Result<Table> {
+        unsafe { self.lock().create_sequence_from_slice(s) }
+    }
+
+    /// Creates a table by serializing `t` into a Lua value.
+    ///
+    /// This is a shortcut for [`LuaSerdeExt::to_value`] that returns an error if the serialized
+    /// value is not a table (eg. a scalar).
+    ///
+    /// Requires `feature = "serialize"`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mlua::{Lua, Result};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct User {
+    ///     name: String,
+    ///     age: u8,
+    /// }
+    ///
+    /// fn main() -> Result<()> {
+    ///     let lua = Lua::new();
+    ///     let user = lua.create_table_ser(&User { name: "John Smith".into(), age: 20 })?;
+    ///     assert_eq!(user.get::<String>("name")?, "John Smith");
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`LuaSerdeExt::to_value`]: crate::LuaSerdeExt::to_value
+    #[cfg(feature = "serialize")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+    pub fn create_table_ser<T: Serialize + ?Sized>(&self, t: &T) -> Result<Table> {
+        use crate::serde::LuaSerdeExt;
+        Table::from_lua(self.to_value(t)?, self)
+    }
+
     /// Wraps a Rust function or closure, creating a callable Lua function handle to it.
     ///
     /// The function's return value is always a `Result`: If the function returns `Err`, the error
@@ -1159,6 +2235,108 @@ impl Lua {
         }))
     }
 
+    /// Wraps a Rust function and anchors it in the Lua registry under the given `id`.
+    ///
+    /// Works like [`Lua::create_function`], but the function (together with the Rust closure
+    /// backing it) is kept alive by the Lua state even if all handles to it are dropped. It can be
+    /// retrieved later using [`Lua::persistent_function`]. This is useful for hot-reloadable
+    /// plugins, which can re-register their functions under the same ids after reloading.
+    ///
+    /// If a function with the same `id` already exists, it's replaced and the previous one is
+    /// released (it stays valid as long as there are other references to it).
+    ///
+    /// Persistent functions are never collected automatically: they live until they are removed
+    /// using [`Lua::remove_persistent_function`] or the Lua state is dropped. Any values captured
+    /// by the closure are leaked for that time as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    ///
+    /// lua.create_persistent_function("plugin.greet", |_, name: String| Ok(format!("hello, {name}")))?;
+    ///
+    /// let greet = lua.persistent_function("plugin.greet")?.unwrap();
+    /// assert_eq!(greet.call::<String>("world")?, "hello, world");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_persistent_function<F, A, R>(&self, id: &str, func: F) -> Result<Function>
+    where
+        F: Fn(&Lua, A) -> Result<R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        let func = self.create_function(func)?;
+        let key = self.create_registry_value(&func)?;
+        let lua = self.lock();
+        let extra = lua.extra.get();
+        let prev = unsafe { (*extra).persistent_functions.insert(id.to_owned(), key) };
+        if let Some(prev) = prev {
+            self.remove_registry_value(prev)?;
+        }
+        Ok(func)
+    }
+
+    /// Returns a persistent function previously created using [`Lua::create_persistent_function`].
+    ///
+    /// Returns `None` if there is no function with the given `id`.
+    pub fn persistent_function(&self, id: &str) -> Result<Option<Function>> {
+        let lua = self.lock();
+        match unsafe { (*lua.extra.get()).persistent_functions.get(id) } {
+            Some(key) => self.registry_value(key).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes a persistent function created using [`Lua::create_persistent_function`].
+    ///
+    /// The function stays valid as long as there are other references to it.
+    /// Returns `false` if there is no function with the given `id`.
+    pub fn remove_persistent_function(&self, id: &str) -> Result<bool> {
+        let lua = self.lock();
+        let key = unsafe { (*lua.extra.get()).persistent_functions.remove(id) };
+        match key {
+            Some(key) => self.remove_registry_value(key).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Wraps a Rust function that can be preempted, creating a callable Lua function handle to it.
+    ///
+    /// This is a version of [`Lua::create_function`] that passes a [`YieldPoint`] to the function.
+    /// Long-running computations should call [`YieldPoint::check`] periodically and propagate its
+    /// error, so that deadlines and other hooks (interrupts for Luau) can stop them too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::{Duration, Instant};
+    /// # use mlua::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let spin = lua.create_yielding_function(|_, yp, ()| -> Result<()> {
+    ///     loop {
+    ///         yp.check()?;
+    ///     }
+    /// })?;
+    /// lua.set_deadline(Instant::now() + Duration::from_millis(10))?;
+    /// assert!(spin.call::<()>(()).is_err());
+    /// # lua.clear_deadline()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_yielding_function<F, A, R>(&self, func: F) -> Result<Function>
+    where
+        F: Fn(&Lua, YieldPoint, A) -> Result<R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        self.create_function(move |lua, args| func(lua, YieldPoint::new(lua.weak()), args))
+    }
+
     /// Wraps a Rust mutable closure, creating a callable Lua function handle to it.
     ///
     /// This is a version of [`Lua::create_function`] that accepts a `FnMut` argument.
@@ -1174,6 +2352,25 @@ impl Lua {
         })
     }
 
+    /// Wraps a Rust function or closure returning [`anyhow::Result`], creating a callable Lua
+    /// function handle to it.
+    ///
+    /// This is a version of [`Lua::create_function`] that converts the returned [`anyhow::Error`]
+    /// to [`Error`] automatically. If the error wraps an [`Error`], it's returned as-is, otherwise
+    /// it's converted to [`Error::ExternalError`].
+    ///
+    /// Requires `feature = "anyhow"`
+    #[cfg(feature = "anyhow")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "anyhow")))]
+    pub fn create_function_anyhow<F, A, R>(&self, func: F) -> Result<Function>
+    where
+        F: Fn(&Lua, A) -> anyhow::Result<R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        self.create_function(move |lua, args| func(lua, args).map_err(Error::from))
+    }
+
     /// Wraps a C function, creating a callable Lua function handle to it.
     ///
     /// # Safety
@@ -1184,6 +2381,37 @@ impl Lua {
         Ok(Function(lua.pop_ref_thread()))
     }
 
+    /// Wraps a C function with the given upvalues, creating a callable Lua function handle to it.
+    ///
+    /// The upvalues are available to the C function through `lua_upvalueindex(1..=n)`.
+    /// Returns an error if the number of upvalues exceeds the limit supported by Lua.
+    ///
+    /// # Safety
+    /// This function is unsafe because provides a way to execute unsafe C function.
+    pub unsafe fn create_closure(
+        &self,
+        func: ffi::lua_CFunction,
+        upvalues: impl IntoLuaMulti,
+    ) -> Result<Function> {
+        let lua = self.lock();
+        let state = lua.state();
+        let upvalues = upvalues.into_lua_multi(self)?;
+        let nupvalues = upvalues.len() as c_int;
+        if nupvalues > ffi::LUA_MAX_UPVALUES {
+            return Err(Error::runtime("too many upvalues"));
+        }
+
+        let _sg = StackGuard::new(state);
+        check_stack(state, nupvalues + 1)?;
+        for value in &upvalues {
+            lua.push_value(value)?;
+        }
+        protect_lua!(state, nupvalues, 1, |state| {
+            ffi::lua_pushcclosure(state, func, nupvalues);
+        })?;
+        Ok(Function(lua.pop_ref()))
+    }
+
     /// Wraps a Rust async function or closure, creating a callable Lua function handle to it.
     ///
     /// While executing the function Rust will poll the Future and if the result is not ready,
@@ -1244,6 +2472,73 @@ impl Lua {
         }))
     }
 
+    /// Wraps a Rust function returning a [`Stream`], creating a Lua function that returns an
+    /// iterator over the stream items.
+    ///
+    /// Every call to the returned function invokes `make_stream` with the call arguments and
+    /// returns an async iterator function, suitable for the generic `for` loop. Each call to the
+    /// iterator awaits the next stream item and returns it, or `nil` when the stream is exhausted.
+    /// If the stream yields an error, it is raised in Lua.
+    ///
+    /// The iterator can be shared between coroutines: concurrent calls wait for each other and
+    /// every call receives the next item. Note that an item converted to `nil` ends the `for` loop,
+    /// as with any other Lua iterator.
+    ///
+    /// As with [`Lua::create_async_function`], the iterator must be called inside a Lua coroutine.
+    ///
+    /// Requires `feature = "async"`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures_util::stream;
+    /// use mlua::{Lua, Result};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let lua = Lua::new();
+    ///     let range = lua.create_async_iterator(|_, n: i64| stream::iter((1..=n).map(Ok)))?;
+    ///     lua.globals().set("range", range)?;
+    ///     let sum: i64 = lua
+    ///         .load("local sum = 0; for i in range(4) do sum = sum + i end; return sum")
+    ///         .call_async(())
+    ///         .await?;
+    ///     assert_eq!(sum, 10);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`Stream`]: futures_util::stream::Stream
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub fn create_async_iterator<F, A, S, T>(&self, make_stream: F) -> Result<Function>
+    where
+        F: Fn(Lua, A) -> S + MaybeSend + 'static,
+        A: FromLuaMulti,
+        S: Stream<Item = Result<T>> + MaybeSend + 'static,
+        T: IntoLua,
+    {
+        self.create_function(move |lua, args: A| {
+            let stream = Box::pin(make_stream(lua.clone(), args));
+            let stream = XRc::new(futures_util::lock::Mutex::new(Some(stream)));
+            lua.create_async_function(move |_, ()| {
+                let stream = stream.clone();
+                async move {
+                    // Hold the lock while polling, so concurrent calls wait for the next item
+                    let mut stream = stream.lock().await;
+                    let Some(s) = stream.as_mut() else {
+                        return Ok(None);
+                    };
+                    let item = s.next().await.transpose()?;
+                    if item.is_none() {
+                        *stream = None;
+                    }
+                    Ok(item)
+                }
+            })
+        })
+    }
+
     /// Wraps a Lua function into a new thread (or coroutine).
     ///
     /// Equivalent to `coroutine.create`.
@@ -1251,6 +2546,29 @@ impl Lua {
         unsafe { self.lock().create_thread(&func) }
     }
 
+    /// Creates a [`ThreadPool`] that keeps up to `size` idle threads for reuse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Function, Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let pool = lua.thread_pool(16);
+    /// let double: Function = lua.load("function(x) return x * 2 end").eval()?;
+    /// for i in 0..100 {
+    ///     // The same coroutine is reused on every iteration
+    ///     let thread = pool.acquire(double.clone())?;
+    ///     assert_eq!(thread.resume::<i32>(i)?, i * 2);
+    /// }
+    /// assert_eq!(pool.idle(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn thread_pool(&self, size: usize) -> ThreadPool {
+        ThreadPool::new(self.weak(), size)
+    }
+
     /// Creates a Lua userdata object from a custom userdata type.
     ///
     /// All userdata instances of the same type `T` shares the same metatable.
@@ -1316,8 +2634,8 @@ impl Lua {
         let lua = self.lock();
         unsafe {
             // Deregister the type if it already registered
-            if let Some(&table_id) = (*lua.extra.get()).registered_userdata_t.get(&type_id) {
-                ffi::luaL_unref(lua.state(), ffi::LUA_REGISTRYINDEX, table_id);
+            if let Some(info) = (*lua.extra.get()).registered_userdata_t.get(&type_id) {
+                ffi::luaL_unref(lua.state(), ffi::LUA_REGISTRYINDEX, info.id);
             }
 
             // Add to "pending" registration map
@@ -1437,17 +2755,35 @@ impl Lua {
 
     /// Returns a handle to the global environment.
     pub fn globals(&self) -> Table {
-        let lua = self.lock();
-        let state = lua.state();
-        unsafe {
-            let _sg = StackGuard::new(state);
-            assert_stack(state, 1);
-            #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52"))]
-            ffi::lua_rawgeti(state, ffi::LUA_REGISTRYINDEX, ffi::LUA_RIDX_GLOBALS);
-            #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
-            ffi::lua_pushvalue(state, ffi::LUA_GLOBALSINDEX);
-            Table(lua.pop_ref())
-        }
+        unsafe { self.lock().globals() }
+    }
+
+    /// Installs a curated set of Rust-backed helper functions into the global environment.
+    ///
+    /// This is a convenience for REPLs and embeddings that want debugging utilities such as
+    /// `dump` or `hexdump` available by default. See [`HelperSet`] for the list of helpers and
+    /// their behavior.
+    ///
+    /// Requires `feature = "helpers"`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{HelperSet, Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// lua.install_helpers(HelperSet::DUMP | HelperSet::HEXDUMP)?;
+    /// let dump = lua.load("dump({answer = 42})").eval::<String>()?;
+    /// assert!(dump.contains("answer = 42"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`HelperSet`]: crate::HelperSet
+    #[cfg(feature = "helpers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "helpers")))]
+    pub fn install_helpers(&self, set: crate::HelperSet) -> Result<()> {
+        crate::helpers::install(self, set)
     }
 
     /// Returns a handle to the active `Thread`.
@@ -1475,6 +2811,10 @@ impl Lua {
     /// lifetime of values created through [`Scope`], and we know that [`Lua`] cannot be sent to
     /// another thread while [`Scope`] is live, it is safe to allow `!Send` data types and whose
     /// lifetimes only outlive the scope lifetime.
+    ///
+    /// Scopes can be nested: values created through an inner [`Scope`] are invalidated when the
+    /// inner call completes, while values of the outer scope remain valid until the outer call
+    /// completes.
     pub fn scope<'env, R>(
         &self,
         f: impl for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> Result<R>,
@@ -1483,6 +2823,56 @@ impl Lua {
         f(&Scope::new(self.lock_arc()))
     }
 
+    /// Asynchronous version of [`Lua::scope`].
+    ///
+    /// Calls the given function with a [`Scope`] parameter and awaits the returned future. Values
+    /// created through the [`Scope`] (including async functions created using
+    /// [`Scope::create_async_function`]) stay valid until the future completes and are invalidated
+    /// afterwards. If the returned future is dropped before completion, the scoped values are
+    /// invalidated at that point, and any of their pending futures are dropped.
+    ///
+    /// The Lua instance is locked (by the current thread) until the returned future completes or
+    /// is dropped.
+    ///
+    /// Requires `feature = "async"`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::cell::RefCell;
+    /// # use mlua::{Function, Lua, Result};
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let mut names = Vec::new();
+    /// let names_ref = &RefCell::new(&mut names);
+    /// let f: Function = lua.load("function(add) add('alice'); add('bob') end").eval()?;
+    ///
+    /// lua.scope_async(|scope| {
+    ///     Box::pin(async move {
+    ///         let add_name = scope.create_async_function(move |_, name: String| async move {
+    ///             names_ref.borrow_mut().push(name);
+    ///             Ok(())
+    ///         })?;
+    ///         f.call_async::<()>(add_name).await
+    ///     })
+    /// })
+    /// .await?;
+    ///
+    /// assert_eq!(names, ["alice", "bob"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn scope_async<'env, R>(
+        &self,
+        f: impl for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> LocalBoxFuture<'scope, Result<R>>,
+    ) -> Result<R> {
+        let scope = Scope::new(self.lock_arc());
+        f(&scope).await
+    }
+
     /// Attempts to coerce a Lua value into a String in a manner consistent with Lua's internal
     /// behavior.
     ///
@@ -1670,6 +3060,7 @@ impl Lua {
             // Try to reuse previously allocated slot
             let free_registry_id = unref_list.lock().as_mut().and_then(|x| x.pop());
             if let Some(registry_id) = free_registry_id {
+                (*lua.extra.get()).invalidate_registry_value(registry_id);
                 // It must be safe to replace the value without triggering memory error
                 ffi::lua_rawseti(state, ffi::LUA_REGISTRYINDEX, registry_id as Integer);
                 return Ok(RegistryKey::new(registry_id, unref_list));
@@ -1683,6 +3074,9 @@ impl Lua {
                     ffi::luaL_ref(state, ffi::LUA_REGISTRYINDEX)
                 })?
             };
+            let extra = &mut *lua.extra.get();
+            extra.registry_slots += 1;
+            extra.registry_slots_peak = extra.registry_slots_peak.max(extra.registry_slots);
             Ok(RegistryKey::new(registry_id, unref_list))
         }
     }
@@ -1710,6 +3104,73 @@ impl Lua {
         }
     }
 
+    /// Places a value in the Lua registry, returning a key that remembers its type.
+    ///
+    /// This is a version of [`Lua::create_registry_value`] that returns a [`TypedRegistryKey`],
+    /// so the value can be retrieved using [`Lua::typed_registry_value`] without specifying
+    /// the type again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result};
+    /// # fn main() -> Result<()> {
+    /// let lua = Lua::new();
+    /// let key = lua.create_typed_registry_value(String::from("hello"))?;
+    /// assert_eq!(lua.typed_registry_value(&key)?, "hello");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_typed_registry_value<T>(&self, t: T) -> Result<TypedRegistryKey<T>>
+    where
+        T: IntoLua + FromLua,
+    {
+        Ok(TypedRegistryKey::new(self.create_registry_value(t)?))
+    }
+
+    /// Gets a value from the Lua registry by its [`TypedRegistryKey`].
+    ///
+    /// See [`Lua::registry_value`] for more details.
+    pub fn typed_registry_value<T: FromLua>(&self, key: &TypedRegistryKey<T>) -> Result<T> {
+        self.registry_value(key.as_key())
+    }
+
+    /// Gets a value from the Lua registry by its [`RegistryKey`], caching the converted value.
+    ///
+    /// The first call converts the value as [`Lua::registry_value`] does and stores the result;
+    /// subsequent calls for the same key and type return a clone of the stored value without
+    /// converting it again. This suits values read frequently in hot paths.
+    ///
+    /// The cache is invalidated by [`Lua::replace_registry_value`] and when the key is removed.
+    /// Modifications made to the value from Lua (eg. to fields of a table) are not tracked.
+    pub fn registry_value_cached<T>(&self, key: &RegistryKey) -> Result<T>
+    where
+        T: FromLua + Clone + MaybeSend + 'static,
+    {
+        let lua = self.lock();
+        if !lua.owns_registry_value(key) {
+            return Err(Error::MismatchedRegistryKey);
+        }
+
+        let extra = lua.extra.get();
+        let cached = unsafe { (*extra).registry_value_cache.get(&key.id()) };
+        if let Some(value) = cached.and_then(|values| values.get(&TypeId::of::<T>())) {
+            if let Some(value) = value.downcast_ref::<T>() {
+                return Ok(value.clone());
+            }
+        }
+
+        let value: T = self.registry_value(key)?;
+        unsafe {
+            (*extra)
+                .registry_value_cache
+                .entry(key.id())
+                .or_default()
+                .insert(TypeId::of::<T>(), Box::new(value.clone()))
+        };
+        Ok(value)
+    }
+
     /// Removes a value from the Lua registry.
     ///
     /// You may call this function to manually remove a value placed in the registry with
@@ -1722,7 +3183,14 @@ impl Lua {
             return Err(Error::MismatchedRegistryKey);
         }
 
-        unsafe { ffi::luaL_unref(lua.state(), ffi::LUA_REGISTRYINDEX, key.take()) };
+        let registry_id = key.take();
+        if registry_id != ffi::LUA_REFNIL {
+            unsafe {
+                ffi::luaL_unref(lua.state(), ffi::LUA_REGISTRYINDEX, registry_id);
+                (*lua.extra.get()).invalidate_registry_value(registry_id);
+                (*lua.extra.get()).registry_slots -= 1;
+            }
+        }
         Ok(())
     }
 
@@ -1744,6 +3212,8 @@ impl Lua {
             let _sg = StackGuard::new(state);
             check_stack(state, 2)?;
 
+            (*lua.extra.get()).invalidate_registry_value(key.id());
+
             match (t, key.id()) {
                 (Value::Nil, ffi::LUA_REFNIL) => {
                     // Do nothing, no need to replace nil with nil
@@ -1751,6 +3221,7 @@ impl Lua {
                 (Value::Nil, registry_id) => {
                     // Remove the value
                     ffi::luaL_unref(state, ffi::LUA_REGISTRYINDEX, registry_id);
+                    (*lua.extra.get()).registry_slots -= 1;
                     key.set_id(ffi::LUA_REFNIL);
                 }
                 (value, ffi::LUA_REFNIL) => {
@@ -1788,10 +3259,41 @@ impl Lua {
         let lua = self.lock();
         let state = lua.state();
         unsafe {
-            let mut unref_list = (*lua.extra.get()).registry_unref_list.lock();
-            let unref_list = mem::replace(&mut *unref_list, Some(Vec::new()));
+            let extra = &mut *lua.extra.get();
+            let unref_list = mem::replace(&mut *extra.registry_unref_list.lock(), Some(Vec::new()));
             for id in mlua_expect!(unref_list, "unref list not set") {
                 ffi::luaL_unref(state, ffi::LUA_REGISTRYINDEX, id);
+                extra.invalidate_registry_value(id);
+                extra.registry_slots -= 1;
+            }
+        }
+    }
+
+    /// Shrinks the auxiliary stack used to store references to Lua values (handles).
+    ///
+    /// The stack grows when many handles (eg. [`Table`]s or [`Function`]s) are alive at the same
+    /// time, but is never shrunk automatically. This method releases the free slots at the top of
+    /// the stack, allowing Lua to reclaim the memory after a transient spike of references.
+    ///
+    /// Returns the number of released slots.
+    pub fn shrink_ref_stack(&self) -> usize {
+        let lua = self.lock();
+        unsafe { (*lua.extra.get()).shrink_ref_stack() }
+    }
+
+    /// Returns statistics about values stored in the registry using [`RegistryKey`]s.
+    ///
+    /// This is useful for diagnosing registry growth, eg. when dropped [`RegistryKey`]s
+    /// accumulate without calling [`Lua::expire_registry_values`].
+    pub fn registry_stats(&self) -> RegistryStats {
+        let lua = self.lock();
+        unsafe {
+            let extra = &*lua.extra.get();
+            let pending_free = extra.registry_unref_list.lock().as_ref().map_or(0, |l| l.len());
+            RegistryStats {
+                live: extra.registry_slots.saturating_sub(pending_free),
+                pending_free,
+                peak: extra.registry_slots_peak,
             }
         }
     }
@@ -1830,7 +3332,7 @@ impl Lua {
     pub fn set_app_data<T: MaybeSend + 'static>(&self, data: T) -> Option<T> {
         let lua = self.lock();
         let extra = unsafe { &*lua.extra.get() };
-        extra.app_data.insert(data)
+        extra.app_data.insert(None, data)
     }
 
     /// Tries to set or replace an application data object of type `T`.
@@ -1845,7 +3347,7 @@ impl Lua {
     pub fn try_set_app_data<T: MaybeSend + 'static>(&self, data: T) -> StdResult<Option<T>, T> {
         let lua = self.lock();
         let extra = unsafe { &*lua.extra.get() };
-        extra.app_data.try_insert(data)
+        extra.app_data.try_insert(None, data)
     }
 
     /// Gets a reference to an application data object stored by [`Lua::set_app_data`] of type
@@ -1859,7 +3361,7 @@ impl Lua {
     pub fn app_data_ref<T: 'static>(&self) -> Option<AppDataRef<T>> {
         let guard = self.lock_arc();
         let extra = unsafe { &*guard.extra.get() };
-        extra.app_data.borrow(Some(guard))
+        extra.app_data.borrow(None, Some(guard))
     }
 
     /// Tries to get a reference to an application data object stored by [`Lua::set_app_data`] of
@@ -1867,7 +3369,7 @@ impl Lua {
     pub fn try_app_data_ref<T: 'static>(&self) -> StdResult<Option<AppDataRef<T>>, BorrowError> {
         let guard = self.lock_arc();
         let extra = unsafe { &*guard.extra.get() };
-        extra.app_data.try_borrow(Some(guard))
+        extra.app_data.try_borrow(None, Some(guard))
     }
 
     /// Gets a mutable reference to an application data object stored by [`Lua::set_app_data`] of
@@ -1880,7 +3382,7 @@ impl Lua {
     pub fn app_data_mut<T: 'static>(&self) -> Option<AppDataRefMut<T>> {
         let guard = self.lock_arc();
         let extra = unsafe { &*guard.extra.get() };
-        extra.app_data.borrow_mut(Some(guard))
+        extra.app_data.borrow_mut(None, Some(guard))
     }
 
     /// Tries to get a mutable reference to an application data object stored by
@@ -1888,7 +3390,7 @@ impl Lua {
     pub fn try_app_data_mut<T: 'static>(&self) -> StdResult<Option<AppDataRefMut<T>>, BorrowMutError> {
         let guard = self.lock_arc();
         let extra = unsafe { &*guard.extra.get() };
-        extra.app_data.try_borrow_mut(Some(guard))
+        extra.app_data.try_borrow_mut(None, Some(guard))
     }
 
     /// Removes an application data of type `T`.
@@ -1900,7 +3402,76 @@ impl Lua {
     pub fn remove_app_data<T: 'static>(&self) -> Option<T> {
         let lua = self.lock();
         let extra = unsafe { &*lua.extra.get() };
-        extra.app_data.remove()
+        extra.app_data.remove(None)
+    }
+
+    /// Sets or replaces an application data object of type `T` stored under the given `key`.
+    ///
+    /// Unlike [`Lua::set_app_data`], this allows storing multiple values of the same type,
+    /// distinguished by name. Keyed values are independent from the unkeyed one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app data container is currently borrowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mlua::{Lua, Result};
+    ///
+    /// fn main() -> Result<()> {
+    ///     let lua = Lua::new();
+    ///     lua.set_app_data_keyed("primary", "db1.example.com".to_string());
+    ///     lua.set_app_data_keyed("replica", "db2.example.com".to_string());
+    ///
+    ///     assert_eq!(*lua.app_data_ref_keyed::<String>("replica").unwrap(), "db2.example.com");
+    ///     assert!(lua.app_data_ref::<String>().is_none());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn set_app_data_keyed<T: MaybeSend + 'static>(&self, key: &'static str, data: T) -> Option<T> {
+        let lua = self.lock();
+        let extra = unsafe { &*lua.extra.get() };
+        extra.app_data.insert(Some(key), data)
+    }
+
+    /// Gets a reference to an application data object of type `T` stored by
+    /// [`Lua::set_app_data_keyed`] under the given `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data object is currently mutably borrowed.
+    #[track_caller]
+    pub fn app_data_ref_keyed<T: 'static>(&self, key: &'static str) -> Option<AppDataRef<'_, T>> {
+        let guard = self.lock_arc();
+        let extra = unsafe { &*guard.extra.get() };
+        extra.app_data.borrow(Some(key), Some(guard))
+    }
+
+    /// Gets a mutable reference to an application data object of type `T` stored by
+    /// [`Lua::set_app_data_keyed`] under the given `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data object is currently borrowed.
+    #[track_caller]
+    pub fn app_data_mut_keyed<T: 'static>(&self, key: &'static str) -> Option<AppDataRefMut<'_, T>> {
+        let guard = self.lock_arc();
+        let extra = unsafe { &*guard.extra.get() };
+        extra.app_data.borrow_mut(Some(key), Some(guard))
+    }
+
+    /// Removes an application data object of type `T` stored under the given `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app data container is currently borrowed.
+    #[track_caller]
+    pub fn remove_app_data_keyed<T: 'static>(&self, key: &'static str) -> Option<T> {
+        let lua = self.lock();
+        let extra = unsafe { &*lua.extra.get() };
+        extra.app_data.remove(Some(key))
     }
 
     /// Returns an internal `Poll::Pending` constant used for executing async callbacks.
@@ -1,5 +1,6 @@
-use std::any::TypeId;
+use std::any::{Any, TypeId};
 use std::cell::UnsafeCell;
+use std::cmp;
 use std::mem::MaybeUninit;
 use std::os::raw::{c_int, c_void};
 use std::ptr;
@@ -12,7 +13,7 @@ use rustc_hash::FxHashMap;
 use crate::error::Result;
 use crate::state::RawLua;
 use crate::stdlib::StdLib;
-use crate::types::{AppData, ReentrantMutex, XRc};
+use crate::types::{AppData, ReentrantMutex, RegistryKey, XRc};
 use crate::userdata::RawUserDataRegistry;
 use crate::util::{get_internal_metatable, push_internal_userdata, TypeKey, WrappedFailure};
 
@@ -22,14 +23,30 @@ use crate::chunk::Compiler;
 #[cfg(feature = "async")]
 use {futures_util::task::noop_waker_ref, std::ptr::NonNull, std::task::Waker};
 
+use super::raw::SavedHook;
 use super::{Lua, WeakLua};
 
 // Unique key to store `ExtraData` in the registry
 static EXTRA_REGISTRY_KEY: u8 = 0;
 
+#[cfg(not(feature = "send"))]
+type CachedRegistryValue = Box<dyn Any>;
+
+#[cfg(feature = "send")]
+type CachedRegistryValue = Box<dyn Any + Send>;
+
 const WRAPPED_FAILURE_POOL_DEFAULT_CAPACITY: usize = 64;
 const REF_STACK_RESERVE: c_int = 1;
 
+/// Cached information about a registered userdata metatable.
+#[derive(Clone, Copy)]
+pub(crate) struct UserDataMetatableInfo {
+    // Metatable reference in the registry
+    pub(super) id: c_int,
+    #[cfg(not(feature = "luau"))]
+    pub(super) finalizer_priority: Option<i32>,
+}
+
 /// Data associated with the Lua state.
 pub(crate) struct ExtraData {
     pub(super) lua: MaybeUninit<Lua>,
@@ -37,18 +54,33 @@ pub(crate) struct ExtraData {
     pub(super) owned: bool,
 
     pub(super) pending_userdata_reg: FxHashMap<TypeId, RawUserDataRegistry>,
-    pub(super) registered_userdata_t: FxHashMap<TypeId, c_int>,
+    pub(super) registered_userdata_t: FxHashMap<TypeId, UserDataMetatableInfo>,
     pub(super) registered_userdata_mt: FxHashMap<*const c_void, Option<TypeId>>,
     pub(super) last_checked_userdata_mt: (*const c_void, Option<TypeId>),
 
     // When Lua instance dropped, setting `None` would prevent collecting `RegistryKey`s
     pub(super) registry_unref_list: Arc<Mutex<Option<Vec<c_int>>>>,
+    // Number of registry slots allocated for `RegistryKey`s (and the peak value)
+    pub(super) registry_slots: usize,
+    pub(super) registry_slots_peak: usize,
+    // Converted registry values, keyed by registry id and then by target type
+    pub(super) registry_value_cache: FxHashMap<c_int, FxHashMap<TypeId, CachedRegistryValue>>,
+    // Functions anchored in the registry using `Lua::create_persistent_function`
+    pub(super) persistent_functions: FxHashMap<String, RegistryKey>,
+
+    // Number of completed GC cycles observed using `gc_collect`/`gc_step`
+    pub(super) gc_cycles: u64,
+    // Last GC mode set using `gc_inc`/`gc_gen`
+    #[cfg(feature = "lua54")]
+    pub(super) gc_mode: super::GCMode,
 
     // Container to store arbitrary data (extensions)
     pub(super) app_data: AppData,
 
     pub(super) safe: bool,
     pub(super) libs: StdLib,
+    #[cfg(not(feature = "luau"))]
+    pub(super) allow_bytecode: bool,
     // Used in module mode
     pub(super) skip_memory_check: bool,
 
@@ -72,14 +104,15 @@ pub(crate) struct ExtraData {
     #[cfg(feature = "async")]
     pub(super) waker: NonNull<Waker>,
 
-    #[cfg(not(feature = "luau"))]
-    pub(super) hook_callback: Option<crate::types::HookCallback>,
-    #[cfg(not(feature = "luau"))]
-    pub(super) hook_thread: *mut ffi::lua_State,
     #[cfg(feature = "lua54")]
     pub(super) warn_callback: Option<crate::types::WarnCallback>,
+    pub(super) load_observer: Option<crate::types::LoadObserver>,
     #[cfg(feature = "luau")]
     pub(super) interrupt_callback: Option<crate::types::InterruptCallback>,
+    // Hook (interrupt for Luau) replaced by `Lua::set_deadline`
+    pub(super) deadline_saved_hook: Option<SavedHook>,
+    // Hooks (interrupts for Luau) replaced by running profilers, keyed by the profiler hook id
+    pub(super) profiler_saved_hooks: FxHashMap<usize, SavedHook>,
 
     #[cfg(feature = "luau")]
     pub(super) sandboxed: bool,
@@ -116,6 +149,12 @@ impl ExtraData {
     #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
     pub(super) const ERROR_TRACEBACK_IDX: c_int = 1;
 
+    // Index of the (cached) globals table in auxiliary thread stack
+    #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
+    pub(super) const GLOBALS_IDX: c_int = 2;
+    #[cfg(not(any(feature = "lua51", feature = "luajit", feature = "luau")))]
+    pub(super) const GLOBALS_IDX: c_int = 1;
+
     pub(super) unsafe fn init(state: *mut ffi::lua_State, owned: bool) -> XRc<UnsafeCell<Self>> {
         // Create ref stack thread and place it in the registry to prevent it
         // from being garbage collected.
@@ -142,6 +181,13 @@ impl ExtraData {
             assert_eq!(ffi::lua_gettop(ref_thread), Self::ERROR_TRACEBACK_IDX);
         }
 
+        // Store the globals table on the ref stack, to avoid fetching it every time
+        #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52"))]
+        ffi::lua_rawgeti(ref_thread, ffi::LUA_REGISTRYINDEX, ffi::LUA_RIDX_GLOBALS);
+        #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
+        ffi::lua_pushvalue(ref_thread, ffi::LUA_GLOBALSINDEX);
+        assert_eq!(ffi::lua_gettop(ref_thread), Self::GLOBALS_IDX);
+
         #[allow(clippy::arc_with_non_send_sync)]
         let extra = XRc::new(UnsafeCell::new(ExtraData {
             lua: MaybeUninit::uninit(),
@@ -152,9 +198,18 @@ impl ExtraData {
             registered_userdata_mt: FxHashMap::default(),
             last_checked_userdata_mt: (ptr::null(), None),
             registry_unref_list: Arc::new(Mutex::new(Some(Vec::new()))),
+            registry_slots: 0,
+            registry_slots_peak: 0,
+            registry_value_cache: FxHashMap::default(),
+            persistent_functions: FxHashMap::default(),
+            gc_cycles: 0,
+            #[cfg(feature = "lua54")]
+            gc_mode: super::GCMode::Incremental,
             app_data: AppData::default(),
             safe: false,
             libs: StdLib::NONE,
+            #[cfg(not(feature = "luau"))]
+            allow_bytecode: true,
             skip_memory_check: false,
             ref_thread,
             // We need some reserved stack space to move values in and out of the ref stack.
@@ -168,14 +223,13 @@ impl ExtraData {
             wrapped_failure_mt_ptr,
             #[cfg(feature = "async")]
             waker: NonNull::from(noop_waker_ref()),
-            #[cfg(not(feature = "luau"))]
-            hook_callback: None,
-            #[cfg(not(feature = "luau"))]
-            hook_thread: ptr::null_mut(),
             #[cfg(feature = "lua54")]
             warn_callback: None,
+            load_observer: None,
             #[cfg(feature = "luau")]
             interrupt_callback: None,
+            deadline_saved_hook: None,
+            profiler_saved_hooks: FxHashMap::default(),
             #[cfg(feature = "luau")]
             sandboxed: false,
             #[cfg(feature = "luau")]
@@ -234,6 +288,26 @@ impl ExtraData {
         })
     }
 
+    // Drops cached conversions of the registry value stored under `registry_id`
+    #[inline]
+    pub(super) fn invalidate_registry_value(&mut self, registry_id: c_int) {
+        self.registry_value_cache.remove(&registry_id);
+    }
+
+    // Releases free slots at the top of the ref stack, returning their number
+    pub(super) unsafe fn shrink_ref_stack(&mut self) -> usize {
+        let old_top = self.ref_stack_top;
+        self.ref_free.sort_unstable();
+        while self.ref_free.last() == Some(&self.ref_stack_top) {
+            self.ref_free.pop();
+            self.ref_stack_top -= 1;
+        }
+        ffi::lua_settop(self.ref_thread, self.ref_stack_top);
+        // Lua can shrink the thread stack during GC, so we need to check stack space again
+        self.ref_stack_size = cmp::max(self.ref_stack_top, ffi::LUA_MINSTACK - REF_STACK_RESERVE);
+        (old_top - self.ref_stack_top) as usize
+    }
+
     #[inline(always)]
     pub(super) unsafe fn lua(&self) -> &Lua {
         self.lua.assume_init_ref()
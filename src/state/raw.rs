@@ -8,6 +8,8 @@ use std::ptr::{self, NonNull};
 use std::result::Result as StdResult;
 use std::sync::Arc;
 
+#[cfg(not(feature = "luau"))]
+use crate::chunk::check_bytecode_header;
 use crate::chunk::ChunkMode;
 use crate::error::{Error, Result};
 use crate::function::Function;
@@ -17,7 +19,9 @@ use crate::stdlib::StdLib;
 use crate::string::String;
 use crate::table::Table;
 use crate::thread::Thread;
-use crate::traits::IntoLua;
+use crate::traits::{IntoLua, NumericElement};
+#[cfg(not(feature = "luau"))]
+use crate::types::HookCallback;
 use crate::types::{
     AppDataRef, AppDataRefMut, Callback, CallbackUpvalue, DestructedUserdata, Integer, LightUserData,
     MaybeSend, ReentrantMutex, RegistryKey, ValueRef, XRc,
@@ -33,11 +37,14 @@ use crate::util::{
 };
 use crate::value::{Nil, Value};
 
-use super::extra::ExtraData;
+use super::extra::{ExtraData, UserDataMetatableInfo};
 use super::{Lua, LuaOptions, WeakLua};
 
 #[cfg(not(feature = "luau"))]
-use crate::hook::{Debug, HookTriggers};
+use {
+    crate::hook::{Debug, HookTriggers},
+    crate::util::{push_finalizer_queue_entry, run_finalizer_queue},
+};
 
 #[cfg(feature = "async")]
 use {
@@ -47,6 +54,24 @@ use {
     std::task::{Context, Poll, Waker},
 };
 
+/// A hook (interrupt for Luau) saved using [`RawLua::save_hook`] to be restored later.
+pub(crate) struct SavedHook {
+    #[cfg(not(feature = "luau"))]
+    state: *mut ffi::lua_State,
+    #[cfg(not(feature = "luau"))]
+    hook: Option<ffi::lua_Hook>,
+    #[cfg(not(feature = "luau"))]
+    mask: c_int,
+    #[cfg(not(feature = "luau"))]
+    count: c_int,
+    #[cfg(not(feature = "luau"))]
+    callback: Option<HookCallback>,
+    #[cfg(feature = "luau")]
+    interrupt: Option<unsafe extern "C-unwind" fn(*mut ffi::lua_State, c_int)>,
+    #[cfg(feature = "luau")]
+    callback: Option<crate::types::InterruptCallback>,
+}
+
 /// An inner Lua struct which holds a raw Lua state.
 #[doc(hidden)]
 pub struct RawLua {
@@ -65,6 +90,8 @@ impl Drop for RawLua {
 
             let mem_state = MemoryState::get(self.main_state());
 
+            #[cfg(not(feature = "luau"))]
+            run_finalizer_queue(self.main_state());
             ffi::lua_close(self.main_state());
 
             // Deallocate `MemoryState`
@@ -164,6 +191,11 @@ impl RawLua {
             (*extra).thread_pool.reserve_exact(options.thread_pool_size);
         }
 
+        #[cfg(not(feature = "luau"))]
+        {
+            (*extra).allow_bytecode = options.allow_bytecode;
+        }
+
         rawlua
     }
 
@@ -186,6 +218,8 @@ impl RawLua {
                 init_internal_metatable::<XRc<UnsafeCell<ExtraData>>>(state, None)?;
                 init_internal_metatable::<Callback>(state, None)?;
                 init_internal_metatable::<CallbackUpvalue>(state, None)?;
+                #[cfg(not(feature = "luau"))]
+                init_internal_metatable::<crate::types::HookCallback>(state, None)?;
                 #[cfg(feature = "async")]
                 {
                     init_internal_metatable::<AsyncCallback>(state, None)?;
@@ -279,11 +313,65 @@ impl RawLua {
         res
     }
 
+    /// See [`Lua::open_library_into`]
+    pub(super) unsafe fn open_library_into(&self, lib: StdLib) -> Result<Table> {
+        #[cfg(not(feature = "luau"))]
+        if (*self.extra.get()).safe && lib == StdLib::DEBUG {
+            return Err(Error::SafetyError(
+                "the unsafe `debug` module can't be loaded in safe mode".to_string(),
+            ));
+        }
+        #[cfg(feature = "luajit")]
+        if (*self.extra.get()).safe && lib == StdLib::FFI {
+            return Err(Error::SafetyError(
+                "the unsafe `ffi` module can't be loaded in safe mode".to_string(),
+            ));
+        }
+        #[cfg(not(feature = "luau"))]
+        if (*self.extra.get()).safe
+            && lib == StdLib::PACKAGE
+            && !(*self.extra.get()).libs.contains(StdLib::PACKAGE)
+        {
+            return Err(Error::SafetyError(
+                "the `package` module can't be opened standalone in safe mode".to_string(),
+            ));
+        }
+
+        let (modname, openf) = std_lib_opener(lib)
+            .ok_or_else(|| Error::runtime(format!("cannot open {lib:?} as a standalone library")))?;
+
+        // Some Lua versions set the global from the library open function regardless of `glb`
+        let globals = self.globals();
+        let prev_global = globals.raw_get::<Value>(modname)?;
+
+        let state = self.state();
+        let _sg = StackGuard::new(state);
+        check_stack(state, 6)?;
+
+        let modname_c = mlua_expect!(CString::new(modname), "modname contains nil byte");
+        protect_lua!(state, 0, 1, |state| {
+            ffi::luaL_requiref(state, modname_c.as_ptr() as *const c_char, openf, 0);
+            // The library table can be shared with the global or `package.loaded`, make a shallow copy
+            ffi::lua_newtable(state);
+            ffi::lua_pushnil(state);
+            while ffi::lua_next(state, -3) != 0 {
+                ffi::lua_pushvalue(state, -2);
+                ffi::lua_insert(state, -2);
+                ffi::lua_rawset(state, -4);
+            }
+            ffi::lua_remove(state, -2);
+        })?;
+        let library = Table(self.pop_ref());
+
+        globals.raw_set(modname, prev_global)?;
+        Ok(library)
+    }
+
     /// See [`Lua::try_set_app_data`]
     #[inline]
     pub(crate) fn try_set_app_data<T: MaybeSend + 'static>(&self, data: T) -> StdResult<Option<T>, T> {
         let extra = unsafe { &*self.extra.get() };
-        extra.app_data.try_insert(data)
+        extra.app_data.try_insert(None, data)
     }
 
     /// See [`Lua::app_data_ref`]
@@ -291,7 +379,7 @@ impl RawLua {
     #[inline]
     pub(crate) fn app_data_ref_unguarded<T: 'static>(&self) -> Option<AppDataRef<T>> {
         let extra = unsafe { &*self.extra.get() };
-        extra.app_data.borrow(None)
+        extra.app_data.borrow(None, None)
     }
 
     /// See [`Lua::app_data_mut`]
@@ -299,7 +387,7 @@ impl RawLua {
     #[inline]
     pub(crate) fn app_data_mut_unguarded<T: 'static>(&self) -> Option<AppDataRefMut<T>> {
         let extra = unsafe { &*self.extra.get() };
-        extra.app_data.borrow_mut(None)
+        extra.app_data.borrow_mut(None, None)
     }
 
     /// See [`Lua::create_registry_value`]
@@ -309,6 +397,13 @@ impl RawLua {
         Arc::ptr_eq(&key.unref_list, registry_unref_list)
     }
 
+    /// See [`LuaOptions::allow_bytecode`]
+    #[cfg(not(feature = "luau"))]
+    #[inline]
+    pub(crate) fn allow_bytecode(&self) -> bool {
+        unsafe { (*self.extra.get()).allow_bytecode }
+    }
+
     pub(crate) fn load_chunk(
         &self,
         name: Option<&CStr>,
@@ -321,7 +416,18 @@ impl RawLua {
             let _sg = StackGuard::new(state);
             check_stack(state, 3)?;
 
+            let chunk_name = name;
             let name = name.map(CStr::as_ptr).unwrap_or(ptr::null());
+            #[cfg(not(feature = "luau"))]
+            let mode = match self.allow_bytecode() {
+                true => mode,
+                false => Some(ChunkMode::Text),
+            };
+            // Lua treats a chunk as binary if it starts with the signature first byte
+            #[cfg(not(feature = "luau"))]
+            if mode != Some(ChunkMode::Text) && source.first() == Some(&ffi::LUA_SIGNATURE[0]) {
+                check_bytecode_header(source)?;
+            }
             let mode = match mode {
                 Some(ChunkMode::Binary) => cstr!("b"),
                 Some(ChunkMode::Text) => cstr!("t"),
@@ -335,10 +441,16 @@ impl RawLua {
                     self.load_chunk_inner(state, name, env, mode, source)
                 })?
             };
-            match status {
-                ffi::LUA_OK => Ok(Function(self.pop_ref())),
-                err => Err(pop_error(state, err)),
+            if status != ffi::LUA_OK {
+                return Err(pop_error(state, status));
             }
+            let func = Function(self.pop_ref());
+            if let (Some(observer), Some(chunk_name)) =
+                ((*self.extra.get()).load_observer.clone(), chunk_name)
+            {
+                observer(&chunk_name.to_string_lossy());
+            }
+            Ok(func)
         }
     }
 
@@ -373,6 +485,74 @@ impl RawLua {
         status
     }
 
+    /// Saves the hook of the current thread (interrupt for Luau) to be restored later.
+    pub(crate) unsafe fn save_hook(&self) -> Result<SavedHook> {
+        #[cfg(not(feature = "luau"))]
+        {
+            let state = self.state();
+            Ok(SavedHook {
+                state,
+                hook: ffi::lua_gethook(state),
+                mask: ffi::lua_gethookmask(state),
+                count: ffi::lua_gethookcount(state),
+                callback: self.thread_hook_callback(state)?,
+            })
+        }
+        #[cfg(feature = "luau")]
+        Ok(SavedHook {
+            interrupt: (*ffi::lua_callbacks(self.main_state())).interrupt,
+            callback: (*self.extra.get()).interrupt_callback.clone(),
+        })
+    }
+
+    /// Restores the hook (interrupt for Luau) previously saved using [`RawLua::save_hook`].
+    pub(crate) unsafe fn restore_hook(&self, saved: SavedHook) -> Result<()> {
+        #[cfg(not(feature = "luau"))]
+        {
+            ffi::lua_sethook(saved.state, saved.hook, saved.mask, saved.count);
+            self.set_thread_hook_callback(saved.state, saved.callback)
+        }
+        #[cfg(feature = "luau")]
+        {
+            (*self.extra.get()).interrupt_callback = saved.callback;
+            (*ffi::lua_callbacks(self.main_state())).interrupt = saved.interrupt;
+            Ok(())
+        }
+    }
+
+    /// Keeps the hook replaced by a profiler until the profiler is stopped using
+    /// [`RawLua::restore_profiler_hook`].
+    ///
+    /// Must be called right after setting the profiler hook. Returns the profiler hook id.
+    pub(crate) unsafe fn save_profiler_hook(&self, saved: SavedHook) -> Result<usize> {
+        let id = self
+            .save_hook()?
+            .callback
+            .as_ref()
+            .map(callback_id)
+            .unwrap_or_default();
+        (*self.extra.get()).profiler_saved_hooks.insert(id, saved);
+        Ok(id)
+    }
+
+    /// Restores the hook replaced by the profiler with the given hook id.
+    ///
+    /// If the profiler hook has been replaced in the meantime, the current hook is kept.
+    pub(crate) unsafe fn restore_profiler_hook(&self, id: usize) -> Result<()> {
+        let extra = self.extra.get();
+        let Some(saved) = (*extra).profiler_saved_hooks.remove(&id) else {
+            return Ok(());
+        };
+        #[cfg(not(feature = "luau"))]
+        let current = self.thread_hook_callback(saved.state)?;
+        #[cfg(feature = "luau")]
+        let current = (*extra).interrupt_callback.clone();
+        if current.as_ref().map(callback_id) == Some(id) {
+            self.restore_hook(saved)?;
+        }
+        Ok(())
+    }
+
     /// Sets a 'hook' function for a thread (coroutine).
     #[cfg(not(feature = "luau"))]
     pub(crate) unsafe fn set_thread_hook<F>(
@@ -380,7 +560,8 @@ impl RawLua {
         state: *mut ffi::lua_State,
         triggers: HookTriggers,
         callback: F,
-    ) where
+    ) -> Result<()>
+    where
         F: Fn(&Lua, Debug) -> Result<crate::VmState> + MaybeSend + 'static,
     {
         use crate::types::VmState;
@@ -388,14 +569,16 @@ impl RawLua {
 
         unsafe extern "C-unwind" fn hook_proc(state: *mut ffi::lua_State, ar: *mut ffi::lua_Debug) {
             let extra = ExtraData::get(state);
-            if (*extra).hook_thread != state {
-                // Hook was destined for a different thread, ignore
-                ffi::lua_sethook(state, None, 0, 0);
-                return;
-            }
+            ffi::lua_pushthread(state);
+            let hook_cb = match get_thread_hook_callback(state) {
+                Some(hook_cb) => hook_cb,
+                None => {
+                    // Hook was inherited by (or destined for) a different thread, ignore
+                    ffi::lua_sethook(state, None, 0, 0);
+                    return;
+                }
+            };
             let result = callback_error_ext(state, extra, move |extra, _| {
-                let hook_cb = (*extra).hook_callback.clone();
-                let hook_cb = mlua_expect!(hook_cb, "no hook callback set in hook_proc");
                 if Rc::strong_count(&hook_cb) > 2 {
                     return Ok(VmState::Continue); // Don't allow recursion
                 }
@@ -423,9 +606,159 @@ impl RawLua {
             }
         }
 
-        (*self.extra.get()).hook_callback = Some(Rc::new(callback));
-        (*self.extra.get()).hook_thread = state; // Mark for what thread the hook is set
+        self.set_thread_hook_callback(state, Some(Rc::new(callback)))?;
         ffi::lua_sethook(state, Some(hook_proc), triggers.mask(), triggers.count());
+        Ok(())
+    }
+
+    /// Removes a hook set for a thread (coroutine).
+    #[cfg(not(feature = "luau"))]
+    pub(crate) unsafe fn remove_thread_hook(&self, state: *mut ffi::lua_State) -> Result<()> {
+        ffi::lua_sethook(state, None, 0, 0);
+        self.set_thread_hook_callback(state, None)
+    }
+
+    /// Returns the hook callback set for a thread (coroutine), if any.
+    #[cfg(not(feature = "luau"))]
+    pub(crate) unsafe fn thread_hook_callback(
+        &self,
+        state: *mut ffi::lua_State,
+    ) -> Result<Option<HookCallback>> {
+        let _sg = StackGuard::new(self.state());
+        check_stack(self.state(), 4)?;
+
+        self.push_thread_state(state)?;
+        Ok(get_thread_hook_callback(self.state()))
+    }
+
+    /// Sets or removes the hook callback for a thread (coroutine).
+    ///
+    /// Callbacks are stored in a registry table with weak keys, so they are dropped together with
+    /// the thread they are set for.
+    #[cfg(not(feature = "luau"))]
+    pub(crate) unsafe fn set_thread_hook_callback(
+        &self,
+        state: *mut ffi::lua_State,
+        callback: Option<HookCallback>,
+    ) -> Result<()> {
+        let current = self.state();
+        let _sg = StackGuard::new(current);
+        check_stack(current, 5)?;
+
+        self.push_thread_state(state)?;
+        let key = &HOOK_CALLBACKS_KEY as *const u8 as *const c_void;
+        ffi::lua_rawgetp(current, ffi::LUA_REGISTRYINDEX, key);
+        if ffi::lua_istable(current, -1) != 0 {
+            ffi::lua_pushvalue(current, -2);
+            ffi::lua_rawget(current, -2);
+            // Replace the callback in place if the thread already has one
+            let ud = get_internal_userdata::<HookCallback>(current, -1, ptr::null());
+            if !ud.is_null() {
+                match callback {
+                    Some(callback) => *ud = callback,
+                    None => {
+                        ffi::lua_pop(current, 1);
+                        ffi::lua_pushvalue(current, -2);
+                        ffi::lua_pushnil(current);
+                        ffi::lua_rawset(current, -3);
+                    }
+                }
+                return Ok(());
+            }
+            ffi::lua_pop(current, 1);
+        }
+        let Some(callback) = callback else {
+            return Ok(());
+        };
+        ffi::lua_pop(current, 1);
+
+        push_internal_userdata(current, callback, true)?;
+        protect_lua!(current, 2, 0, |state| {
+            if ffi::lua_rawgetp(state, ffi::LUA_REGISTRYINDEX, key) != ffi::LUA_TTABLE {
+                ffi::lua_pop(state, 1);
+                ffi::lua_createtable(state, 0, 0);
+                ffi::lua_createtable(state, 0, 1);
+                ffi::lua_pushstring(state, cstr!("k"));
+                ffi::lua_setfield(state, -2, cstr!("__mode"));
+                ffi::lua_setmetatable(state, -2);
+                ffi::lua_pushvalue(state, -1);
+                ffi::lua_rawsetp(state, ffi::LUA_REGISTRYINDEX, key);
+            }
+            ffi::lua_insert(state, -3);
+            ffi::lua_rawset(state, -3);
+        })
+    }
+
+    /// Removes hook callbacks set for all threads.
+    #[cfg(not(feature = "luau"))]
+    pub(crate) unsafe fn clear_thread_hook_callbacks(&self) {
+        let state = self.state();
+        let _sg = StackGuard::new(state);
+        assert_stack(state, 1);
+
+        ffi::lua_pushnil(state);
+        ffi::lua_rawsetp(
+            state,
+            ffi::LUA_REGISTRYINDEX,
+            &HOOK_CALLBACKS_KEY as *const u8 as *const c_void,
+        );
+    }
+
+    // Pushes the thread (coroutine) onto the stack of the current thread.
+    #[cfg(not(feature = "luau"))]
+    unsafe fn push_thread_state(&self, thread_state: *mut ffi::lua_State) -> Result<()> {
+        let state = self.state();
+        if thread_state == state {
+            ffi::lua_pushthread(state);
+        } else {
+            check_stack(thread_state, 1)?;
+            ffi::lua_pushthread(thread_state);
+            ffi::lua_xmove(thread_state, state, 1);
+        }
+        Ok(())
+    }
+
+    /// See [`YieldPoint::check`]
+    ///
+    /// [`YieldPoint::check`]: crate::YieldPoint::check
+    pub(crate) unsafe fn check_yield_point(&self) -> Result<()> {
+        use std::rc::Rc;
+
+        #[cfg(not(feature = "luau"))]
+        {
+            let state = self.state();
+            // Only instruction count hooks set for the current thread are used for preemption
+            if ffi::lua_gethookmask(state) & ffi::LUA_MASKCOUNT == 0 {
+                return Ok(());
+            }
+            check_stack(state, 4)?;
+            ffi::lua_pushthread(state);
+            let hook_cb = match get_thread_hook_callback(state) {
+                Some(hook_cb) if Rc::strong_count(&hook_cb) <= 2 => hook_cb,
+                _ => return Ok(()), // Don't allow recursion
+            };
+            let mut ar: ffi::lua_Debug = mem::zeroed();
+            if ffi::lua_getstack(state, 0, &mut ar) == 0 {
+                return Ok(());
+            }
+            ar.event = ffi::LUA_HOOKCOUNT;
+            hook_cb(self.lua(), Debug::new(self, &mut ar))?;
+        }
+
+        #[cfg(feature = "luau")]
+        {
+            if (*ffi::lua_callbacks(self.main_state())).interrupt.is_none() {
+                return Ok(());
+            }
+            match (*self.extra.get()).interrupt_callback.clone() {
+                Some(interrupt_cb) if Rc::strong_count(&interrupt_cb) <= 2 => {
+                    interrupt_cb(self.lua())?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
     }
 
     /// See [`Lua::create_string`]
@@ -484,6 +817,28 @@ impl RawLua {
         Ok(Table(self.pop_ref()))
     }
 
+    pub(crate) unsafe fn create_sequence_from_slice<T: NumericElement>(&self, s: &[T]) -> Result<Table> {
+        let state = self.state();
+        let _sg = StackGuard::new(state);
+        check_stack(state, 4)?;
+
+        let protect = !self.unlikely_memory_error();
+        push_table(state, s.len(), 0, protect)?;
+        let push_all = |state: *mut ffi::lua_State| {
+            for (i, &n) in s.iter().enumerate() {
+                n.push_number(state);
+                ffi::lua_rawseti(state, -2, (i + 1) as Integer);
+            }
+        };
+        if protect {
+            protect_lua!(state, 1, 1, push_all)?;
+        } else {
+            push_all(state);
+        }
+
+        Ok(Table(self.pop_ref()))
+    }
+
     /// Wraps a Lua function into a new thread (or coroutine).
     ///
     /// Takes function by reference.
@@ -502,6 +857,35 @@ impl RawLua {
         Ok(thread)
     }
 
+    /// Returns the global environment table of the current thread.
+    ///
+    /// Uses the table cached on the ref stack when possible.
+    pub(crate) unsafe fn globals(&self) -> Table {
+        // The globals table of the current thread can be replaced (eg. using `setfenv` or by
+        // sandboxing), fallback to fetching it directly in this case.
+        #[cfg(any(feature = "lua51", feature = "luajit", feature = "luau"))]
+        {
+            let state = self.state();
+            let cached = ffi::lua_topointer(self.ref_thread(), ExtraData::GLOBALS_IDX);
+            if ffi::lua_topointer(state, ffi::LUA_GLOBALSINDEX) != cached {
+                let _sg = StackGuard::new(state);
+                assert_stack(state, 1);
+                ffi::lua_pushvalue(state, ffi::LUA_GLOBALSINDEX);
+                return Table(self.pop_ref());
+            }
+        }
+
+        ffi::lua_pushvalue(self.ref_thread(), ExtraData::GLOBALS_IDX);
+        Table(self.pop_ref_thread())
+    }
+
+    /// Updates the cached globals table from the main thread (eg. after enabling sandbox mode).
+    #[cfg(feature = "luau")]
+    pub(crate) unsafe fn refresh_globals(&self) {
+        ffi::lua_xpush(self.main_state(), self.ref_thread(), ffi::LUA_GLOBALSINDEX);
+        ffi::lua_replace(self.ref_thread(), ExtraData::GLOBALS_IDX);
+    }
+
     /// Wraps a Lua function into a new or recycled thread (coroutine).
     #[cfg(feature = "async")]
     pub(crate) unsafe fn create_recycled_thread(&self, func: &Function) -> Result<Thread> {
@@ -793,8 +1177,8 @@ impl RawLua {
         self.make_userdata_with_metatable(data, || {
             // Check if userdata/metatable is already registered
             let type_id = TypeId::of::<T>();
-            if let Some(&table_id) = (*self.extra.get()).registered_userdata_t.get(&type_id) {
-                return Ok(table_id as Integer);
+            if let Some(&info) = (*self.extra.get()).registered_userdata_t.get(&type_id) {
+                return Ok(info);
             }
 
             // Create a new metatable from `UserData` definition
@@ -812,8 +1196,8 @@ impl RawLua {
         self.make_userdata_with_metatable(data, || {
             // Check if userdata/metatable is already registered
             let type_id = TypeId::of::<T>();
-            if let Some(&table_id) = (*self.extra.get()).registered_userdata_t.get(&type_id) {
-                return Ok(table_id as Integer);
+            if let Some(&info) = (*self.extra.get()).registered_userdata_t.get(&type_id) {
+                return Ok(info);
             }
 
             // Check if metatable creation is pending or create an empty metatable otherwise
@@ -828,15 +1212,16 @@ impl RawLua {
     unsafe fn make_userdata_with_metatable<T>(
         &self,
         data: UserDataStorage<T>,
-        get_metatable_id: impl FnOnce() -> Result<Integer>,
+        get_metatable_info: impl FnOnce() -> Result<UserDataMetatableInfo>,
     ) -> Result<AnyUserData> {
         let state = self.state();
         let _sg = StackGuard::new(state);
-        check_stack(state, 3)?;
+        check_stack(state, 4)?;
 
         // We push metatable first to ensure having correct metatable with `__gc` method
         ffi::lua_pushnil(state);
-        ffi::lua_rawgeti(state, ffi::LUA_REGISTRYINDEX, get_metatable_id()?);
+        let mt_info = get_metatable_info()?;
+        ffi::lua_rawgeti(state, ffi::LUA_REGISTRYINDEX, mt_info.id as Integer);
         let protect = !self.unlikely_memory_error();
         crate::util::push_userdata(state, data, protect)?;
         ffi::lua_replace(state, -3);
@@ -854,12 +1239,22 @@ impl RawLua {
             ffi::lua_setuservalue(state, -2);
         }
 
+        #[cfg(not(feature = "luau"))]
+        if let Some(priority) = mt_info.finalizer_priority {
+            push_finalizer_queue_entry(state, priority)?;
+        }
+
         Ok(AnyUserData(self.pop_ref()))
     }
 
-    pub(crate) unsafe fn create_userdata_metatable(&self, registry: RawUserDataRegistry) -> Result<Integer> {
+    pub(crate) unsafe fn create_userdata_metatable(
+        &self,
+        registry: RawUserDataRegistry,
+    ) -> Result<UserDataMetatableInfo> {
         let state = self.state();
         let type_id = registry.type_id;
+        #[cfg(not(feature = "luau"))]
+        let finalizer_priority = registry.finalizer_priority;
 
         self.push_userdata_metatable(registry)?;
 
@@ -868,12 +1263,17 @@ impl RawLua {
             ffi::luaL_ref(state, ffi::LUA_REGISTRYINDEX)
         })?;
 
+        let info = UserDataMetatableInfo {
+            id,
+            #[cfg(not(feature = "luau"))]
+            finalizer_priority,
+        };
         if let Some(type_id) = type_id {
-            (*self.extra.get()).registered_userdata_t.insert(type_id, id);
+            (*self.extra.get()).registered_userdata_t.insert(type_id, info);
         }
         self.register_userdata_metatable(mt_ptr, type_id);
 
-        Ok(id as Integer)
+        Ok(info)
     }
 
     pub(crate) unsafe fn push_userdata_metatable(&self, mut registry: RawUserDataRegistry) -> Result<()> {
@@ -934,13 +1334,53 @@ impl RawLua {
         }
 
         let mut field_getters_index = None;
-        let field_getters_nrec = registry.field_getters.len() + registry.fields.len();
+        let field_getters_nrec =
+            registry.field_getters.len() + registry.cached_field_getters.len() + registry.fields.len();
         if field_getters_nrec > 0 {
             push_table(state, 0, field_getters_nrec, true)?;
             for (k, m) in registry.field_getters {
                 self.push(self.create_callback(m)?)?;
                 rawset_field(state, -2, &k)?;
             }
+            for (k, m) in registry.cached_field_getters {
+                // Stores the getter result in the uservalue table, using the getter itself as a key
+                unsafe extern "C-unwind" fn cached_field(state: *mut ffi::lua_State) -> c_int {
+                    let getter = ffi::lua_upvalueindex(1);
+                    // Cached values are not returned for destructed userdata, call the getter to
+                    // raise an error instead
+                    let mt_ptr = get_metatable_ptr(state, 1);
+                    let destructed = Some(TypeId::of::<DestructedUserdata>());
+                    if (*ExtraData::get(state)).registered_userdata_mt.get(&mt_ptr) == Some(&destructed) {
+                        ffi::lua_pushvalue(state, getter);
+                        ffi::lua_pushvalue(state, 1);
+                        ffi::lua_call(state, 1, 1);
+                        return 1;
+                    }
+                    if ffi::lua_getuservalue(state, 1) != ffi::LUA_TTABLE {
+                        ffi::lua_pop(state, 1);
+                        ffi::lua_newtable(state);
+                        ffi::lua_pushvalue(state, -1);
+                        ffi::lua_setuservalue(state, 1);
+                    }
+                    ffi::lua_pushvalue(state, getter);
+                    if ffi::lua_rawget(state, -2) != ffi::LUA_TNIL {
+                        return 1;
+                    }
+                    ffi::lua_pop(state, 1);
+                    ffi::lua_pushvalue(state, getter);
+                    ffi::lua_pushvalue(state, 1);
+                    ffi::lua_call(state, 1, 1);
+                    ffi::lua_pushvalue(state, getter);
+                    ffi::lua_pushvalue(state, -2);
+                    ffi::lua_rawset(state, -4);
+                    1
+                }
+                self.push(self.create_callback(m)?)?;
+                protect_lua!(state, 1, 1, fn(state) {
+                    ffi::lua_pushcclosure(state, cached_field, 1);
+                })?;
+                rawset_field(state, -2, &k)?;
+            }
             for (k, v) in registry.fields {
                 unsafe extern "C-unwind" fn return_field(state: *mut ffi::lua_State) -> c_int {
                     ffi::lua_pushvalue(state, ffi::lua_upvalueindex(1));
@@ -1283,6 +1723,38 @@ impl RawLua {
     }
 }
 
+// Returns the module name and open function of a single standard library
+fn std_lib_opener(lib: StdLib) -> Option<(&'static str, ffi::lua_CFunction)> {
+    Some(match lib {
+        #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52", feature = "luau"))]
+        StdLib::COROUTINE => (ffi::LUA_COLIBNAME, ffi::luaopen_coroutine),
+        StdLib::TABLE => (ffi::LUA_TABLIBNAME, ffi::luaopen_table),
+        #[cfg(not(feature = "luau"))]
+        StdLib::IO => (ffi::LUA_IOLIBNAME, ffi::luaopen_io),
+        StdLib::OS => (ffi::LUA_OSLIBNAME, ffi::luaopen_os),
+        StdLib::STRING => (ffi::LUA_STRLIBNAME, ffi::luaopen_string),
+        #[cfg(any(feature = "lua54", feature = "lua53", feature = "luau"))]
+        StdLib::UTF8 => (ffi::LUA_UTF8LIBNAME, ffi::luaopen_utf8),
+        #[cfg(any(feature = "lua52", feature = "luau"))]
+        StdLib::BIT => (ffi::LUA_BITLIBNAME, ffi::luaopen_bit32),
+        #[cfg(feature = "luajit")]
+        StdLib::BIT => (ffi::LUA_BITLIBNAME, ffi::luaopen_bit),
+        #[cfg(feature = "luau")]
+        StdLib::BUFFER => (ffi::LUA_BUFFERLIBNAME, ffi::luaopen_buffer),
+        #[cfg(feature = "luau")]
+        StdLib::VECTOR => (ffi::LUA_VECLIBNAME, ffi::luaopen_vector),
+        StdLib::MATH => (ffi::LUA_MATHLIBNAME, ffi::luaopen_math),
+        StdLib::DEBUG => (ffi::LUA_DBLIBNAME, ffi::luaopen_debug),
+        #[cfg(not(feature = "luau"))]
+        StdLib::PACKAGE => (ffi::LUA_LOADLIBNAME, ffi::luaopen_package),
+        #[cfg(feature = "luajit")]
+        StdLib::JIT => (ffi::LUA_JITLIBNAME, ffi::luaopen_jit),
+        #[cfg(feature = "luajit")]
+        StdLib::FFI => (ffi::LUA_FFILIBNAME, ffi::luaopen_ffi),
+        _ => return None,
+    })
+}
+
 // Uses 3 stack spaces
 unsafe fn load_std_libs(state: *mut ffi::lua_State, libs: StdLib) -> Result<()> {
     #[inline(always)]
@@ -1421,3 +1893,29 @@ unsafe fn load_std_libs(state: *mut ffi::lua_State, libs: StdLib) -> Result<()>
 
     Ok(())
 }
+
+// Returns an identifier of the hook (interrupt for Luau) callback
+fn callback_id<T: ?Sized>(callback: &std::rc::Rc<T>) -> usize {
+    std::rc::Rc::as_ptr(callback) as *const () as usize
+}
+
+// Returns the hook callback set for the thread on top of the stack, if any. Pops the thread.
+// Uses 4 stack spaces (including the thread), does not call checkstack.
+#[cfg(not(feature = "luau"))]
+unsafe fn get_thread_hook_callback(state: *mut ffi::lua_State) -> Option<HookCallback> {
+    let key = &HOOK_CALLBACKS_KEY as *const u8 as *const c_void;
+    if ffi::lua_rawgetp(state, ffi::LUA_REGISTRYINDEX, key) != ffi::LUA_TTABLE {
+        ffi::lua_pop(state, 2);
+        return None;
+    }
+    ffi::lua_insert(state, -2);
+    ffi::lua_rawget(state, -2);
+    let hook_cb = get_internal_userdata::<HookCallback>(state, -1, ptr::null())
+        .as_ref()
+        .cloned();
+    ffi::lua_pop(state, 2);
+    hook_cb
+}
+
+#[cfg(not(feature = "luau"))]
+static HOOK_CALLBACKS_KEY: u8 = 0;
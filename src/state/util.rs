@@ -107,6 +107,20 @@ where
             prealloc_failure.release(state, extra);
             r
         }
+        Ok(Err(Error::ErrorObject(value))) => {
+            // Raise the error object as is
+            prealloc_failure.release(state, extra);
+            ffi::lua_settop(state, 0);
+            {
+                let rawlua = (*extra).raw_lua();
+                let _guard = StateGuard::new(rawlua, state);
+                if value.push_into_stack(rawlua).is_err() {
+                    ffi::lua_pushnil(state);
+                }
+                drop(value);
+            }
+            ffi::lua_error(state)
+        }
         Ok(Err(err)) => {
             let wrapped_error = prealloc_failure.r#use(state, extra);
 
@@ -139,6 +153,23 @@ where
     }
 }
 
+// Pops a non-string error object (without `__tostring` metamethod) from the top of the stack.
+// Returns `None` and leaves the stack untouched if the error can be represented as a string.
+// Uses 2 stack spaces, does not call checkstack.
+pub(crate) unsafe fn pop_error_object(state: *mut ffi::lua_State) -> Option<Error> {
+    if !util::is_error_object(state, -1) {
+        return None;
+    }
+    let extra = ExtraData::get(state);
+    if extra.is_null() {
+        return None;
+    }
+    let lua = (*extra).raw_lua();
+    let _guard = StateGuard::new(lua, state);
+    let value = lua.pop_value();
+    Some(Error::with_value(value))
+}
+
 pub(super) unsafe fn ref_stack_pop(extra: *mut ExtraData) -> c_int {
     let extra = &mut *extra;
     if let Some(free) = extra.ref_free.pop() {
@@ -83,6 +83,38 @@ impl StdLib {
     pub fn contains(self, lib: Self) -> bool {
         (self & lib).0 != 0
     }
+
+    /// Returns the library flag corresponding to the given library name (eg. `"table"`), if the
+    /// library is available in the current Lua backend.
+    pub(crate) fn from_name(name: &str) -> Option<StdLib> {
+        Some(match name {
+            #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52", feature = "luau"))]
+            "coroutine" => StdLib::COROUTINE,
+            "table" => StdLib::TABLE,
+            #[cfg(not(feature = "luau"))]
+            "io" => StdLib::IO,
+            "os" => StdLib::OS,
+            "string" => StdLib::STRING,
+            #[cfg(any(feature = "lua54", feature = "lua53", feature = "luau"))]
+            "utf8" => StdLib::UTF8,
+            #[cfg(any(feature = "lua52", feature = "luau"))]
+            "bit32" => StdLib::BIT,
+            #[cfg(feature = "luajit")]
+            "bit" => StdLib::BIT,
+            "math" => StdLib::MATH,
+            "package" => StdLib::PACKAGE,
+            #[cfg(feature = "luau")]
+            "buffer" => StdLib::BUFFER,
+            #[cfg(feature = "luau")]
+            "vector" => StdLib::VECTOR,
+            #[cfg(feature = "luajit")]
+            "jit" => StdLib::JIT,
+            #[cfg(feature = "luajit")]
+            "ffi" => StdLib::FFI,
+            "debug" => StdLib::DEBUG,
+            _ => return None,
+        })
+    }
 }
 
 impl BitAnd for StdLib {
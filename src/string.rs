@@ -6,9 +6,12 @@ use std::string::String as StdString;
 use std::{cmp, fmt, slice, str};
 
 use crate::error::{Error, Result};
+use crate::function::Function;
+use crate::multi::MultiValue;
 use crate::state::Lua;
-use crate::traits::IntoLua;
-use crate::types::{LuaType, ValueRef};
+use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti};
+use crate::types::{Integer, LuaType, ValueRef};
+use crate::util::{check_stack, StackGuard};
 use crate::value::Value;
 
 #[cfg(feature = "serialize")]
@@ -144,6 +147,83 @@ impl String {
     pub fn to_pointer(&self) -> *const c_void {
         self.0.to_pointer()
     }
+
+    /// Returns a copy of this string with all lowercase letters changed to uppercase.
+    ///
+    /// Calls the `string.upper` function of the Lua `string` library (the library must be
+    /// loaded).
+    pub fn upper(&self) -> Result<String> {
+        self.call_string_lib("upper", ())
+    }
+
+    /// Returns a copy of this string with all uppercase letters changed to lowercase.
+    ///
+    /// Calls the `string.lower` function of the Lua `string` library (the library must be
+    /// loaded).
+    pub fn lower(&self) -> Result<String> {
+        self.call_string_lib("lower", ())
+    }
+
+    /// Returns the byte at (1-based) position `i`, or `None` if the position is out of range.
+    ///
+    /// Negative positions count from the end of the string, the same as `string.byte`.
+    pub fn byte_at(&self, i: Integer) -> Result<Option<u8>> {
+        self.call_string_lib("byte", i)
+    }
+
+    /// Looks for the first match of `pattern` in this string.
+    ///
+    /// Returns the (1-based, inclusive) start and end positions of the match, or `None` if not
+    /// found. Calls `string.find`, so `pattern` uses the Lua pattern syntax.
+    pub fn find(&self, pattern: impl IntoLua) -> Result<Option<(Integer, Integer)>> {
+        let (start, end) = self.call_string_lib::<(Option<Integer>, Option<Integer>)>("find", pattern)?;
+        Ok(start.zip(end))
+    }
+
+    /// Looks for the first match of `pattern` in this string and returns its captures.
+    ///
+    /// If `pattern` has no captures, the whole match is returned. Position captures (`()`) are
+    /// returned as strings. Returns `None` if there is no match.
+    /// Calls `string.match`, so `pattern` uses the Lua pattern syntax.
+    pub fn match_pattern(&self, pattern: impl IntoLua) -> Result<Option<Vec<String>>> {
+        let captures = self.call_string_lib::<MultiValue>("match", pattern)?;
+        if captures.front().map(|v| v.is_nil()).unwrap_or(true) {
+            return Ok(None);
+        }
+        let lua = self.0.lua.upgrade();
+        captures
+            .into_iter()
+            .map(|v| String::from_lua(v, &lua))
+            .collect::<Result<_>>()
+            .map(Some)
+    }
+
+    // Calls function `name` from the Lua `string` library with this string as the first argument
+    fn call_string_lib<R: FromLuaMulti>(&self, name: &str, args: impl IntoLuaMulti) -> Result<R> {
+        let lua = self.0.lua.upgrade();
+        // The `string` library sets itself as `__index` of the strings metatable
+        let metatable = unsafe {
+            let rawlua = lua.lock();
+            let state = rawlua.state();
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+
+            rawlua.push_ref(&self.0);
+            match ffi::lua_getmetatable(state, -1) {
+                0 => None,
+                _ => Some(rawlua.pop_value()),
+            }
+        };
+        let func = match metatable {
+            Some(Value::Table(mt)) => match mt.raw_get::<Value>("__index")? {
+                Value::Table(lib) => lib.raw_get::<Option<Function>>(name)?,
+                _ => None,
+            },
+            _ => None,
+        };
+        let func = func.ok_or_else(|| Error::runtime("string library is not loaded"))?;
+        func.call((self, args.into_lua_multi(&lua)?))
+    }
 }
 
 impl fmt::Debug for String {
@@ -325,6 +405,16 @@ pub struct BorrowedBytes<'a> {
     pub(crate) _lua: Lua,
 }
 
+impl BorrowedBytes<'_> {
+    /// Returns the bytes as a [`bstr::BStr`] slice.
+    ///
+    /// Unlike converting to `&str`, this does not validate UTF-8 and preserves arbitrary bytes.
+    #[inline(always)]
+    pub fn as_bstr(&self) -> &bstr::BStr {
+        bstr::BStr::new(self.buf)
+    }
+}
+
 impl Deref for BorrowedBytes<'_> {
     type Target = [u8];
 
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::marker::PhantomData;
 use std::os::raw::{c_int, c_void};
@@ -6,9 +6,9 @@ use std::string::String as StdString;
 
 use crate::error::{Error, Result};
 use crate::function::Function;
-use crate::state::{LuaGuard, RawLua};
-use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti, ObjectLike};
-use crate::types::{Integer, LuaType, ValueRef};
+use crate::state::{Lua, LuaGuard, RawLua};
+use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti, NumericElement, ObjectLike};
+use crate::types::{Integer, LuaType, MaybeSend, Number, ValueRef};
 use crate::util::{assert_stack, check_stack, get_metatable_ptr, StackGuard};
 use crate::value::{Nil, Value};
 
@@ -23,7 +23,10 @@ use {
 };
 
 /// Handle to an internal Lua table.
-#[derive(Clone, PartialEq)]
+///
+/// Equality and hashing use reference identity of the table and never invoke the `__eq`
+/// metamethod (see [`Table::equals`] for that). The hash is stable only while the table is alive.
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Table(pub(crate) ValueRef);
 
 impl Table {
@@ -265,6 +268,21 @@ impl Table {
         }
     }
 
+    /// Sets a key-value pair without invoking metamethods, ensuring that the table does not have
+    /// a `__newindex` metamethod.
+    ///
+    /// Unlike [`Table::raw_set`], returns an error if the table has a `__newindex` metamethod
+    /// instead of silently bypassing it. This is useful to make sure that a plain data write does
+    /// not have unexpected side effects.
+    pub fn set_checked(&self, key: impl IntoLua, value: impl IntoLua) -> Result<()> {
+        if let Some(mt) = self.metatable() {
+            if !mt.raw_get::<Value>("__newindex")?.is_nil() {
+                return Err(Error::runtime("table has a __newindex metamethod"));
+            }
+        }
+        self.raw_set(key, value)
+    }
+
     /// Gets the value associated to `key` without invoking metamethods.
     pub fn raw_get<V: FromLua>(&self, key: impl IntoLua) -> Result<V> {
         let lua = self.0.lua.lock();
@@ -281,6 +299,178 @@ impl Table {
         }
     }
 
+    /// Gets the integer associated to `key` without invoking metamethods.
+    ///
+    /// This is a fast path that reads the value directly from the Lua stack. Returns `None` if the
+    /// value is not a number or cannot be represented as an integer (strings are not converted).
+    pub fn get_integer(&self, key: impl IntoLua) -> Result<Option<Integer>> {
+        self.raw_get_scalar(key, |state| unsafe {
+            if ffi::lua_type(state, -1) != ffi::LUA_TNUMBER {
+                return None;
+            }
+            let mut isint = 0;
+            let i = ffi::lua_tointegerx(state, -1, &mut isint);
+            (isint != 0).then_some(i)
+        })
+    }
+
+    /// Gets the number associated to `key` without invoking metamethods.
+    ///
+    /// This is a fast path that reads the value directly from the Lua stack. Returns `None` if the
+    /// value is not a number (strings are not converted).
+    pub fn get_number(&self, key: impl IntoLua) -> Result<Option<Number>> {
+        self.raw_get_scalar(key, |state| unsafe {
+            match ffi::lua_type(state, -1) {
+                ffi::LUA_TNUMBER => Some(ffi::lua_tonumber(state, -1)),
+                _ => None,
+            }
+        })
+    }
+
+    /// Gets the boolean associated to `key` without invoking metamethods.
+    ///
+    /// This is a fast path that reads the value directly from the Lua stack. Returns `None` if the
+    /// value is not a boolean.
+    pub fn get_boolean(&self, key: impl IntoLua) -> Result<Option<bool>> {
+        self.raw_get_scalar(key, |state| unsafe {
+            match ffi::lua_type(state, -1) {
+                ffi::LUA_TBOOLEAN => Some(ffi::lua_toboolean(state, -1) != 0),
+                _ => None,
+            }
+        })
+    }
+
+    fn raw_get_scalar<T>(
+        &self,
+        key: impl IntoLua,
+        read: impl FnOnce(*mut ffi::lua_State) -> Option<T>,
+    ) -> Result<Option<T>> {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 3)?;
+
+            lua.push_ref(&self.0);
+            key.push_into_stack(&lua)?;
+            ffi::lua_rawget(state, -2);
+            Ok(read(state))
+        }
+    }
+
+    /// Gets the value associated to `key`, inserting the value returned by `f` if the slot is `nil`.
+    ///
+    /// `f` is called only when the key is missing. The key is pushed only once and metamethods
+    /// are not invoked. If the produced value cannot be converted, nothing is inserted.
+    pub fn get_or_insert_with<V: IntoLua + FromLua>(
+        &self,
+        key: impl IntoLua,
+        f: impl FnOnce() -> V,
+    ) -> Result<V> {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 5)?;
+
+            lua.push_ref(&self.0);
+            key.push_into_stack(&lua)?;
+            ffi::lua_pushvalue(state, -1);
+            if ffi::lua_rawget(state, -3) != ffi::LUA_TNIL {
+                return V::from_stack(-1, &lua);
+            }
+            ffi::lua_pop(state, 1);
+
+            f().push_into_stack(&lua)?;
+            let value = V::from_stack(-1, &lua)?;
+            if lua.unlikely_memory_error() {
+                ffi::lua_rawset(state, -3);
+            } else {
+                protect_lua!(state, 3, 0, fn(state) ffi::lua_rawset(state, -3))?;
+            }
+            Ok(value)
+        }
+    }
+
+    /// Returns the table stored at `key`, creating (and raw-setting) a new empty table if the slot
+    /// is `nil`.
+    ///
+    /// Returns an error if the slot holds a value of any other type.
+    /// Metamethods are not invoked.
+    pub fn get_or_create_table(&self, key: impl IntoLua) -> Result<Table> {
+        let lua = self.0.lua.lock();
+        let key = key.into_lua(lua.lua())?;
+        match self.raw_get::<Value>(&key)? {
+            Value::Table(table) => Ok(table),
+            Value::Nil => {
+                let table = lua.lua().create_table()?;
+                self.raw_set(key, &table)?;
+                Ok(table)
+            }
+            value => Err(Error::runtime(format!(
+                "cannot create table at key: slot holds a {} value",
+                value.type_name()
+            ))),
+        }
+    }
+
+    /// Replaces the function stored at `key` with `new`, returning the previously stored function.
+    ///
+    /// Returns `None` if the slot was `nil`, or an error if it holds a value that is not a function.
+    /// Metamethods are not invoked.
+    pub fn replace_function(&self, key: impl IntoLua, new: Function) -> Result<Option<Function>> {
+        let lua = self.0.lua.lock();
+        let key = key.into_lua(lua.lua())?;
+        let old = match self.raw_get::<Value>(&key)? {
+            Value::Function(func) => Some(func),
+            Value::Nil => None,
+            value => {
+                return Err(Error::runtime(format!(
+                    "cannot replace function at key: slot holds a {} value",
+                    value.type_name()
+                )))
+            }
+        };
+        self.raw_set(key, new)?;
+        Ok(old)
+    }
+
+    /// Reads the sequence part of the table as numbers, appending them to `out`.
+    ///
+    /// Reads elements `1..=n` where `n` is the raw length of the table, without invoking
+    /// metamethods. Strings convertible to numbers are accepted (as in Lua arithmetic).
+    /// Returns an error if any element cannot be converted to `T`, leaving `out` unchanged.
+    ///
+    /// This is faster than reading elements one by one for large numeric arrays.
+    pub fn read_numeric_slice<T: NumericElement>(&self, out: &mut Vec<T>) -> Result<()> {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+
+            lua.push_ref(&self.0);
+            let len = ffi::lua_rawlen(state, -1);
+            let mut values = Vec::with_capacity(len);
+            for i in 1..=len {
+                ffi::lua_rawgeti(state, -1, i as Integer);
+                match T::to_number(state, -1) {
+                    Some(n) => values.push(n),
+                    None => {
+                        return Err(Error::FromLuaConversionError {
+                            from: lua.stack_value(-1, None).type_name(),
+                            to: T::TYPE_NAME.to_string(),
+                            message: Some(format!("invalid sequence element #{i}")),
+                        })
+                    }
+                }
+                ffi::lua_pop(state, 1);
+            }
+            out.append(&mut values);
+        }
+        Ok(())
+    }
+
     /// Inserts element value at position `idx` to the table, shifting up the elements from
     /// `table[idx]`.
     ///
@@ -464,6 +654,21 @@ impl Table {
         unsafe { ffi::lua_rawlen(lua.ref_thread(), self.0.index) }
     }
 
+    /// Returns the raw border of the table as `usize`, without invoking the `__len` metamethod.
+    ///
+    /// Unlike [`Table::raw_len`], returns an error if the border reported by Lua cannot be
+    /// represented as `usize` (eg. on 32-bit targets) instead of silently truncating it.
+    pub fn len_usize(&self) -> Result<usize> {
+        let lua = self.0.lua.lock();
+        let len = unsafe { ffi::lua_rawlen(lua.ref_thread(), self.0.index) };
+        #[allow(clippy::useless_conversion)]
+        usize::try_from(len).map_err(|_| Error::FromLuaConversionError {
+            from: "integer",
+            to: "usize".to_string(),
+            message: Some("table length out of range".to_string()),
+        })
+    }
+
     /// Returns `true` if the table is empty, without invoking metamethods.
     ///
     /// It checks both the array part and the hash part.
@@ -545,6 +750,42 @@ impl Table {
         }
     }
 
+    /// Sets or removes the metatable of this table, returning the previous metatable (if any).
+    ///
+    /// Unlike [`Table::set_metatable`], this returns an error if the current metatable is
+    /// protected by a `__metatable` field, or (in Luau) if the table is readonly.
+    pub fn replace_metatable(&self, metatable: Option<Table>) -> Result<Option<Table>> {
+        let lua = self.0.lua.lock();
+        #[cfg(feature = "luau")]
+        self.check_readonly_write(&lua)?;
+
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 3)?;
+
+            lua.push_ref(&self.0);
+            let previous = if ffi::lua_getmetatable(state, -1) != 0 {
+                ffi::lua_pushstring(state, cstr!("__metatable"));
+                if ffi::lua_rawget(state, -2) != ffi::LUA_TNIL {
+                    return Err(Error::runtime("cannot change a protected metatable"));
+                }
+                ffi::lua_pop(state, 1);
+                Some(Table(lua.pop_ref()))
+            } else {
+                None
+            };
+
+            if let Some(metatable) = metatable {
+                lua.push_ref(&metatable.0);
+            } else {
+                ffi::lua_pushnil(state);
+            }
+            ffi::lua_setmetatable(state, -2);
+            Ok(previous)
+        }
+    }
+
     /// Returns true if the table has metatable attached.
     #[doc(hidden)]
     #[inline]
@@ -645,6 +886,98 @@ impl Table {
         }
     }
 
+    /// Collects all pairs of the table, sorted by key.
+    ///
+    /// Unlike [`Table::pairs`], the result does not depend on the (unspecified) table iteration
+    /// order, which is useful for tests and canonical serialization.
+    ///
+    /// All keys must be convertible to the single orderable type `K` (eg. [`StdString`] for
+    /// string keys, or [`Integer`] for sequence indices). Tables with mixed key types return a
+    /// conversion error. Does not invoke the `__pairs` metamethod.
+    ///
+    /// [`StdString`]: std::string::String
+    pub fn pairs_sorted<K: FromLua + Ord, V: FromLua>(&self) -> Result<Vec<(K, V)>> {
+        let mut pairs = self.pairs::<K, V>().collect::<Result<Vec<_>>>()?;
+        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(pairs)
+    }
+
+    /// Creates a recursive copy of the table.
+    ///
+    /// Nested tables (both keys and values) are duplicated, while other values (including
+    /// functions and userdata) are shared by reference. Metatables are shared as well.
+    /// Tables referenced multiple times (including cycles) are copied only once, preserving the
+    /// original structure. Metamethods are not invoked.
+    pub fn deep_clone(&self) -> Result<Table> {
+        fn clone_value(
+            lua: &Lua,
+            value: Value,
+            visited: &mut HashMap<*const c_void, Table>,
+        ) -> Result<Value> {
+            match value {
+                Value::Table(table) => clone_table(lua, &table, visited).map(Value::Table),
+                value => Ok(value),
+            }
+        }
+
+        fn clone_table(
+            lua: &Lua,
+            table: &Table,
+            visited: &mut HashMap<*const c_void, Table>,
+        ) -> Result<Table> {
+            if let Some(copy) = visited.get(&table.to_pointer()) {
+                return Ok(copy.clone());
+            }
+            let copy = lua.create_table_with_capacity(table.raw_len(), 0)?;
+            visited.insert(table.to_pointer(), copy.clone());
+            table.for_each::<Value, Value>(|key, value| {
+                let key = clone_value(lua, key, visited)?;
+                let value = clone_value(lua, value, visited)?;
+                copy.raw_set(key, value)
+            })?;
+            copy.set_metatable(table.metatable());
+            Ok(copy)
+        }
+
+        let lua = self.0.lua.lock();
+        clone_table(lua.lua(), self, &mut HashMap::new())
+    }
+
+    /// Converts this table into a Rust struct.
+    ///
+    /// This is a shortcut for [`FromLua::from_lua`] with the table as a value, intended to be used
+    /// with types deriving [`FromLua`] using the `#[mlua(from_table)]` attribute.
+    ///
+    /// [`FromLua`]: crate::FromLua
+    pub fn into_struct<T: FromLua>(self) -> Result<T> {
+        let lua = self.0.lua.upgrade();
+        T::from_lua(Value::Table(self), &lua)
+    }
+
+    /// Computes the difference between this table and `other`, by keys.
+    ///
+    /// Values are compared using raw equality, so nested tables are considered changed only if
+    /// they are different table objects. Metamethods are not invoked.
+    /// The order of keys in the result is unspecified.
+    pub fn diff(&self, other: &Table) -> Result<TableDiff> {
+        let mut diff = TableDiff::default();
+        self.for_each(|key: Value, value: Value| {
+            match other.raw_get::<Value>(&key)? {
+                Value::Nil => diff.removed.push(key),
+                other_value if other_value != value => diff.changed.push(key),
+                _ => {}
+            }
+            Ok(())
+        })?;
+        other.for_each(|key: Value, _: Value| {
+            if self.raw_get::<Value>(&key)?.is_nil() {
+                diff.added.push(key);
+            }
+            Ok(())
+        })?;
+        Ok(diff)
+    }
+
     /// Iterates over the pairs of the table, invoking the given closure on each pair.
     ///
     /// This method is similar to [`Table::pairs`], but optimized for performance.
@@ -710,6 +1043,32 @@ impl Table {
         }
     }
 
+    /// Returns a 0-based, bounds-checked view over the sequence part of the table.
+    ///
+    /// Elements are read using raw access (without invoking metamethods) and no values are
+    /// copied up front. Index `i` of the view corresponds to the Lua index `i + 1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result, Table};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let table: Table = lua.load("{10, 20, 30}").eval()?;
+    /// let seq = table.sequence::<i32>();
+    /// assert_eq!(seq.len(), 3);
+    /// assert_eq!(seq.get(0)?, Some(10));
+    /// assert_eq!(seq.get(3)?, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sequence<V: FromLua>(&self) -> SequenceView<'_, V> {
+        SequenceView {
+            table: self,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Iterates over the sequence part of the table, invoking the given closure on each value.
     #[doc(hidden)]
     pub fn for_each_value<V>(&self, mut f: impl FnMut(V) -> Result<()>) -> Result<()>
@@ -1187,6 +1546,159 @@ where
     }
 }
 
+/// A 0-based view over the sequence part of a [`Table`].
+///
+/// This struct is created by the [`Table::sequence`] method.
+pub struct SequenceView<'a, V> {
+    table: &'a Table,
+    _phantom: PhantomData<V>,
+}
+
+impl<V: FromLua> SequenceView<'_, V> {
+    /// Returns the element at position `idx` (0-based), or `None` if it is out of bounds.
+    ///
+    /// The bounds are checked against the current [`Table::raw_len`].
+    pub fn get(&self, idx: usize) -> Result<Option<V>> {
+        let lua = self.table.0.lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _sg = StackGuard::new(state);
+            check_stack(state, 2)?;
+
+            lua.push_ref(&self.table.0);
+            if idx >= ffi::lua_rawlen(state, -1) {
+                return Ok(None);
+            }
+            ffi::lua_rawgeti(state, -1, (idx + 1) as _);
+            V::from_stack(-1, &lua).map(Some)
+        }
+    }
+
+    /// Returns the number of elements in the sequence, as reported by [`Table::raw_len`].
+    pub fn len(&self) -> usize {
+        self.table.raw_len()
+    }
+
+    /// Returns `true` if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the elements of the sequence.
+    pub fn iter(&self) -> impl Iterator<Item = Result<V>> + '_ {
+        (0..self.len()).map_while(move |i| self.get(i).transpose())
+    }
+}
+
+impl<V> fmt::Debug for SequenceView<'_, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SequenceView").field(self.table).finish()
+    }
+}
+
+/// Keys that differ between two tables, as returned by [`Table::diff`].
+#[derive(Clone, Debug, Default)]
+pub struct TableDiff {
+    /// Keys present only in the other table.
+    pub added: Vec<Value>,
+    /// Keys present only in this table.
+    pub removed: Vec<Value>,
+    /// Keys present in both tables, with different values.
+    pub changed: Vec<Value>,
+}
+
+impl TableDiff {
+    /// Returns `true` if the tables have the same keys and values.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// A builder for assembling a table with fields, methods and a metatable.
+///
+/// This struct is created by the [`Lua::table_builder`] method.
+/// Entries are accumulated and written to a new table (without invoking metamethods) when
+/// [`TableBuilder::build`] is called. Any conversion error is deferred until then.
+///
+/// [`Lua::table_builder`]: crate::Lua::table_builder
+#[must_use = "call `build` to create the table"]
+pub struct TableBuilder<'a> {
+    lua: &'a Lua,
+    entries: Result<Vec<(Value, Value)>>,
+    metatable: Option<Table>,
+}
+
+impl<'a> TableBuilder<'a> {
+    pub(crate) fn new(lua: &'a Lua) -> Self {
+        TableBuilder {
+            lua,
+            entries: Ok(Vec::new()),
+            metatable: None,
+        }
+    }
+
+    /// Adds a field `key = value` to the table.
+    pub fn field(mut self, key: impl IntoLua, value: impl IntoLua) -> Self {
+        if let Ok(entries) = &mut self.entries {
+            match (key.into_lua(self.lua), value.into_lua(self.lua)) {
+                (Ok(key), Ok(value)) => entries.push((key, value)),
+                (Err(err), _) | (_, Err(err)) => self.entries = Err(err),
+            }
+        }
+        self
+    }
+
+    /// Adds a method to the table.
+    ///
+    /// The method is expected to be called using the `:` syntax (`obj:name(...)`) and receives
+    /// the table as its first argument.
+    pub fn method<F, A, R>(self, name: &str, method: F) -> Self
+    where
+        F: Fn(&Lua, Table, A) -> Result<R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        let func = self
+            .lua
+            .create_function(move |lua, (this, args): (Table, A)| method(lua, this, args));
+        match func {
+            Ok(func) => self.field(name, func),
+            Err(err) => self.fail(err),
+        }
+    }
+
+    /// Sets the metatable of the table.
+    pub fn metatable(mut self, metatable: Table) -> Self {
+        self.metatable = Some(metatable);
+        self
+    }
+
+    /// Creates the table with all accumulated entries.
+    pub fn build(self) -> Result<Table> {
+        let table = self.lua.create_table_from(self.entries?)?;
+        if self.metatable.is_some() {
+            table.set_metatable(self.metatable);
+        }
+        Ok(table)
+    }
+
+    fn fail(mut self, err: Error) -> Self {
+        if self.entries.is_ok() {
+            self.entries = Err(err);
+        }
+        self
+    }
+}
+
+impl fmt::Debug for TableBuilder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TableBuilder")
+            .field("entries", &self.entries.as_ref().map(|e| e.len()))
+            .field("metatable", &self.metatable)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod assertions {
     use super::*;
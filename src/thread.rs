@@ -1,18 +1,23 @@
 use std::fmt;
+use std::mem;
+use std::ops::Deref;
 use std::os::raw::{c_int, c_void};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
 
 use crate::error::{Error, Result};
 use crate::function::Function;
-use crate::state::RawLua;
+use crate::multi::MultiValue;
+use crate::state::{RawLua, WeakLua};
 use crate::traits::{FromLuaMulti, IntoLuaMulti};
 use crate::types::{LuaType, ValueRef};
 use crate::util::{check_stack, error_traceback_thread, pop_error, StackGuard};
 
+use crate::hook::Debug;
+
 #[cfg(not(feature = "luau"))]
-use crate::{
-    hook::{Debug, HookTriggers},
-    types::MaybeSend,
-};
+use crate::{hook::HookTriggers, types::MaybeSend};
 
 #[cfg(feature = "async")]
 use {
@@ -143,6 +148,56 @@ impl Thread {
     /// [`coroutine.resume`]: https://www.lua.org/manual/5.4/manual.html#pdf-coroutine.resume
     /// [`coroutine.yield`]: https://www.lua.org/manual/5.4/manual.html#pdf-coroutine.yield
     pub fn resume<R>(&self, args: impl IntoLuaMulti) -> Result<R>
+    where
+        R: FromLuaMulti,
+    {
+        self.resume_status(args).map(|(_, res)| res)
+    }
+
+    /// Resumes execution of this thread, returning all yielded (or returned) values.
+    ///
+    /// This is a shortcut for `resume::<MultiValue>`. The values are kept as is (including any
+    /// trailing `nil`s), so the number of values passed to `coroutine.yield` can be inspected.
+    /// This is useful for generic coroutine drivers that handle values positionally.
+    pub fn resume_multi(&self, args: impl IntoLuaMulti) -> Result<MultiValue> {
+        self.resume(args)
+    }
+
+    /// Resumes execution of this thread, returning the thread status together with the
+    /// yielded (or returned) values.
+    ///
+    /// The status is [`ThreadStatus::Resumable`] if the thread yielded, or
+    /// [`ThreadStatus::Finished`] if it returned. Errors are reported in the same way as in
+    /// [`Thread::resume`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mlua::{Lua, Result, Thread, ThreadStatus};
+    /// # fn main() -> Result<()> {
+    /// # let lua = Lua::new();
+    /// let thread: Thread = lua.load(r#"
+    ///     coroutine.create(function()
+    ///         for i = 1, 2 do
+    ///             coroutine.yield(i)
+    ///         end
+    ///         return 0
+    ///     end)
+    /// "#).eval()?;
+    ///
+    /// let mut values = Vec::new();
+    /// loop {
+    ///     let (status, value) = thread.resume_status::<i32>(())?;
+    ///     values.push(value);
+    ///     if status == ThreadStatus::Finished {
+    ///         break;
+    ///     }
+    /// }
+    /// assert_eq!(values, [1, 2, 0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resume_status<R>(&self, args: impl IntoLuaMulti) -> Result<(ThreadStatus, R)>
     where
         R: FromLuaMulti,
     {
@@ -165,11 +220,15 @@ impl Thread {
                 pushed_nargs += nargs;
             }
 
-            let (_, nresults) = self.resume_inner(&lua, pushed_nargs)?;
+            let (status, nresults) = self.resume_inner(&lua, pushed_nargs)?;
             check_stack(state, nresults + 1)?;
             ffi::lua_xmove(thread_state, state, nresults);
 
-            R::from_stack_multi(nresults, &lua)
+            let status = match status {
+                ThreadStatusInner::Finished => ThreadStatus::Finished,
+                _ => ThreadStatus::Resumable,
+            };
+            Ok((status, R::from_stack_multi(nresults, &lua)?))
         }
     }
 
@@ -259,20 +318,66 @@ impl Thread {
         }
     }
 
+    /// Gets information about the thread's call stack at the given level.
+    ///
+    /// Works like [`Lua::inspect_stack`], but inspects the stack of this thread instead of the
+    /// currently running one. This makes it possible to look into a suspended coroutine.
+    ///
+    /// Returns `None` if the thread is running, finished (or failed with an error), or if there is
+    /// no function at the given level.
+    ///
+    /// [`Lua::inspect_stack`]: crate::Lua::inspect_stack
+    pub fn inspect_stack(&self, level: usize) -> Option<Debug<'_>> {
+        let lua = self.0.lua.lock();
+        match self.status_inner(&lua) {
+            ThreadStatusInner::New(_) | ThreadStatusInner::Yielded(_) => {}
+            _ => return None,
+        }
+        let thread_state = self.state();
+        unsafe {
+            let mut ar: ffi::lua_Debug = mem::zeroed();
+            let level = level as c_int;
+            #[cfg(not(feature = "luau"))]
+            if ffi::lua_getstack(thread_state, level, &mut ar) == 0 {
+                return None;
+            }
+            #[cfg(feature = "luau")]
+            if ffi::lua_getinfo(thread_state, level, cstr!(""), &mut ar) == 0 {
+                return None;
+            }
+            Some(Debug::new_thread(lua, thread_state, level, ar))
+        }
+    }
+
     /// Sets a hook function that will periodically be called as Lua code executes.
     ///
     /// This function is similar or [`Lua::set_hook`] except that it sets for the thread.
-    /// To remove a hook call [`Lua::remove_hook`].
+    /// Each thread can have its own hook, independent of hooks set for other threads.
+    /// Coroutines created by this thread do not inherit the hook.
+    ///
+    /// For LuaJIT the hook mask is shared by all threads, so setting or removing a hook for one
+    /// thread changes the triggers of the others.
+    ///
+    /// To remove the hook call [`Thread::remove_hook`] (or [`Lua::remove_hook`] to remove all
+    /// hooks).
     #[cfg(not(feature = "luau"))]
     #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
-    pub fn set_hook<F>(&self, triggers: HookTriggers, callback: F)
+    pub fn set_hook<F>(&self, triggers: HookTriggers, callback: F) -> Result<()>
     where
         F: Fn(&crate::Lua, Debug) -> Result<crate::VmState> + MaybeSend + 'static,
     {
         let lua = self.0.lua.lock();
-        unsafe {
-            lua.set_thread_hook(self.state(), triggers, callback);
-        }
+        unsafe { lua.set_thread_hook(self.state(), triggers, callback) }
+    }
+
+    /// Removes the hook previously set for this thread using [`Thread::set_hook`].
+    ///
+    /// Hooks set for other threads are not affected.
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub fn remove_hook(&self) -> Result<()> {
+        let lua = self.0.lua.lock();
+        unsafe { lua.remove_thread_hook(self.state()) }
     }
 
     /// Resets a thread
@@ -292,7 +397,27 @@ impl Thread {
     pub fn reset(&self, func: Function) -> Result<()> {
         let lua = self.0.lua.lock();
         let thread_state = self.state();
-        match self.status_inner(&lua) {
+        self.clear(&lua)?;
+
+        unsafe {
+            // Push function to the top of the thread stack
+            ffi::lua_xpush(lua.ref_thread(), thread_state, func.0.index);
+
+            #[cfg(feature = "luau")]
+            {
+                // Inherit `LUA_GLOBALSINDEX` from the main thread
+                ffi::lua_xpush(lua.main_state(), thread_state, ffi::LUA_GLOBALSINDEX);
+                ffi::lua_replace(thread_state, ffi::LUA_GLOBALSINDEX);
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Cleans the thread stack, bringing it to the finished state (see [`Thread::reset`]).
+    fn clear(&self, lua: &RawLua) -> Result<()> {
+        let thread_state = self.state();
+        match self.status_inner(lua) {
             ThreadStatusInner::Running => return Err(Error::runtime("cannot reset a running thread")),
             // Any Lua can reuse new or finished thread
             ThreadStatusInner::New(_) => unsafe { ffi::lua_settop(thread_state, 0) },
@@ -313,20 +438,7 @@ impl Thread {
                 ffi::lua_resetthread(thread_state);
             },
         }
-
-        unsafe {
-            // Push function to the top of the thread stack
-            ffi::lua_xpush(lua.ref_thread(), thread_state, func.0.index);
-
-            #[cfg(feature = "luau")]
-            {
-                // Inherit `LUA_GLOBALSINDEX` from the main thread
-                ffi::lua_xpush(lua.main_state(), thread_state, ffi::LUA_GLOBALSINDEX);
-                ffi::lua_replace(thread_state, ffi::LUA_GLOBALSINDEX);
-            }
-
-            Ok(())
-        }
+        Ok(())
     }
 
     /// Converts [`Thread`] to an [`AsyncThread`] which implements [`Future`] and [`Stream`] traits.
@@ -483,6 +595,122 @@ impl LuaType for Thread {
     const TYPE_ID: c_int = ffi::LUA_TTHREAD;
 }
 
+/// A pool of reusable Lua threads (coroutines), created using [`Lua::thread_pool`].
+///
+/// Threads handed out by [`ThreadPool::acquire`] are returned to the pool when dropped, and
+/// reset (see [`Thread::reset`]) to be reused for another function. This avoids allocating
+/// a new coroutine for every task in coroutine-heavy applications.
+///
+/// Threads that cannot be reset (eg. yielded threads in Lua 5.1-5.3) are not returned to the pool.
+///
+/// [`Lua::thread_pool`]: crate::Lua::thread_pool
+#[derive(Clone)]
+pub struct ThreadPool {
+    lua: WeakLua,
+    threads: Arc<Mutex<Vec<Thread>>>,
+    size: usize,
+}
+
+impl ThreadPool {
+    pub(crate) fn new(lua: WeakLua, size: usize) -> Self {
+        ThreadPool {
+            lua,
+            threads: Arc::new(Mutex::new(Vec::with_capacity(size))),
+            size,
+        }
+    }
+
+    /// Takes a thread from the pool (or creates a new one) and loads `func` into it.
+    ///
+    /// Returns an error if `func` was created by a different Lua instance than the pool.
+    pub fn acquire(&self, func: Function) -> Result<PooledThread> {
+        if func.0.lua != self.lua {
+            return Err(Error::runtime("function belongs to a different Lua instance"));
+        }
+        let thread = self.threads.lock().pop();
+        let thread = match thread {
+            Some(thread) => {
+                thread.reset(func)?;
+                thread
+            }
+            None => unsafe { func.0.lua.lock().create_thread(&func)? },
+        };
+        Ok(PooledThread {
+            thread: Some(thread),
+            pool: self.clone(),
+        })
+    }
+
+    /// Returns the number of idle threads in the pool.
+    pub fn idle(&self) -> usize {
+        self.threads.lock().len()
+    }
+
+    /// Returns the maximum number of idle threads kept in the pool.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn release(&self, thread: Thread) {
+        let mut threads = self.threads.lock();
+        if threads.len() >= self.size {
+            return;
+        }
+        if let Some(lua) = thread.0.lua.try_lock() {
+            if thread.clear(&lua).is_ok() {
+                threads.push(thread);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ThreadPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ThreadPool")
+            .field("idle", &self.idle())
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+/// A [`Thread`] taken from a [`ThreadPool`].
+///
+/// The thread is returned to the pool when this handle is dropped.
+pub struct PooledThread {
+    thread: Option<Thread>,
+    pool: ThreadPool,
+}
+
+impl PooledThread {
+    /// Detaches the thread from the pool, so it will not be returned to it.
+    pub fn into_inner(mut self) -> Thread {
+        self.thread.take().unwrap()
+    }
+}
+
+impl Deref for PooledThread {
+    type Target = Thread;
+
+    #[inline]
+    fn deref(&self) -> &Thread {
+        self.thread.as_ref().unwrap()
+    }
+}
+
+impl fmt::Debug for PooledThread {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl Drop for PooledThread {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.pool.release(thread);
+        }
+    }
+}
+
 #[cfg(feature = "async")]
 impl<R> AsyncThread<R> {
     #[inline(always)]
@@ -616,6 +844,8 @@ mod assertions {
     static_assertions::assert_not_impl_any!(Thread: Send);
     #[cfg(feature = "send")]
     static_assertions::assert_impl_all!(Thread: Send, Sync);
+    #[cfg(feature = "send")]
+    static_assertions::assert_impl_all!(ThreadPool: Send, Sync);
     #[cfg(all(feature = "async", not(feature = "send")))]
     static_assertions::assert_not_impl_any!(AsyncThread<()>: Send);
     #[cfg(all(feature = "async", feature = "send"))]
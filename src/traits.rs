@@ -6,7 +6,7 @@ use crate::error::{Error, Result};
 use crate::multi::MultiValue;
 use crate::private::Sealed;
 use crate::state::{Lua, RawLua};
-use crate::types::MaybeSend;
+use crate::types::{Integer, MaybeSend, Number};
 use crate::util::{check_stack, short_type_name};
 use crate::value::Value;
 
@@ -210,6 +210,59 @@ pub trait ObjectLike: Sealed {
     fn to_string(&self) -> Result<StdString>;
 }
 
+/// A trait for numeric types that can be transferred in bulk between Rust slices and Lua sequences.
+///
+/// Implemented for [`Integer`] and [`Number`].
+/// See [`Lua::create_sequence_from_slice`] and [`Table::read_numeric_slice`].
+///
+/// [`Integer`]: crate::Integer
+/// [`Number`]: crate::Number
+/// [`Table::read_numeric_slice`]: crate::Table::read_numeric_slice
+pub trait NumericElement: Copy + Sealed {
+    #[doc(hidden)]
+    const TYPE_NAME: &'static str;
+
+    /// Pushes the number into the Lua stack.
+    #[doc(hidden)]
+    unsafe fn push_number(self, state: *mut ffi::lua_State);
+
+    /// Reads a number at the given stack index, returning `None` if the value is not convertible.
+    #[doc(hidden)]
+    unsafe fn to_number(state: *mut ffi::lua_State, idx: c_int) -> Option<Self>;
+}
+
+impl NumericElement for Integer {
+    const TYPE_NAME: &'static str = "integer";
+
+    #[inline]
+    unsafe fn push_number(self, state: *mut ffi::lua_State) {
+        ffi::lua_pushinteger(state, self);
+    }
+
+    #[inline]
+    unsafe fn to_number(state: *mut ffi::lua_State, idx: c_int) -> Option<Self> {
+        let mut isnum = 0;
+        let i = ffi::lua_tointegerx(state, idx, &mut isnum);
+        (isnum != 0).then_some(i)
+    }
+}
+
+impl NumericElement for Number {
+    const TYPE_NAME: &'static str = "number";
+
+    #[inline]
+    unsafe fn push_number(self, state: *mut ffi::lua_State) {
+        ffi::lua_pushnumber(state, self);
+    }
+
+    #[inline]
+    unsafe fn to_number(state: *mut ffi::lua_State, idx: c_int) -> Option<Self> {
+        let mut isnum = 0;
+        let n = ffi::lua_tonumberx(state, idx, &mut isnum);
+        (isnum != 0).then_some(n)
+    }
+}
+
 /// A trait for types that can be used as Lua functions.
 pub trait LuaNativeFn<A: FromLuaMulti> {
     type Output: IntoLuaMulti;
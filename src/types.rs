@@ -18,7 +18,7 @@ pub(crate) type BoxFuture<'a, T> = futures_util::future::LocalBoxFuture<'a, T>;
 
 pub use app_data::{AppData, AppDataRef, AppDataRefMut};
 pub use either::Either;
-pub use registry_key::RegistryKey;
+pub use registry_key::{RegistryKey, RegistryStats, TypedRegistryKey};
 pub(crate) use value_ref::ValueRef;
 
 /// Type of Lua integer numbers.
@@ -43,6 +43,10 @@ pub(crate) type Callback = Box<dyn Fn(&RawLua, c_int) -> Result<c_int> + 'static
 
 pub(crate) type ScopedCallback<'s> = Box<dyn Fn(&RawLua, c_int) -> Result<c_int> + 's>;
 
+#[cfg(feature = "async")]
+pub(crate) type ScopedAsyncCallback<'s> =
+    Box<dyn for<'a> Fn(&'a RawLua, c_int) -> BoxFuture<'a, Result<c_int>> + 's>;
+
 pub(crate) struct Upvalue<T> {
     pub(crate) data: T,
     pub(crate) extra: XRc<UnsafeCell<ExtraData>>,
@@ -91,6 +95,12 @@ pub(crate) type WarnCallback = Box<dyn Fn(&Lua, &str, bool) -> Result<()> + Send
 #[cfg(all(not(feature = "send"), feature = "lua54"))]
 pub(crate) type WarnCallback = Box<dyn Fn(&Lua, &str, bool) -> Result<()>>;
 
+#[cfg(feature = "send")]
+pub(crate) type LoadObserver = Rc<dyn Fn(&str) + Send>;
+
+#[cfg(not(feature = "send"))]
+pub(crate) type LoadObserver = Rc<dyn Fn(&str)>;
+
 /// A trait that adds `Send` requirement if `send` feature is enabled.
 #[cfg(feature = "send")]
 pub trait MaybeSend: Send {}
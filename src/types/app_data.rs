@@ -9,11 +9,14 @@ use rustc_hash::FxHashMap;
 use super::MaybeSend;
 use crate::state::LuaGuard;
 
+// Data is keyed by its type and an optional name (to store multiple values of the same type)
+type Key = (TypeId, Option<&'static str>);
+
 #[cfg(not(feature = "send"))]
-type Container = UnsafeCell<FxHashMap<TypeId, RefCell<Box<dyn Any>>>>;
+type Container = UnsafeCell<FxHashMap<Key, RefCell<Box<dyn Any>>>>;
 
 #[cfg(feature = "send")]
-type Container = UnsafeCell<FxHashMap<TypeId, RefCell<Box<dyn Any + Send>>>>;
+type Container = UnsafeCell<FxHashMap<Key, RefCell<Box<dyn Any + Send>>>>;
 
 /// A container for arbitrary data associated with the Lua state.
 #[derive(Debug, Default)]
@@ -24,27 +27,35 @@ pub struct AppData {
 
 impl AppData {
     #[track_caller]
-    pub(crate) fn insert<T: MaybeSend + 'static>(&self, data: T) -> Option<T> {
-        match self.try_insert(data) {
+    pub(crate) fn insert<T: MaybeSend + 'static>(&self, key: Option<&'static str>, data: T) -> Option<T> {
+        match self.try_insert(key, data) {
             Ok(data) => data,
             Err(_) => panic!("cannot mutably borrow app data container"),
         }
     }
 
-    pub(crate) fn try_insert<T: MaybeSend + 'static>(&self, data: T) -> StdResult<Option<T>, T> {
+    pub(crate) fn try_insert<T: MaybeSend + 'static>(
+        &self,
+        key: Option<&'static str>,
+        data: T,
+    ) -> StdResult<Option<T>, T> {
         if self.borrow.get() != 0 {
             return Err(data);
         }
         // SAFETY: we checked that there are no other references to the container
         Ok(unsafe { &mut *self.container.get() }
-            .insert(TypeId::of::<T>(), RefCell::new(Box::new(data)))
+            .insert((TypeId::of::<T>(), key), RefCell::new(Box::new(data)))
             .and_then(|data| data.into_inner().downcast::<T>().ok().map(|data| *data)))
     }
 
     #[inline]
     #[track_caller]
-    pub(crate) fn borrow<T: 'static>(&self, guard: Option<LuaGuard>) -> Option<AppDataRef<T>> {
-        match self.try_borrow(guard) {
+    pub(crate) fn borrow<T: 'static>(
+        &self,
+        key: Option<&'static str>,
+        guard: Option<LuaGuard>,
+    ) -> Option<AppDataRef<T>> {
+        match self.try_borrow(key, guard) {
             Ok(data) => data,
             Err(err) => panic!("already mutably borrowed: {err:?}"),
         }
@@ -52,10 +63,11 @@ impl AppData {
 
     pub(crate) fn try_borrow<T: 'static>(
         &self,
+        key: Option<&'static str>,
         guard: Option<LuaGuard>,
     ) -> Result<Option<AppDataRef<T>>, BorrowError> {
         let data = unsafe { &*self.container.get() }
-            .get(&TypeId::of::<T>())
+            .get(&(TypeId::of::<T>(), key))
             .map(|c| c.try_borrow())
             .transpose()?
             .and_then(|data| Ref::filter_map(data, |data| data.downcast_ref()).ok());
@@ -74,8 +86,12 @@ impl AppData {
 
     #[inline]
     #[track_caller]
-    pub(crate) fn borrow_mut<T: 'static>(&self, guard: Option<LuaGuard>) -> Option<AppDataRefMut<T>> {
-        match self.try_borrow_mut(guard) {
+    pub(crate) fn borrow_mut<T: 'static>(
+        &self,
+        key: Option<&'static str>,
+        guard: Option<LuaGuard>,
+    ) -> Option<AppDataRefMut<T>> {
+        match self.try_borrow_mut(key, guard) {
             Ok(data) => data,
             Err(err) => panic!("already borrowed: {err:?}"),
         }
@@ -83,10 +99,11 @@ impl AppData {
 
     pub(crate) fn try_borrow_mut<T: 'static>(
         &self,
+        key: Option<&'static str>,
         guard: Option<LuaGuard>,
     ) -> Result<Option<AppDataRefMut<T>>, BorrowMutError> {
         let data = unsafe { &*self.container.get() }
-            .get(&TypeId::of::<T>())
+            .get(&(TypeId::of::<T>(), key))
             .map(|c| c.try_borrow_mut())
             .transpose()?
             .and_then(|data| RefMut::filter_map(data, |data| data.downcast_mut()).ok());
@@ -104,13 +121,13 @@ impl AppData {
     }
 
     #[track_caller]
-    pub(crate) fn remove<T: 'static>(&self) -> Option<T> {
+    pub(crate) fn remove<T: 'static>(&self, key: Option<&'static str>) -> Option<T> {
         if self.borrow.get() != 0 {
             panic!("cannot mutably borrow app data container");
         }
         // SAFETY: we checked that there are no other references to the container
         unsafe { &mut *self.container.get() }
-            .remove(&TypeId::of::<T>())?
+            .remove(&(TypeId::of::<T>(), key))?
             .into_inner()
             .downcast::<T>()
             .ok()
@@ -1,4 +1,5 @@
 use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 use std::os::raw::c_int;
 use std::sync::Arc;
 use std::{fmt, mem, ptr};
@@ -60,6 +61,81 @@ impl Drop for RegistryKey {
     }
 }
 
+/// A [`RegistryKey`] that remembers the type of the value stored in the Lua registry.
+///
+/// Created by [`Lua::create_typed_registry_value`] and read back using
+/// [`Lua::typed_registry_value`], which always converts the value to `T`.
+///
+/// Retrieving the value as a different type is rejected at compile time:
+///
+/// ```compile_fail
+/// # use mlua::{Lua, Result};
+/// # fn main() -> Result<()> {
+/// let lua = Lua::new();
+/// let key = lua.create_typed_registry_value(String::from("hello"))?;
+/// let value: i64 = lua.typed_registry_value(&key)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Lua::create_typed_registry_value`]: crate::Lua::create_typed_registry_value
+/// [`Lua::typed_registry_value`]: crate::Lua::typed_registry_value
+pub struct TypedRegistryKey<T> {
+    key: RegistryKey,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for TypedRegistryKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TypedRegistryKey({})", self.key.id())
+    }
+}
+
+impl<T> TypedRegistryKey<T> {
+    pub(crate) const fn new(key: RegistryKey) -> Self {
+        TypedRegistryKey {
+            key,
+            _type: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying untyped [`RegistryKey`].
+    #[inline(always)]
+    pub fn as_key(&self) -> &RegistryKey {
+        &self.key
+    }
+
+    /// Converts this key into the underlying untyped [`RegistryKey`].
+    ///
+    /// Can be used to remove the value using [`Lua::remove_registry_value`].
+    ///
+    /// [`Lua::remove_registry_value`]: crate::Lua::remove_registry_value
+    #[inline(always)]
+    pub fn into_key(self) -> RegistryKey {
+        self.key
+    }
+}
+
+/// Statistics about values stored in the Lua registry using [`RegistryKey`]s.
+///
+/// Returned by [`Lua::registry_stats`].
+///
+/// [`Lua::registry_stats`]: crate::Lua::registry_stats
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RegistryStats {
+    /// Number of registry values referenced by live (not dropped) [`RegistryKey`]s.
+    pub live: usize,
+    /// Number of registry slots whose [`RegistryKey`]s were dropped.
+    ///
+    /// These values are still held by the registry until the slot is reused or
+    /// [`Lua::expire_registry_values`] is called.
+    ///
+    /// [`Lua::expire_registry_values`]: crate::Lua::expire_registry_values
+    pub pending_free: usize,
+    /// Maximum number of registry slots in use at any time.
+    pub peak: usize,
+}
+
 impl RegistryKey {
     /// Creates a new instance of `RegistryKey`
     pub(crate) const fn new(id: c_int, unref_list: Arc<Mutex<Option<Vec<c_int>>>>) -> Self {
@@ -97,4 +173,5 @@ mod assertions {
     use super::*;
 
     static_assertions::assert_impl_all!(RegistryKey: Send, Sync);
+    static_assertions::assert_impl_all!(TypedRegistryKey<std::rc::Rc<()>>: Send, Sync);
 }
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::os::raw::{c_int, c_void};
 
 use crate::state::{RawLua, WeakLua};
@@ -69,3 +70,11 @@ impl PartialEq for ValueRef {
         unsafe { ffi::lua_rawequal(lua.ref_thread(), self.index, other.index) == 1 }
     }
 }
+
+impl Eq for ValueRef {}
+
+impl Hash for ValueRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_pointer().hash(state);
+    }
+}
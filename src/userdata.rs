@@ -3,7 +3,9 @@ use std::ffi::CStr;
 use std::fmt;
 use std::hash::Hash;
 use std::os::raw::{c_char, c_void};
+use std::panic::{self, AssertUnwindSafe};
 use std::string::String as StdString;
+use std::{mem, ptr};
 
 use crate::error::{Error, Result};
 use crate::function::Function;
@@ -12,7 +14,7 @@ use crate::string::String;
 use crate::table::{Table, TablePairs};
 use crate::traits::{FromLua, FromLuaMulti, IntoLua, IntoLuaMulti};
 use crate::types::{MaybeSend, ValueRef};
-use crate::util::{check_stack, get_userdata, push_string, take_userdata, StackGuard};
+use crate::util::{assert_stack, check_stack, get_userdata, push_string, take_userdata, StackGuard};
 use crate::value::Value;
 
 #[cfg(feature = "async")]
@@ -255,6 +257,30 @@ pub trait UserDataMethods<T> {
         A: FromLuaMulti,
         R: IntoLuaMulti;
 
+    /// Add a regular method which accepts a `&T` as the first parameter and returns bytes borrowed
+    /// from it.
+    ///
+    /// The returned bytes (eg. `&str` pointing into a `String` field) are copied directly into a
+    /// new Lua string while the userdata is still borrowed, avoiding an intermediate Rust clone.
+    ///
+    /// The returned reference can only borrow from `T` (or the [`Lua`] instance) and is valid
+    /// only until the method returns; it cannot be stored or returned to Lua as is.
+    ///
+    /// Refer to [`add_method`] for more information about the implementation.
+    ///
+    /// [`add_method`]: UserDataMethods::add_method
+    fn add_method_ref<M, A, R>(&mut self, name: impl ToString, method: M)
+    where
+        M: for<'a> Fn(&'a Lua, &'a T, A) -> Result<&'a R> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: AsRef<[u8]> + ?Sized,
+    {
+        self.add_method(name, move |lua, this, args| {
+            let bytes = method(lua, this, args)?;
+            lua.create_string(bytes.as_ref())
+        });
+    }
+
     /// Add a regular method which accepts a `&mut T` as the first parameter.
     ///
     /// Refer to [`add_method`] for more information about the implementation.
@@ -266,6 +292,49 @@ pub trait UserDataMethods<T> {
         A: FromLuaMulti,
         R: IntoLuaMulti;
 
+    /// Add a regular method which takes `T` by value and returns the new value to store back.
+    ///
+    /// This is useful to model state transitions that consume the current value. While the method
+    /// runs the userdata is mutably borrowed, so any reentrant access to it fails with a borrow
+    /// error. If the method returns an error (or panics), there is no value to store back and the
+    /// userdata becomes destructed, in the same way as after [`AnyUserData::take`].
+    ///
+    /// Refer to [`add_method`] for more information about the implementation.
+    ///
+    /// [`add_method`]: UserDataMethods::add_method
+    fn add_method_replace<M, A, R>(&mut self, name: impl ToString, mut method: M)
+    where
+        T: 'static,
+        M: FnMut(&Lua, T, A) -> Result<(T, R)> + MaybeSend + 'static,
+        A: FromLuaMulti,
+        R: IntoLuaMulti,
+    {
+        self.add_function_mut(name, move |lua, (ud, args): (AnyUserData, A)| {
+            let mut this = ud.borrow_mut::<T>()?;
+            let ptr = &mut *this as *mut T;
+            // The value is moved out for the duration of the call and the exclusive borrow prevents
+            // any access to the (logically uninitialized) cell
+            let value = unsafe { ptr::read(ptr) };
+            match panic::catch_unwind(AssertUnwindSafe(|| method(lua, value, args))) {
+                Ok(Ok((value, ret))) => {
+                    unsafe { ptr::write(ptr, value) };
+                    Ok(ret)
+                }
+                result => {
+                    // The value has been consumed, so mark userdata as destructed and forget the
+                    // stale copy left in the cell
+                    drop(this);
+                    unsafe { ud.forget_value::<T>() };
+                    match result {
+                        Ok(Err(err)) => Err(err),
+                        Err(p) => panic::resume_unwind(p),
+                        Ok(Ok(_)) => unreachable!(),
+                    }
+                }
+            }
+        });
+    }
+
     /// Add an async method which accepts a `&T` as the first parameter and returns [`Future`].
     ///
     /// Refer to [`add_method`] for more information about the implementation.
@@ -287,9 +356,15 @@ pub trait UserDataMethods<T> {
     ///
     /// Refer to [`add_method`] for more information about the implementation.
     ///
+    /// The mutable borrow is held for as long as the [`UserDataRefMut`] is alive, including across
+    /// `.await` points if it's moved into the future. Any other access to the userdata while it's
+    /// borrowed fails with [`Error::UserDataBorrowMutError`]. To avoid this, update or copy the
+    /// required state in the synchronous part of the future and drop the reference before awaiting.
+    ///
     /// Requires `feature = "async"`
     ///
     /// [`add_method`]: UserDataMethods::add_method
+    /// [`Error::UserDataBorrowMutError`]: crate::Error::UserDataBorrowMutError
     #[cfg(feature = "async")]
     #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
     fn add_async_method_mut<M, A, MR, R>(&mut self, name: impl ToString, method: M)
@@ -468,6 +543,19 @@ pub trait UserDataFields<T> {
         M: Fn(&Lua, &T) -> Result<R> + MaybeSend + 'static,
         R: IntoLua;
 
+    /// Add a regular field getter as a method which accepts a `&T` as the parameter, caching the
+    /// returned value.
+    ///
+    /// On first access the value is stored in the userdata instance's user value table, and
+    /// subsequent reads of the field are served from there without calling back into Rust.
+    /// This only suits fields whose value never changes during the lifetime of the instance.
+    ///
+    /// `nil` values are not cached.
+    fn add_field_method_get_cached<M, R>(&mut self, name: impl ToString, method: M)
+    where
+        M: Fn(&Lua, &T) -> Result<R> + MaybeSend + 'static,
+        R: IntoLua;
+
     /// Add a regular field setter as a method which accepts a `&mut T` as the first parameter.
     ///
     /// Regular field setters are implemented by overriding the `__newindex` metamethod and setting
@@ -615,7 +703,10 @@ pub trait UserData: Sized {
 ///
 /// [`is`]: crate::AnyUserData::is
 /// [`borrow`]: crate::AnyUserData::borrow
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Equality and hashing are based on reference identity of the userdata object (the `__eq`
+/// metamethod is not used). The hash is stable only while the userdata is alive.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AnyUserData(pub(crate) ValueRef);
 
 impl AnyUserData {
@@ -643,6 +734,9 @@ impl AnyUserData {
     /// Borrow this userdata immutably if it is of type `T`, passing the borrowed value
     /// to the closure.
     ///
+    /// The borrow is released as soon as the closure returns, which makes it less likely to
+    /// conflict with other borrows when the userdata is accessed again from Lua.
+    ///
     /// This method is the only way to borrow scoped userdata (created inside [`Lua::scope`]).
     pub fn borrow_scoped<T: 'static, R>(&self, f: impl FnOnce(&T) -> R) -> Result<R> {
         self.inspect(|ud| ud.try_borrow_scoped(|ud| f(ud)))
@@ -666,6 +760,8 @@ impl AnyUserData {
     /// Borrow this userdata mutably if it is of type `T`, passing the borrowed value
     /// to the closure.
     ///
+    /// The borrow is released as soon as the closure returns (see [`AnyUserData::borrow_scoped`]).
+    ///
     /// This method is the only way to borrow scoped userdata (created inside [`Lua::scope`]).
     pub fn borrow_mut_scoped<T: 'static, R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R> {
         self.inspect(|ud| ud.try_borrow_scoped_mut(|ud| f(ud)))
@@ -696,6 +792,20 @@ impl AnyUserData {
         }
     }
 
+    /// Marks this userdata as destructed without dropping the stored value.
+    ///
+    /// Used when the value has already been moved out of the cell. The userdata must hold `T`
+    /// and must not be borrowed. This function does not allocate and cannot fail.
+    pub(crate) unsafe fn forget_value<T: 'static>(&self) {
+        let lua = self.0.lua.lock();
+        let state = lua.state();
+        let _sg = StackGuard::new(state);
+        assert_stack(state, 2);
+
+        lua.push_ref(&self.0);
+        mem::forget(take_userdata::<UserDataStorage<T>>(state));
+    }
+
     /// Destroys this userdata.
     ///
     /// This is similar to [`AnyUserData::take`], but it doesn't require a type.
@@ -934,7 +1044,20 @@ impl AnyUserData {
         }
     }
 
-    pub(crate) fn equals(&self, other: &Self) -> Result<bool> {
+    /// Returns `true` if both handles refer to the same userdata object (raw identity).
+    ///
+    /// The `__eq` metamethod is not invoked, see [`AnyUserData::equals`] for that.
+    #[inline]
+    pub fn ptr_eq(&self, other: &AnyUserData) -> bool {
+        self.to_pointer() == other.to_pointer()
+    }
+
+    /// Compares two userdata for equality, invoking the `__eq` metamethod if needed.
+    ///
+    /// Returns `true` if both handles refer to the same object. Otherwise, if both userdata have
+    /// the same metatable with the `__eq` metamethod, returns the result of calling it (in
+    /// protected mode). Returns `false` in all other cases.
+    pub fn equals(&self, other: &Self) -> Result<bool> {
         // Uses lua_rawequal() under the hood
         if self == other {
             return Ok(true);
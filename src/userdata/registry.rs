@@ -59,6 +59,7 @@ pub(crate) struct RawUserDataRegistry {
     // Fields
     pub(crate) fields: Vec<(String, Result<Value>)>,
     pub(crate) field_getters: Vec<(String, Callback)>,
+    pub(crate) cached_field_getters: Vec<(String, Callback)>,
     pub(crate) field_setters: Vec<(String, Callback)>,
     pub(crate) meta_fields: Vec<(String, Result<Value>)>,
 
@@ -71,6 +72,8 @@ pub(crate) struct RawUserDataRegistry {
     pub(crate) async_meta_methods: Vec<(String, AsyncCallback)>,
 
     pub(crate) destructor: ffi::lua_CFunction,
+    #[cfg(not(feature = "luau"))]
+    pub(crate) finalizer_priority: Option<i32>,
     pub(crate) type_id: Option<TypeId>,
     pub(crate) type_name: StdString,
 }
@@ -118,6 +121,7 @@ impl<T> UserDataRegistry<T> {
         let raw = RawUserDataRegistry {
             fields: Vec::new(),
             field_getters: Vec::new(),
+            cached_field_getters: Vec::new(),
             field_setters: Vec::new(),
             meta_fields: Vec::new(),
             methods: Vec::new(),
@@ -127,6 +131,8 @@ impl<T> UserDataRegistry<T> {
             #[cfg(feature = "async")]
             async_meta_methods: Vec::new(),
             destructor: super::util::userdata_destructor::<T>,
+            #[cfg(not(feature = "luau"))]
+            finalizer_priority: None,
             type_id: ud_type_id.type_id(),
             type_name: short_type_name::<T>(),
         };
@@ -517,6 +523,23 @@ impl<T> UserDataRegistry<T> {
         })
     }
 
+    /// Sets the finalizer priority for this userdata type.
+    ///
+    /// By default, when [`Lua`] is dropped, userdata values are finalized by the garbage collector
+    /// in unspecified order. Userdata of types with a finalizer priority are instead destructed
+    /// before that, in descending order of priority (values with the same priority are destructed
+    /// in unspecified order).
+    ///
+    /// This is useful when one userdata depends on another during cleanup, eg. a buffered writer
+    /// must be flushed before the underlying file is closed.
+    ///
+    /// Not available under Luau, where userdata destructors are managed by the Luau runtime.
+    #[cfg(not(feature = "luau"))]
+    #[cfg_attr(docsrs, doc(cfg(not(feature = "luau"))))]
+    pub fn set_finalizer_priority(&mut self, priority: i32) {
+        self.raw.finalizer_priority = Some(priority);
+    }
+
     pub(crate) fn check_meta_field(lua: &Lua, name: &str, value: impl IntoLua) -> Result<Value> {
         let value = value.into_lua(lua)?;
         if name == MetaMethod::Index || name == MetaMethod::NewIndex {
@@ -564,6 +587,16 @@ impl<T> UserDataFields<T> for UserDataRegistry<T> {
         self.raw.field_getters.push((name, callback));
     }
 
+    fn add_field_method_get_cached<M, R>(&mut self, name: impl ToString, method: M)
+    where
+        M: Fn(&Lua, &T) -> Result<R> + MaybeSend + 'static,
+        R: IntoLua,
+    {
+        let name = name.to_string();
+        let callback = self.box_method(&name, move |lua, data, ()| method(lua, data));
+        self.raw.cached_field_getters.push((name, callback));
+    }
+
     fn add_field_method_set<M, A>(&mut self, name: impl ToString, method: M)
     where
         M: FnMut(&Lua, &mut T, A) -> Result<()> + MaybeSend + 'static,
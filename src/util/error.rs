@@ -130,6 +130,12 @@ pub(crate) unsafe fn pop_error(state: *mut ffi::lua_State, err_code: c_int) -> E
             }
         }
         _ => {
+            if err_code == ffi::LUA_ERRRUN {
+                if let Some(err) = crate::state::util::pop_error_object(state) {
+                    return err;
+                }
+            }
+
             let err_string = to_string(state, -1);
             ffi::lua_pop(state, 1);
 
@@ -274,7 +280,9 @@ pub(crate) unsafe extern "C-unwind" fn error_traceback(state: *mut ffi::lua_Stat
         return 1;
     }
 
-    if get_internal_userdata::<WrappedFailure>(state, -1, ptr::null()).is_null() {
+    if !is_error_object(state, -1)
+        && get_internal_userdata::<WrappedFailure>(state, -1, ptr::null()).is_null()
+    {
         let s = ffi::luaL_tolstring(state, -1, ptr::null_mut());
         if ffi::lua_checkstack(state, ffi::LUA_TRACEBACK_STACK) != 0 {
             ffi::luaL_traceback(state, state, s, 0);
@@ -285,12 +293,31 @@ pub(crate) unsafe extern "C-unwind" fn error_traceback(state: *mut ffi::lua_Stat
     1
 }
 
+// Checks if the error at the given index is a non-string error object without `__tostring`
+// metamethod, that should be passed as is (without adding traceback) to be converted to
+// `Error::ErrorObject` later.
+// Uses 1 stack space, does not call checkstack.
+pub(crate) unsafe fn is_error_object(state: *mut ffi::lua_State, idx: c_int) -> bool {
+    match ffi::lua_type(state, idx) {
+        ffi::LUA_TSTRING | ffi::LUA_TNUMBER => false,
+        _ => {
+            if ffi::luaL_getmetafield(state, idx, cstr!("__tostring")) != ffi::LUA_TNIL {
+                ffi::lua_pop(state, 1);
+                return false;
+            }
+            true
+        }
+    }
+}
+
 // A variant of `error_traceback` that can safely inspect another (yielded) thread stack
 pub(crate) unsafe fn error_traceback_thread(state: *mut ffi::lua_State, thread: *mut ffi::lua_State) {
     // Move error object to the main thread to safely call `__tostring` metamethod if present
     ffi::lua_xmove(thread, state, 1);
 
-    if get_internal_userdata::<WrappedFailure>(state, -1, ptr::null()).is_null() {
+    if !is_error_object(state, -1)
+        && get_internal_userdata::<WrappedFailure>(state, -1, ptr::null()).is_null()
+    {
         let s = ffi::luaL_tolstring(state, -1, ptr::null_mut());
         if ffi::lua_checkstack(state, ffi::LUA_TRACEBACK_STACK) != 0 {
             ffi::luaL_traceback(state, thread, s, 0);
@@ -5,6 +5,7 @@ use std::{ptr, slice, str};
 
 use crate::error::{Error, Result};
 
+pub(crate) use error::is_error_object;
 pub(crate) use error::{
     error_traceback, error_traceback_thread, init_error_registry, pop_error, protect_lua_call,
     protect_lua_closure, WrappedFailure,
@@ -17,9 +18,9 @@ pub(crate) use userdata::{
     DESTRUCTED_USERDATA_METATABLE,
 };
 
-#[cfg(not(feature = "luau"))]
-pub(crate) use userdata::push_uninit_userdata;
 pub(crate) use userdata::push_userdata;
+#[cfg(not(feature = "luau"))]
+pub(crate) use userdata::{push_finalizer_queue_entry, push_uninit_userdata, run_finalizer_queue};
 
 // Checks that Lua has enough free stack space for future stack operations. On failure, this will
 // panic with an internal error message.
@@ -70,6 +70,17 @@ impl TypeKey for AsyncPollUpvalue {
     }
 }
 
+#[cfg(not(feature = "luau"))]
+static HOOK_CALLBACK_TYPE_KEY: u8 = 0;
+
+#[cfg(not(feature = "luau"))]
+impl TypeKey for crate::types::HookCallback {
+    #[inline(always)]
+    fn type_key() -> *const c_void {
+        &HOOK_CALLBACK_TYPE_KEY as *const u8 as *const c_void
+    }
+}
+
 #[cfg(feature = "async")]
 static WAKER_TYPE_KEY: u8 = 0;
 
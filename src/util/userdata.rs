@@ -339,6 +339,73 @@ unsafe extern "C-unwind" fn userdata_destructor<T>(state: *mut ffi::lua_State) -
     0
 }
 
+// Adds the userdata on top of the stack to the (weak) queue of userdata that must be destructed
+// in order of their finalizer priority when Lua is closed.
+// Uses 4 stack spaces, does not call checkstack.
+#[cfg(not(feature = "luau"))]
+pub(crate) unsafe fn push_finalizer_queue_entry(state: *mut ffi::lua_State, priority: i32) -> Result<()> {
+    protect_lua!(state, 1, 1, |state| {
+        let key = &FINALIZER_QUEUE as *const u8 as *const c_void;
+        if ffi::lua_rawgetp(state, ffi::LUA_REGISTRYINDEX, key) != ffi::LUA_TTABLE {
+            ffi::lua_pop(state, 1);
+            ffi::lua_createtable(state, 0, 0);
+            ffi::lua_createtable(state, 0, 1);
+            ffi::lua_pushstring(state, cstr!("k"));
+            ffi::lua_setfield(state, -2, cstr!("__mode"));
+            ffi::lua_setmetatable(state, -2);
+            ffi::lua_pushvalue(state, -1);
+            ffi::lua_rawsetp(state, ffi::LUA_REGISTRYINDEX, key);
+        }
+        ffi::lua_pushvalue(state, -2);
+        ffi::lua_pushinteger(state, priority as ffi::lua_Integer);
+        ffi::lua_rawset(state, -3);
+        ffi::lua_pop(state, 1);
+    })
+}
+
+// Destructs all userdata from the finalizer queue, in descending order of their priority.
+// Must be called only when Lua is about to be closed.
+#[cfg(not(feature = "luau"))]
+pub(crate) unsafe fn run_finalizer_queue(state: *mut ffi::lua_State) {
+    let key = &FINALIZER_QUEUE as *const u8 as *const c_void;
+    if ffi::lua_checkstack(state, 3) == 0 {
+        return;
+    }
+    let base = ffi::lua_gettop(state);
+    if ffi::lua_rawgetp(state, ffi::LUA_REGISTRYINDEX, key) != ffi::LUA_TTABLE {
+        ffi::lua_settop(state, base);
+        return;
+    }
+
+    // Collect userdata on the stack (above the queue table)
+    let mut queue = Vec::new();
+    ffi::lua_pushnil(state);
+    while ffi::lua_next(state, base + 1) != 0 {
+        let priority = ffi::lua_tointeger(state, -1);
+        ffi::lua_pop(state, 1);
+        if ffi::lua_checkstack(state, 3) == 0 {
+            ffi::lua_pop(state, 1);
+            break;
+        }
+        ffi::lua_pushvalue(state, -1);
+        queue.push((priority, ffi::lua_gettop(state) - 1));
+    }
+
+    queue.sort_by_key(|&(priority, _)| std::cmp::Reverse(priority));
+    for (_, idx) in queue {
+        // Already destructed userdata do not have `__gc` metamethod
+        if ffi::luaL_getmetafield(state, idx, cstr!("__gc")) != ffi::LUA_TNIL {
+            ffi::lua_pushvalue(state, idx);
+            if ffi::lua_pcall(state, 1, 0, 0) != ffi::LUA_OK {
+                ffi::lua_pop(state, 1);
+            }
+        }
+    }
+    ffi::lua_settop(state, base);
+}
+
 pub(crate) static DESTRUCTED_USERDATA_METATABLE: u8 = 0;
+#[cfg(not(feature = "luau"))]
+static FINALIZER_QUEUE: u8 = 0;
 static USERDATA_METATABLE_INDEX: u8 = 0;
 static USERDATA_METATABLE_NEWINDEX: u8 = 0;
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::ffi::CString;
 use std::os::raw::c_void;
 use std::string::String as StdString;
 use std::{fmt, ptr, str};
@@ -8,11 +9,12 @@ use num_traits::FromPrimitive;
 
 use crate::error::{Error, Result};
 use crate::function::Function;
+use crate::state::Lua;
 use crate::string::{BorrowedStr, String};
 use crate::table::Table;
 use crate::thread::Thread;
 use crate::types::{Integer, LightUserData, Number, ValueRef};
-use crate::userdata::AnyUserData;
+use crate::userdata::{AnyUserData, MetaMethod};
 use crate::util::{check_stack, StackGuard};
 
 #[cfg(feature = "serialize")]
@@ -185,6 +187,74 @@ impl Value {
         }
     }
 
+    /// Returns the metamethod `name` of the value, or `None` if the value has no metatable or the
+    /// metatable does not have such field.
+    ///
+    /// Behaves as [`luaL_getmetafield`] (the metatable field is read without invoking
+    /// metamethods). The `lua` instance is used to look up metatables shared by all values of a
+    /// type (eg. set using [`Lua::set_type_metatable`]).
+    ///
+    /// [`luaL_getmetafield`]: https://www.lua.org/manual/5.4/manual.html#luaL_getmetafield
+    pub fn get_metamethod(&self, lua: &Lua, name: MetaMethod) -> Result<Option<Value>> {
+        let lua = lua.lock();
+        let state = lua.state();
+        unsafe {
+            let _guard = StackGuard::new(state);
+            check_stack(state, 3)?;
+
+            lua.push_value(self)?;
+            let name = mlua_expect!(CString::new(name.name()), "invalid metamethod name");
+            let name = name.as_ptr();
+            let field_type = if lua.unlikely_memory_error() {
+                ffi::luaL_getmetafield(state, -1, name)
+            } else {
+                protect_lua!(state, 1, 1, |state| ffi::luaL_getmetafield(state, -1, name))?
+            };
+            match field_type {
+                ffi::LUA_TNIL => Ok(None),
+                _ => Ok(Some(lua.pop_value())),
+            }
+        }
+    }
+
+    /// Makes a copy of this value that belongs to the `target` Lua instance.
+    ///
+    /// This is intended for multiple [`Lua`] handles sharing the same main state (eg. obtained via
+    /// [`Lua::init_from_ptr`]). The returned value refers to the same underlying Lua object.
+    ///
+    /// Returns an error if the value was created in a different main Lua state.
+    pub fn clone_into(&self, target: &Lua) -> Result<Value> {
+        let vref = match self {
+            Value::String(String(vref))
+            | Value::Table(Table(vref))
+            | Value::Function(Function(vref))
+            | Value::Thread(Thread(vref, ..))
+            | Value::UserData(AnyUserData(vref))
+            | Value::Other(vref) => vref,
+            #[cfg(feature = "luau")]
+            Value::Buffer(crate::Buffer(vref)) => vref,
+            _ => return Ok(self.clone()),
+        };
+
+        let src = vref.lua.try_lock();
+        let src = src.ok_or_else(|| Error::runtime("source Lua instance is destroyed"))?;
+        let dst = target.lock();
+        if src.main_state() != dst.main_state() {
+            return Err(Error::runtime("value belongs to a different main Lua state"));
+        }
+        if &vref.lua == dst.weak() {
+            return Ok(self.clone());
+        }
+
+        let state = dst.state();
+        unsafe {
+            let _guard = StackGuard::new(state);
+            check_stack(state, 1)?;
+            ffi::lua_xpush(src.ref_thread(), state, vref.index);
+            Ok(dst.pop_value())
+        }
+    }
+
     /// Returns `true` if the value is a [`Nil`].
     #[inline]
     pub fn is_nil(&self) -> bool {
@@ -311,6 +311,69 @@ async fn test_async_thread_stream() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_async_iterator() -> Result<()> {
+    use futures_util::stream::{self, StreamExt};
+
+    let lua = Lua::new();
+
+    let range = lua.create_async_iterator(|_, (from, to): (i64, i64)| {
+        stream::iter(from..=to).then(|i| async move {
+            sleep_ms(1).await;
+            Ok(i)
+        })
+    })?;
+    lua.globals().set("range", range)?;
+
+    let items: Vec<i64> = lua
+        .load(
+            r#"
+            local items = {}
+            for i in range(3, 6) do
+                table.insert(items, i)
+            end
+            return items
+            "#,
+        )
+        .call_async(())
+        .await?;
+    assert_eq!(items, vec![3, 4, 5, 6]);
+
+    // Exhausted iterator keeps returning nil
+    let iter: Function = lua.load("range(1, 1)").eval()?;
+    assert_eq!(iter.call_async::<Option<i64>>(()).await?, Some(1));
+    assert_eq!(iter.call_async::<Option<i64>>(()).await?, None);
+    assert_eq!(iter.call_async::<Option<i64>>(()).await?, None);
+
+    // Iterator shared between coroutines does not end early
+    let iter: Function = lua.load("range(1, 6)").eval()?;
+    let consume: Function = lua
+        .load(
+            r#"
+            function(iter)
+                for _ in iter do
+                    taken = (taken or 0) + 1
+                end
+                return taken
+            end
+            "#,
+        )
+        .eval()?;
+    let (a, b) = tokio::try_join!(consume.call_async::<i64>(&iter), consume.call_async::<i64>(&iter))?;
+    assert_eq!((a, b), (6, 6));
+
+    // Errors are propagated to Lua
+    let failing = lua.create_async_iterator(|_, ()| stream::iter([Ok(1), Err(Error::runtime("boom"))]))?;
+    lua.globals().set("failing", failing)?;
+    let res = lua.load("for _ in failing() do end").exec_async().await;
+    match res {
+        Err(err) => assert!(err.to_string().contains("boom")),
+        Ok(_) => panic!("expected error"),
+    }
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_async_thread() -> Result<()> {
     let lua = Lua::new();
@@ -521,6 +584,120 @@ async fn test_async_userdata() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_async_userdata_method_mut() -> Result<()> {
+    struct Counter(u64);
+
+    impl UserData for Counter {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            // Releases the borrow before awaiting
+            methods.add_async_method_mut("incr", |_, mut this, ()| async move {
+                this.0 += 1;
+                let n = this.0;
+                drop(this);
+                sleep_ms(10).await;
+                Ok(n)
+            });
+
+            // Holds the borrow across the await point
+            methods.add_async_method_mut("incr_slow", |_, mut this, ()| async move {
+                sleep_ms(10).await;
+                this.0 += 1;
+                Ok(this.0)
+            });
+
+            methods.add_method("get", |_, this, ()| Ok(this.0));
+        }
+    }
+
+    let lua = Lua::new();
+    lua.globals().set("counter", Counter(0))?;
+
+    let incr: Function = lua.load("function() return counter:incr() end").eval()?;
+    let (a, b) = tokio::try_join!(incr.call_async::<u64>(()), incr.call_async::<u64>(()))?;
+    assert_eq!((a, b), (1, 2));
+
+    let incr_slow: Function = lua.load("function() return counter:incr_slow() end").eval()?;
+    let get: Function = lua.load("function() return counter:get() end").eval()?;
+    let (n, res) = tokio::join!(incr_slow.call_async::<u64>(()), async {
+        tokio::task::yield_now().await;
+        get.call::<u64>(())
+    });
+    assert_eq!(n?, 3);
+    match res {
+        Err(err) => assert!(err.to_string().contains("error borrowing userdata"), "{err}"),
+        Ok(_) => panic!("expected borrow error"),
+    }
+    assert_eq!(get.call::<u64>(())?, 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_scope_async() -> Result<()> {
+    use std::cell::{Cell, RefCell};
+
+    let lua = Lua::new();
+
+    let mut values = Vec::new();
+    let values_ref = &RefCell::new(&mut values);
+    let f: Function = lua
+        .load("function(push) push(1); push(2); return 'done' end")
+        .eval()?;
+    let push = lua
+        .scope_async(|scope| {
+            Box::pin(async move {
+                let push = scope.create_async_function(move |_, n: i64| async move {
+                    sleep_ms(1).await;
+                    values_ref.borrow_mut().push(n);
+                    Ok(())
+                })?;
+                assert_eq!(f.call_async::<String>(&push).await?, "done");
+                Ok(push)
+            })
+        })
+        .await?;
+    assert_eq!(values, [1, 2]);
+    match push.call_async::<()>(3).await {
+        Err(Error::CallbackError { ref cause, .. }) => {
+            assert!(matches!(cause.as_ref(), Error::CallbackDestructed))
+        }
+        r => panic!("improper return for destructed function: {r:?}"),
+    }
+
+    // Cancellation drops pending futures and invalidates scoped functions
+    struct DropFlag<'a>(&'a Cell<bool>);
+    impl Drop for DropFlag<'_> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    let dropped = &Cell::new(false);
+    let leaked = &RefCell::new(None);
+    let res = tokio::time::timeout(
+        Duration::from_millis(50),
+        lua.scope_async(|scope| {
+            Box::pin(async move {
+                let wait = scope.create_async_function(move |_, ()| async move {
+                    let _flag = DropFlag(dropped);
+                    futures_util::future::pending::<()>().await;
+                    Ok(())
+                })?;
+                *leaked.borrow_mut() = Some(wait.clone());
+                wait.call_async::<()>(()).await
+            })
+        }),
+    )
+    .await;
+    assert!(res.is_err());
+    assert!(dropped.get());
+    let wait = leaked.borrow_mut().take().unwrap();
+    assert!(wait.call_async::<()>(()).await.is_err());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_async_thread_error() -> Result<()> {
     struct MyUserData;
@@ -1,6 +1,6 @@
 #![cfg(feature = "luau")]
 
-use mlua::{Lua, Result, Value};
+use mlua::{Error, Lua, Result, Value};
 
 #[test]
 fn test_buffer() -> Result<()> {
@@ -40,6 +40,32 @@ fn test_buffer() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_buffer_slice() -> Result<()> {
+    let lua = Lua::new();
+
+    let buf = lua.create_buffer(b"hello, world!")?;
+    assert_eq!(buf.read_slice(7, 5)?, b"world");
+    buf.write_slice(7, b"Luau!")?;
+    assert_eq!(buf.read_slice(0, 13)?, b"hello, Luau!!");
+    assert_eq!(buf.read_slice(13, 0)?, b"");
+
+    lua.globals().set("buf", &buf)?;
+    lua.load(r#"assert(buffer.tostring(buf) == "hello, Luau!!")"#)
+        .exec()?;
+
+    match buf.write_slice(12, b"!!") {
+        Err(Error::RuntimeError(msg)) => {
+            assert_eq!(msg, "buffer range 12..14 out of bounds for buffer of length 13")
+        }
+        r => panic!("expected `RuntimeError`, got {r:?}"),
+    }
+    assert!(buf.read_slice(usize::MAX, 2).is_err());
+    assert_eq!(buf.to_vec(), b"hello, Luau!!");
+
+    Ok(())
+}
+
 #[test]
 #[should_panic(expected = "range end index 14 out of range for slice of length 13")]
 fn test_buffer_out_of_bounds_read() {
@@ -1,5 +1,5 @@
 use bstr::{BStr, BString};
-use mlua::{Lua, Result};
+use mlua::{Lua, Result, String as LuaString};
 
 #[test]
 fn test_byte_string_round_trip() -> Result<()> {
@@ -94,3 +94,25 @@ fn test_byte_string_round_trip() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_byte_string_borrowed() -> Result<()> {
+    let lua = Lua::new();
+
+    let bytes = BString::from(b"\xff\x00binary\xfe".to_vec());
+    lua.globals().set("data", &bytes)?;
+    assert_eq!(lua.load("#data").eval::<usize>()?, bytes.len());
+
+    // Non-UTF-8 content is rejected by `String` but preserved by `BString`
+    assert!(lua.globals().get::<String>("data").is_err());
+    assert_eq!(lua.globals().get::<BString>("data")?, bytes);
+
+    let s = lua.globals().get::<LuaString>("data")?;
+    let borrowed = s.as_bytes();
+    let bstr: &BStr = borrowed.as_bstr();
+    assert_eq!(bstr, bytes.as_slice());
+    lua.globals().set("data2", bstr)?;
+    assert!(lua.load("data == data2").eval::<bool>()?);
+
+    Ok(())
+}
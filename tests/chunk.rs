@@ -1,6 +1,6 @@
 use std::{fs, io};
 
-use mlua::{Chunk, Lua, Result};
+use mlua::{Chunk, ChunkMode, ChunkNameKind, Lua, ReplLoadResult, Result};
 
 #[test]
 fn test_chunk_path() -> Result<()> {
@@ -93,6 +93,154 @@ fn test_chunk_macro() -> Result<()> {
     Ok(())
 }
 
+#[cfg(not(feature = "luau"))]
+#[test]
+fn test_chunk_disallow_bytecode() -> Result<()> {
+    use mlua::{Error, LuaOptions, StdLib};
+
+    let bytecode = Lua::new().load("return 123").into_function()?.dump(false);
+
+    let lua = Lua::new_with(StdLib::ALL_SAFE, LuaOptions::new().allow_bytecode(false))?;
+    for mode in [None, Some(ChunkMode::Binary)] {
+        let mut chunk = lua.load(&bytecode);
+        if let Some(mode) = mode {
+            chunk = chunk.set_mode(mode);
+        }
+        match chunk.exec() {
+            Err(Error::SyntaxError { .. }) => {}
+            r => panic!("expected SyntaxError, got {r:?}"),
+        }
+    }
+
+    // Text chunks (including internal ones) still work
+    assert_eq!(lua.load("return 123").eval::<i32>()?, 123);
+    let sum = lua
+        .load("function(a, b) return a + b end")
+        .eval::<mlua::Function>()?;
+    assert_eq!(sum.bind(1)?.call::<i32>(2)?, 3);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "luau"))]
+#[test]
+fn test_chunk_bytecode_header() -> Result<()> {
+    use mlua::Error;
+
+    let lua = Lua::new();
+    let bytecode = lua.load("return 123").into_function()?.dump(false);
+    assert_eq!(
+        lua.load(&bytecode).set_mode(ChunkMode::Binary).eval::<i32>()?,
+        123
+    );
+
+    // Truncated header (LuaJIT header is only 4 bytes long)
+    let truncated_len = if cfg!(feature = "luajit") { 3 } else { 8 };
+    match lua
+        .load(&bytecode[..truncated_len])
+        .set_mode(ChunkMode::Binary)
+        .exec()
+    {
+        Err(Error::BytecodeError(msg)) => assert!(msg.contains("truncated"), "{msg}"),
+        r => panic!("expected BytecodeError, got {r:?}"),
+    }
+
+    // Mangled version byte (right after the signature)
+    let mut mangled = bytecode.clone();
+    let version_idx = if cfg!(feature = "luajit") { 3 } else { 4 };
+    mangled[version_idx] = mangled[version_idx].wrapping_add(1);
+    match lua.load(&mangled).exec() {
+        Err(Error::BytecodeError(msg)) => assert!(msg.contains("version mismatch"), "{msg}"),
+        r => panic!("expected BytecodeError, got {r:?}"),
+    }
+
+    // Invalid signature
+    match lua.load(&b"\x1bXYZ"[..]).set_mode(ChunkMode::Binary).exec() {
+        Err(Error::BytecodeError(msg)) => assert!(msg.contains("signature"), "{msg}"),
+        r => panic!("expected BytecodeError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "luau"))]
+#[test]
+fn test_chunk_mode_from_name() -> Result<()> {
+    use mlua::Error;
+
+    let lua = Lua::new();
+    let bytecode = lua.load("return 123").into_function()?.dump(false);
+
+    // Binary mode for `.luac`
+    assert_eq!(lua.load(&bytecode).set_name("@test.luac").eval::<i32>()?, 123);
+    match lua.load("return 123").set_name("@test.luac").exec() {
+        Err(Error::SyntaxError { .. }) => {}
+        r => panic!("expected SyntaxError, got {r:?}"),
+    }
+
+    // Auto-detection for `.lua` (bytecode can be saved with any name)
+    assert_eq!(lua.load(&bytecode).set_name("=test.lua").eval::<i32>()?, 123);
+    assert_eq!(lua.load("return 123").set_name("=test.lua").eval::<i32>()?, 123);
+
+    // Explicit mode overrides the name
+    let chunk = lua.load("return 456").set_name("@test.luac");
+    assert_eq!(chunk.set_mode(ChunkMode::Text).eval::<i32>()?, 456);
+
+    // Auto-detection for other names
+    assert_eq!(lua.load(&bytecode).set_name("=test").eval::<i32>()?, 123);
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_load_observer() -> Result<()> {
+    use std::sync::{Arc, Mutex};
+
+    let lua = Lua::new();
+    let loaded = Arc::new(Mutex::new(Vec::new()));
+
+    let loaded2 = loaded.clone();
+    lua.set_load_observer(move |name| loaded2.lock().unwrap().push(name.to_string()))?;
+
+    lua.load("local x = 1").set_name("=config").exec()?;
+    lua.load("return 2").set_name("@scripts/main.lua").exec()?;
+    // Syntax errors are not reported
+    assert!(lua.load("local =").set_name("=broken").exec().is_err());
+    assert_eq!(*loaded.lock().unwrap(), ["=config", "@scripts/main.lua"]);
+
+    // Modules loaded by `require` (reported once, as they are cached)
+    #[cfg(not(feature = "luau"))]
+    {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let module_path = temp_dir.path().join("observed.lua");
+        std::fs::write(&module_path, "return 1").unwrap();
+        let package: mlua::Table = lua.globals().get("package")?;
+        package.set("path", temp_dir.path().join("?.lua").to_string_lossy())?;
+        for _ in 0..2 {
+            let m: i32 = lua.globals().get::<mlua::Function>("require")?.call("observed")?;
+            assert_eq!(m, 1);
+        }
+        let missing = lua
+            .globals()
+            .get::<mlua::Function>("require")?
+            .call::<()>("missing");
+        assert!(missing.is_err());
+
+        let expected = format!("@{}", module_path.display());
+        assert_eq!(
+            *loaded.lock().unwrap(),
+            ["=config", "@scripts/main.lua", &expected]
+        );
+        loaded.lock().unwrap().pop();
+    }
+
+    lua.remove_load_observer();
+    lua.load("local y = 2").set_name("=other").exec()?;
+    assert_eq!(loaded.lock().unwrap().len(), 2);
+
+    Ok(())
+}
+
 #[cfg(feature = "luau")]
 #[test]
 fn test_compiler() -> Result<()> {
@@ -138,3 +286,180 @@ fn test_chunk_wrap() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_chunk_load_repl() -> Result<()> {
+    let lua = Lua::new();
+
+    match lua.load_repl("1 + 2, 'a'") {
+        ReplLoadResult::Complete(func) => assert_eq!(func.call::<(i32, String)>(())?, (3, "a".into())),
+        r => panic!("expected complete expression, got {r:?}"),
+    }
+
+    let mut input = String::from("function add(a, b)");
+    assert!(matches!(lua.load_repl(&input), ReplLoadResult::Incomplete));
+    input.push_str("\n  return a + b");
+    assert!(matches!(lua.load_repl(&input), ReplLoadResult::Incomplete));
+    input.push_str("\nend");
+    match lua.load_repl(&input) {
+        ReplLoadResult::Complete(func) => func.call::<()>(())?,
+        r => panic!("expected complete statement, got {r:?}"),
+    }
+    assert_eq!(lua.load("add(1, 2)").eval::<i32>()?, 3);
+
+    match lua.load_repl("x = = 1") {
+        ReplLoadResult::Error(mlua::Error::SyntaxError {
+            incomplete_input: false,
+            ..
+        }) => {}
+        r => panic!("expected syntax error, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_exec_streaming() -> Result<()> {
+    use mlua::{Error, Value};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    let lua = Lua::new();
+
+    // Values are produced lazily
+    let counter = Arc::new(AtomicU32::new(0));
+    let counter2 = counter.clone();
+    let step = lua.create_function(move |_, ()| {
+        counter2.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    })?;
+    lua.globals().set("step", step)?;
+    let mut stream = lua.exec_streaming(
+        r#"
+        step()
+        coroutine.yield("a")
+        step()
+        coroutine.yield("b", "c")
+        return "done"
+    "#,
+    );
+    assert_eq!(counter.load(Ordering::Relaxed), 0);
+    assert_eq!(stream.next().unwrap()?, Value::String(lua.create_string("a")?));
+    assert_eq!(counter.load(Ordering::Relaxed), 1);
+    let rest = stream.collect::<Result<Vec<_>>>()?;
+    assert_eq!(counter.load(Ordering::Relaxed), 2);
+    let rest = rest.iter().map(|v| v.to_string()).collect::<Result<Vec<_>>>()?;
+    assert_eq!(rest, ["b", "c", "done"]);
+
+    // Runtime error stops the stream
+    let mut stream = lua.exec_streaming("coroutine.yield(1); error('boom')");
+    assert_eq!(stream.next().unwrap()?, Value::Integer(1));
+    assert!(matches!(stream.next(), Some(Err(Error::RuntimeError(_)))));
+    assert!(stream.next().is_none());
+
+    // Syntax error is returned as the only item
+    let mut stream = lua.exec_streaming("return +");
+    assert!(matches!(stream.next(), Some(Err(Error::SyntaxError { .. }))));
+    assert!(stream.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_name_kind() -> Result<()> {
+    let lua = Lua::new();
+
+    let traceback = |name, kind| {
+        let chunk = lua.load("local x = 1\nerror('boom')").set_name_as(name, kind);
+        chunk.exec().unwrap_err().to_string()
+    };
+
+    let tb = traceback("scripts/main.lua", ChunkNameKind::Source);
+    assert!(tb.starts_with("runtime error: scripts/main.lua:2: boom"), "{tb}");
+    #[cfg(not(feature = "luau"))]
+    assert!(tb.contains("\n\tscripts/main.lua:2: in main chunk"), "{tb}");
+
+    let tb = traceback("my chunk", ChunkNameKind::Literal);
+    assert!(tb.starts_with("runtime error: my chunk:2: boom"), "{tb}");
+    #[cfg(not(feature = "luau"))]
+    assert!(tb.contains("\n\tmy chunk:2: in main chunk"), "{tb}");
+
+    let tb = traceback("local x = 1", ChunkNameKind::Snippet);
+    assert!(
+        tb.starts_with("runtime error: [string \"local x = 1\"]:2: boom"),
+        "{tb}"
+    );
+    assert!(
+        tb.contains("\n\t[string \"local x = 1\"]:2: in main chunk"),
+        "{tb}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_verify() -> Result<()> {
+    let lua = Lua::new();
+
+    let f = lua.load("return 1 + 1").verify(true).into_function()?;
+    assert_eq!(f.call::<i32>(())?, 2);
+    assert_eq!(lua.load("1 + 2").verify(true).eval::<i32>()?, 3);
+
+    // A binary chunk made from a nested closure carries its upvalues
+    #[cfg(not(feature = "luau"))]
+    {
+        let inner = lua
+            .load(
+                r#"
+                local a, b = 1, 2
+                return function() return a + b end
+            "#,
+            )
+            .eval::<mlua::Function>()?;
+        let bytecode = inner.dump(false);
+
+        let f = lua.load(&bytecode).into_function()?;
+        assert!(f.num_upvalues() > 1);
+        let err = lua.load(&bytecode).verify(true).into_function().unwrap_err();
+        assert!(err.to_string().contains("chunk verification failed"), "{err}");
+
+        // Verification applies to compiled chunks too
+        let compiled = lua.load(&bytecode).verify(true).compile()?;
+        let err = compiled.to_function().unwrap_err();
+        assert!(err.to_string().contains("chunk verification failed"), "{err}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_compiled_chunk() -> Result<()> {
+    let lua = Lua::new();
+
+    let compiled = lua.load("return x * 2").set_name("compiled").compile()?;
+
+    let env1 = lua.create_table_from([("x", 1)])?;
+    let env2 = lua.create_table_from([("x", 21)])?;
+    let f1 = compiled.to_function_with_env(env1)?;
+    let f2 = compiled.to_function_with_env(env2)?;
+    assert_eq!(f1.call::<i32>(())?, 2);
+    assert_eq!(f2.call::<i32>(())?, 42);
+
+    lua.globals().set("x", 5)?;
+    assert_eq!(compiled.to_function()?.call::<i32>(())?, 10);
+
+    // Environment set on the chunk is kept
+    let env = lua.create_table_from([("x", 4)])?;
+    let compiled = lua.load("return x * 2").set_environment(env).compile()?;
+    assert_eq!(compiled.to_function()?.call::<i32>(())?, 8);
+    let env = lua.create_table_from([("x", 3)])?;
+    assert_eq!(compiled.to_function_with_env(env)?.call::<i32>(())?, 6);
+
+    // Syntax errors are reported by `compile`
+    assert!(matches!(
+        lua.load("return +").compile(),
+        Err(mlua::Error::SyntaxError { .. })
+    ));
+
+    Ok(())
+}
@@ -30,7 +30,7 @@ fn test_error_context() -> Result<()> {
     assert!(msg2.contains("failed to find global"));
     assert!(msg2.contains("error converting Lua nil to String"));
 
-    // Rewrite context message and test `downcast_ref`
+    // Nest context messages and test `downcast_ref`
     let func3 = lua.create_function(|_, ()| {
         Err::<(), _>(Error::external(io::Error::new(io::ErrorKind::Other, "other")))
             .context("some context")
@@ -38,7 +38,7 @@ fn test_error_context() -> Result<()> {
     })?;
     let err = func3.call::<()>(()).unwrap_err();
     let err = err.parent().unwrap();
-    assert!(!err.to_string().contains("some context"));
+    assert!(err.to_string().contains("some context"));
     assert!(err.to_string().contains("some new context"));
     assert!(err.downcast_ref::<io::Error>().is_some());
     assert!(err.downcast_ref::<fmt::Error>().is_none());
@@ -46,6 +46,32 @@ fn test_error_context() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_error_context_traceback() -> Result<()> {
+    let lua = Lua::new();
+
+    let func = lua.create_function(|_, ()| Err::<(), _>(Error::runtime("inner error")))?;
+    lua.globals().set("func", func)?;
+
+    let err = lua
+        .load("func()")
+        .exec()
+        .context("first context")
+        .context("second context")
+        .unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.starts_with("second context\nfirst context\n"));
+    assert!(msg.contains("inner error"));
+    assert!(msg.contains("stack traceback"));
+
+    // The original callback error is still reachable
+    assert!(err
+        .chain()
+        .any(|err| matches!(err.downcast_ref(), Some(Error::CallbackError { .. }))));
+
+    Ok(())
+}
+
 #[test]
 fn test_error_chain() -> Result<()> {
     let lua = Lua::new();
@@ -74,6 +100,16 @@ fn test_error_chain() -> Result<()> {
     assert!(err.to_string().contains("io error"));
     assert!(err.to_string().contains("other"));
 
+    // Root Lua error under several context layers (external wrappers are skipped)
+    let err = Error::external(Error::runtime("root cause"))
+        .context("first layer")
+        .context("second layer");
+    assert_eq!(err.chain().count(), 3);
+    match err.chain().last().and_then(|err| err.downcast_ref::<Error>()) {
+        Some(Error::RuntimeError(msg)) => assert_eq!(msg, "root cause"),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
     Ok(())
 }
 
@@ -99,3 +135,97 @@ fn test_error_anyhow() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn test_create_function_anyhow() -> Result<()> {
+    let lua = Lua::new();
+
+    let parse = lua.create_function_anyhow(|_, s: String| {
+        let n = s
+            .parse::<i64>()
+            .map_err(|e| anyhow::anyhow!("invalid number `{s}`: {e}"))?;
+        Ok(n * 2)
+    })?;
+    assert_eq!(parse.call::<i64>("21")?, 42);
+
+    let err = parse.call::<i64>("abc").unwrap_err();
+    match err {
+        Error::CallbackError { ref cause, .. } => {
+            assert!(matches!(cause.as_ref(), Error::ExternalError(_)));
+            assert!(cause.to_string().contains("invalid number `abc`"));
+        }
+        ref err => panic!("expected CallbackError, got {err:?}"),
+    }
+
+    // mlua errors are passed through
+    let fail =
+        lua.create_function_anyhow(|_, ()| -> anyhow::Result<()> { Err(Error::runtime("boom").into()) })?;
+    match fail.call::<()>(()) {
+        Err(Error::CallbackError { cause, .. }) => {
+            assert!(matches!(cause.as_ref(), Error::RuntimeError(msg) if msg == "boom"))
+        }
+        r => panic!("expected CallbackError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_error_object() -> Result<()> {
+    use mlua::{Table, Value};
+
+    let lua = Lua::new();
+
+    // Non-string error objects raised in Lua are preserved (without traceback)
+    let err = lua.load("error({code = 42})").exec().unwrap_err();
+    assert!(matches!(err, Error::ErrorObject(_)));
+    let obj = err.value().unwrap().to_value(&lua)?;
+    assert_eq!(obj.as_table().unwrap().get::<i64>("code")?, 42);
+    assert_eq!(err.to_string(), "runtime error: (error object is a table value)");
+
+    for (chunk, type_name) in [
+        ("error(true)", "boolean"),
+        ("error()", "nil"),
+        ("error(print)", "function"),
+    ] {
+        let err = lua.load(chunk).exec().unwrap_err();
+        assert_eq!(err.value().map(|v| v.type_name()), Some(type_name), "{chunk}");
+    }
+
+    // String errors are not affected
+    let err = lua.load("error('boom', 0)").exec().unwrap_err();
+    assert!(matches!(err, Error::RuntimeError(ref msg) if msg.starts_with("boom")));
+    assert!(err.value().is_none());
+
+    // Error objects with `__tostring` metamethod are converted to strings (with traceback)
+    let err = lua
+        .load("error(setmetatable({}, {__tostring = function() return 'custom' end}))")
+        .exec()
+        .unwrap_err();
+    match err {
+        Error::RuntimeError(ref msg) => {
+            assert!(msg.starts_with("custom"), "{msg}");
+            assert!(msg.contains("stack traceback"), "{msg}");
+        }
+        err => panic!("expected RuntimeError, got {err:?}"),
+    }
+
+    // Error objects raised from Rust can be caught in Lua
+    let fail = lua.create_function(|lua, code: i64| -> Result<()> {
+        let obj = lua.create_table()?;
+        obj.set("code", code)?;
+        Err(Error::with_value(Value::Table(obj)))
+    })?;
+    lua.globals().set("fail", &fail)?;
+    let (ok, obj) = lua.load("pcall(fail, 7)").eval::<(bool, Table)>()?;
+    assert!(!ok);
+    assert_eq!(obj.get::<i64>("code")?, 7);
+
+    // And are propagated back to Rust
+    let err = fail.call::<()>(8).unwrap_err();
+    let obj = err.value().unwrap().to_value(&lua)?;
+    assert_eq!(obj.as_table().unwrap().get::<i64>("code")?, 8);
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::string::String as StdString;
+
 use mlua::{Error, Function, Lua, Result, String, Table, Variadic};
 
 #[test]
@@ -57,6 +59,19 @@ fn test_function_bind() -> Result<()> {
     assert_eq!(concat2.call::<String>(())?, "");
     assert_eq!(concat2.call::<String>(("ab", "cd"))?, "abcd");
 
+    // Bound arguments are prepended in order (including nils)
+    let args = lua.create_function(|_, args: Variadic<Option<i64>>| Ok(format!("{:?}", &*args)))?;
+    let bound1 = args.bind(1)?;
+    assert_eq!(bound1.call::<StdString>((2, 3))?, "[Some(1), Some(2), Some(3)]");
+    let bound2 = args.bind((1, mlua::Nil))?;
+    assert_eq!(bound2.call::<StdString>(3)?, "[Some(1), None, Some(3)]");
+    let stacked = bound2.bind(2)?.bind(())?.bind((3, 4))?;
+    assert_eq!(
+        stacked.call::<StdString>((5, 6))?,
+        "[Some(1), None, Some(2), Some(3), Some(4), Some(5), Some(6)]"
+    );
+    assert_eq!(args.bind(())?.to_pointer(), args.to_pointer());
+
     Ok(())
 }
 
@@ -140,6 +155,49 @@ fn test_function_environment() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_function_upvalues() -> Result<()> {
+    let lua = Lua::new();
+
+    let func = lua
+        .load(
+            r#"
+        local a, b = 1, "two"
+        return function() return a, b end
+    "#,
+        )
+        .eval::<Function>()?;
+
+    assert_eq!(func.num_upvalues(), 2);
+    assert_eq!(func.upvalue::<i64>(1)?.unwrap().1, 1);
+    #[cfg(not(feature = "luau"))]
+    assert_eq!(func.upvalue::<i64>(1)?.unwrap().0, "a");
+    assert_eq!(func.upvalue::<String>(2)?.unwrap().1, "two");
+    assert!(func.upvalue::<mlua::Value>(0)?.is_none());
+    assert!(func.upvalue::<mlua::Value>(3)?.is_none());
+
+    func.set_upvalue(1, 10)?;
+    func.set_upvalue(2, "three")?;
+    let (a, b) = func.call::<(i64, String)>(())?;
+    assert_eq!(a, 10);
+    assert_eq!(b, "three");
+    assert!(func.set_upvalue(0, 1).is_err());
+    assert!(func.set_upvalue(3, 1).is_err());
+    let err = func.set_upvalue(usize::MAX, 1).unwrap_err();
+    assert!(err.to_string().contains(&usize::MAX.to_string()));
+
+    let func = lua.load("return function(x) return x end").eval::<Function>()?;
+    assert_eq!(func.num_upvalues(), 0);
+
+    // Rust functions do not expose upvalues
+    let rust_func = lua.create_function(|_, ()| Ok(()))?;
+    assert!(rust_func.num_upvalues() > 0);
+    assert!(rust_func.upvalue::<mlua::Value>(1)?.is_none());
+    assert!(rust_func.set_upvalue(1, 1).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_function_info() -> Result<()> {
     let lua = Lua::new();
@@ -296,6 +354,18 @@ fn test_function_pointer() -> Result<()> {
     assert_eq!(func1.to_pointer(), func1.clone().to_pointer());
     assert_ne!(func1.to_pointer(), func2.to_pointer());
 
+    // Functions can be used as hash map keys (by identity)
+    let mut map = std::collections::HashMap::new();
+    map.insert(func1.clone(), "func1");
+    map.insert(func2.clone(), "func2");
+    let func1_copy: Function = lua.load("return ...").call(&func1)?;
+    assert_eq!(map.get(&func1_copy), Some(&"func1"));
+    assert_eq!(map.get(&func2), Some(&"func2"));
+    assert_eq!(map.len(), 2);
+
+    assert!(func1.ptr_eq(&func1_copy));
+    assert!(!func1.ptr_eq(&func2));
+
     Ok(())
 }
 
@@ -0,0 +1,55 @@
+#![cfg(feature = "helpers")]
+
+use mlua::{HelperSet, Lua, Result, Value};
+
+#[test]
+fn test_install_helpers() -> Result<()> {
+    let lua = Lua::new();
+    lua.install_helpers(HelperSet::DUMP)?;
+
+    let globals = lua.globals();
+    assert!(globals.contains_key("dump")?);
+    assert!(!globals.contains_key("pretty_print")?);
+    assert!(!globals.contains_key("hexdump")?);
+
+    lua.install_helpers(HelperSet::ALL)?;
+    assert!(globals.contains_key("pretty_print")?);
+    assert!(globals.contains_key("hexdump")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_dump() -> Result<()> {
+    let lua = Lua::new();
+    lua.install_helpers(HelperSet::DUMP)?;
+
+    let dump = lua.load(r#"dump({1, key = "value"})"#).eval::<String>()?;
+    assert!(dump.contains("[1] = 1"));
+    assert!(dump.contains(r#"key = "value""#));
+
+    // Recursive tables must not loop forever
+    let dump = lua
+        .load("local t = {}; t.t = t; return dump(t)")
+        .eval::<String>()?;
+    assert!(dump.contains("table: 0x"));
+
+    Ok(())
+}
+
+#[test]
+fn test_hexdump() -> Result<()> {
+    let lua = Lua::new();
+    lua.install_helpers(HelperSet::HEXDUMP)?;
+
+    let dump = lua.load(r#"hexdump("Hello, World!\0\1\2")"#).eval::<String>()?;
+    assert_eq!(
+        dump,
+        "00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 00 01 02  |Hello, World!...|\n"
+    );
+
+    let dump = lua.load(r#"hexdump("")"#).eval::<Value>()?;
+    assert_eq!(dump.to_string()?, "");
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use std::ops::Deref;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::{Arc, Mutex};
 
-use mlua::{DebugEvent, Error, HookTriggers, Lua, Result, ThreadStatus, Value, VmState};
+use mlua::{DebugEvent, Error, Function, HookTriggers, Lua, Result, ThreadStatus, Value, VmState};
 
 #[test]
 fn test_hook_triggers() {
@@ -17,6 +17,26 @@ fn test_hook_triggers() {
     assert_eq!(trigger.every_nth_instruction, Some(5));
 }
 
+#[test]
+fn test_hook_info() -> Result<()> {
+    let lua = Lua::new();
+    assert!(lua.hook_info().is_none());
+
+    lua.set_hook(HookTriggers::EVERY_LINE.every_nth_instruction(10), |_, _| {
+        Ok(VmState::Continue)
+    })?;
+    let triggers = lua.hook_info().unwrap();
+    assert!(!triggers.on_calls);
+    assert!(!triggers.on_returns);
+    assert!(triggers.every_line);
+    assert_eq!(triggers.every_nth_instruction, Some(10));
+
+    lua.remove_hook();
+    assert!(lua.hook_info().is_none());
+
+    Ok(())
+}
+
 #[test]
 fn test_line_counts() -> Result<()> {
     let output = Arc::new(Mutex::new(Vec::new()));
@@ -27,7 +47,7 @@ fn test_line_counts() -> Result<()> {
         assert_eq!(debug.event(), DebugEvent::Line);
         hook_output.lock().unwrap().push(debug.curr_line());
         Ok(VmState::Continue)
-    });
+    })?;
     lua.load(
         r#"
             local x = 2 + 3
@@ -62,7 +82,7 @@ fn test_function_calls() -> Result<()> {
         let name = names.name.map(|s| s.into_owned());
         hook_output.lock().unwrap().push((name, source.what));
         Ok(VmState::Continue)
-    });
+    })?;
 
     lua.load(
         r#"
@@ -101,7 +121,7 @@ fn test_error_within_hook() -> Result<()> {
 
     lua.set_hook(HookTriggers::EVERY_LINE, |_lua, _debug| {
         Err(Error::runtime("Something happened in there!"))
-    });
+    })?;
 
     let err = lua.load("x = 1").exec().expect_err("panic didn't propagate");
 
@@ -135,7 +155,7 @@ fn test_limit_execution_instructions() -> Result<()> {
                 Ok(VmState::Continue)
             }
         },
-    );
+    )?;
 
     lua.globals().set("x", Value::Integer(0))?;
     let _ = lua
@@ -158,7 +178,7 @@ fn test_hook_removal() -> Result<()> {
 
     lua.set_hook(HookTriggers::new().every_nth_instruction(1), |_lua, _debug| {
         Err(Error::runtime("this hook should've been removed by this time"))
-    });
+    })?;
 
     assert!(lua.load("local x = 1").exec().is_err());
     lua.remove_hook();
@@ -205,10 +225,10 @@ fn test_hook_swap_within_hook() -> Result<()> {
                             });
                             Ok(VmState::Continue)
                         })
-                });
+                })?;
                 Ok(VmState::Continue)
             })
-    });
+    })?;
 
     TL_LUA.with(|tl| {
         let tl = tl.borrow();
@@ -247,7 +267,7 @@ fn test_hook_threads() -> Result<()> {
         assert_eq!(debug.event(), DebugEvent::Line);
         hook_output.lock().unwrap().push(debug.curr_line());
         Ok(VmState::Continue)
-    });
+    })?;
 
     co.resume::<()>(())?;
     lua.remove_hook();
@@ -262,6 +282,137 @@ fn test_hook_threads() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_hook_multiple_threads() -> Result<()> {
+    let lua = Lua::new();
+
+    // For LuaJIT disable JIT, as compiled code does not trigger hooks
+    #[cfg(feature = "luajit")]
+    lua.load("jit.off()").exec()?;
+
+    let func = lua
+        .load(
+            r#"
+            local n = ...
+            for i = 1, n do
+                coroutine.yield(i)
+            end
+        "#,
+        )
+        .into_function()?;
+    let co1 = lua.create_thread(func.clone())?;
+    let co2 = lua.create_thread(func)?;
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let (calls1, calls2) = (calls.clone(), calls.clone());
+    co1.set_hook(HookTriggers::EVERY_LINE, move |_, _| {
+        calls1.lock().unwrap().push(1);
+        Ok(VmState::Continue)
+    })?;
+    co2.set_hook(HookTriggers::EVERY_LINE, move |_, _| {
+        calls2.lock().unwrap().push(2);
+        Ok(VmState::Continue)
+    })?;
+
+    // Interleave both coroutines, each hook fires only for its own thread
+    co1.resume::<()>(2)?;
+    co2.resume::<()>(2)?;
+    co1.resume::<()>(())?;
+    co2.resume::<()>(())?;
+    let (n1, n2) = {
+        let calls = calls.lock().unwrap();
+        let n1 = calls.iter().filter(|&&c| c == 1).count();
+        (n1, calls.len() - n1)
+    };
+    assert!(n1 > 0);
+    assert_eq!(n1, n2);
+
+    // Removing one hook does not affect the other (LuaJIT hook state is global)
+    co1.remove_hook()?;
+    calls.lock().unwrap().clear();
+    co1.resume::<()>(())?;
+    co2.resume::<()>(())?;
+    #[cfg(not(feature = "luajit"))]
+    assert!(!calls.lock().unwrap().is_empty());
+    assert!(calls.lock().unwrap().iter().all(|&c| c == 2));
+    assert_eq!(co1.status(), ThreadStatus::Finished);
+    assert_eq!(co2.status(), ThreadStatus::Finished);
+
+    // The main thread hook is independent too
+    let main_calls = Arc::new(AtomicI64::new(0));
+    let main_calls2 = main_calls.clone();
+    lua.set_hook(HookTriggers::EVERY_LINE, move |_, _| {
+        main_calls2.fetch_add(1, Ordering::Relaxed);
+        Ok(VmState::Continue)
+    })?;
+    calls.lock().unwrap().clear();
+    lua.load("local a = 1").exec()?;
+    assert!(main_calls.load(Ordering::Relaxed) > 0);
+    assert!(calls.lock().unwrap().is_empty());
+    lua.remove_hook();
+
+    Ok(())
+}
+
+#[test]
+fn test_hook_memory_error() -> Result<()> {
+    let lua = Lua::new();
+
+    if cfg!(feature = "luajit") && lua.set_memory_limit(0).is_err() {
+        // seems this luajit version does not support memory limit
+        return Ok(());
+    }
+
+    // Storing the hook callback fails instead of panicking
+    lua.set_memory_limit(lua.used_memory())?;
+    let result = lua.set_hook(HookTriggers::EVERY_LINE, |_, _| Ok(VmState::Continue));
+    lua.set_memory_limit(0)?;
+    assert!(matches!(result, Err(Error::MemoryError(_))));
+
+    lua.set_hook(HookTriggers::EVERY_LINE, |_, _| Ok(VmState::Continue))?;
+    lua.remove_hook();
+
+    Ok(())
+}
+
+#[test]
+fn test_hook_collected_threads() -> Result<()> {
+    let lua = Lua::new();
+
+    // For LuaJIT disable JIT, as compiled code does not trigger hooks
+    #[cfg(feature = "luajit")]
+    lua.load("jit.off()").exec()?;
+
+    let func = lua.load("local a = 1").into_function()?;
+    let token = Arc::new(());
+    let calls = Arc::new(AtomicI64::new(0));
+    for _ in 0..100 {
+        let co = lua.create_thread(func.clone())?;
+        let (token, calls) = (token.clone(), calls.clone());
+        co.set_hook(HookTriggers::EVERY_LINE, move |_, _| {
+            let _ = &token;
+            calls.fetch_add(1, Ordering::Relaxed);
+            Ok(VmState::Continue)
+        })?;
+        co.resume::<()>(())?;
+    }
+    assert!(calls.load(Ordering::Relaxed) >= 100);
+
+    // Hooks are dropped together with their threads
+    lua.gc_collect()?;
+    lua.gc_collect()?;
+    assert_eq!(Arc::strong_count(&token), 1);
+
+    // New threads (that can reuse memory of the collected ones) do not run stale hooks
+    calls.store(0, Ordering::Relaxed);
+    for _ in 0..100 {
+        lua.create_thread(func.clone())?.resume::<()>(())?;
+    }
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+    Ok(())
+}
+
 #[test]
 fn test_hook_yield() -> Result<()> {
     let lua = Lua::new();
@@ -277,7 +428,7 @@ fn test_hook_yield() -> Result<()> {
         .into_function()?;
     let co = lua.create_thread(func)?;
 
-    co.set_hook(HookTriggers::EVERY_LINE, move |_lua, _debug| Ok(VmState::Yield));
+    co.set_hook(HookTriggers::EVERY_LINE, move |_lua, _debug| Ok(VmState::Yield))?;
 
     #[cfg(any(feature = "lua54", feature = "lua53"))]
     {
@@ -297,3 +448,198 @@ fn test_hook_yield() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_sampling_profiler() -> Result<()> {
+    let lua = Lua::new();
+
+    // For LuaJIT disable JIT, as compiled code does not trigger hooks
+    #[cfg(feature = "luajit")]
+    lua.load("jit.off()").exec()?;
+
+    let profiler = lua.start_profiler(10)?;
+    assert!(lua.hook_info().is_some());
+    lua.load(
+        r#"
+        local function hot()
+            local x = 0
+            for i = 1, 10000 do x = x + i end
+            return x
+        end
+        local function cold() return 1 end
+        for _ = 1, 10 do hot(); cold() end
+    "#,
+    )
+    .set_name("profiled")
+    .exec()?;
+
+    let report = profiler.report();
+    let hot = report.get("hot ([string \"profiled\"]:2)").copied().unwrap_or(0);
+    let cold = report.get("cold ([string \"profiled\"]:7)").copied().unwrap_or(0);
+    assert!(hot > 100, "unexpected profile: {report:?}");
+    assert!(hot > cold * 10, "unexpected profile: {report:?}");
+
+    profiler.reset();
+    assert!(profiler.report().is_empty());
+
+    // Dropping the handle removes the hook
+    drop(profiler);
+    assert!(lua.hook_info().is_none());
+
+    // A previously set hook is restored when the profiler is dropped
+    lua.set_hook(HookTriggers::EVERY_LINE, |_, _| Ok(VmState::Continue))?;
+    let profiler = lua.start_profiler(10)?;
+    assert_eq!(lua.hook_info().map(|t| t.every_line), Some(false));
+    drop(profiler);
+    assert_eq!(lua.hook_info().map(|t| t.every_line), Some(true));
+    lua.remove_hook();
+
+    // A hook set while the profiler is running is kept
+    let profiler = lua.start_profiler(10)?;
+    lua.set_deadline(std::time::Instant::now() + std::time::Duration::from_secs(10))?;
+    drop(profiler);
+    assert!(lua.hook_info().is_some());
+    lua.clear_deadline()?;
+
+    Ok(())
+}
+
+#[test]
+fn test_deadline() -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    let lua = Lua::new();
+
+    // For LuaJIT disable JIT, as compiled code does not trigger hooks
+    #[cfg(feature = "luajit")]
+    lua.load("jit.off()").exec()?;
+
+    lua.set_deadline(Instant::now() + Duration::from_millis(50))?;
+    match lua.load("while true do end").exec() {
+        Err(Error::CallbackError { cause, .. }) => {
+            assert!(matches!(cause.as_ref(), Error::RuntimeError(msg) if msg == "deadline exceeded"));
+        }
+        r => panic!("expected deadline error, got {r:?}"),
+    }
+
+    // Deadline is in the past: any long running code should fail
+    lua.set_deadline(Instant::now())?;
+    assert!(lua.load("for i = 1, 10000 do end").exec().is_err());
+
+    lua.clear_deadline()?;
+    lua.load("for i = 1, 10000 do end").exec()?;
+
+    // A previously set hook is restored when the deadline is cleared
+    let lines = Arc::new(AtomicI64::new(0));
+    let lines2 = lines.clone();
+    lua.set_hook(HookTriggers::EVERY_LINE, move |_, _| {
+        lines2.fetch_add(1, Ordering::Relaxed);
+        Ok(VmState::Continue)
+    })?;
+    lua.set_deadline(Instant::now() + Duration::from_secs(10))?;
+    lua.set_deadline(Instant::now() + Duration::from_secs(10))?;
+    lua.clear_deadline()?;
+    assert_eq!(lua.hook_info().map(|t| t.every_line), Some(true));
+    lua.load("local x = 1").exec()?;
+    assert!(lines.load(Ordering::Relaxed) > 0);
+    lua.remove_hook();
+
+    Ok(())
+}
+
+#[test]
+fn test_call_with_deadline() -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    let lua = Lua::new();
+
+    // For LuaJIT disable JIT, as compiled code does not trigger hooks
+    #[cfg(feature = "luajit")]
+    lua.load("jit.off()").exec()?;
+
+    let spin: Function = lua.load("function() while true do end end").eval()?;
+    let start = Instant::now();
+    match spin.call_with_deadline::<()>((), start + Duration::from_millis(50)) {
+        Err(Error::Timeout) => {}
+        r => panic!("expected `Timeout` error, got {r:?}"),
+    }
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // Hook is removed after the call
+    assert!(lua.hook_info().is_none());
+    let sum: Function = lua.load("function(a, b) return a + b end").eval()?;
+    assert_eq!(sum.call_with_deadline::<i32>((1, 2), Instant::now())?, 3);
+
+    // Nested calls restore the outer deadline
+    let inner = lua.create_function(move |_, ()| {
+        let r = spin.call_with_deadline::<()>((), Instant::now() + Duration::from_millis(20));
+        Ok(matches!(r, Err(Error::Timeout)))
+    })?;
+    lua.globals().set("inner", inner)?;
+    let outer: Function = lua
+        .load("function() assert(inner()) while true do end end")
+        .eval()?;
+    match outer.call_with_deadline::<()>((), Instant::now() + Duration::from_millis(100)) {
+        Err(Error::Timeout) => {}
+        r => panic!("expected `Timeout` error, got {r:?}"),
+    }
+
+    // A previously set hook is restored
+    lua.set_hook(HookTriggers::EVERY_LINE, |_, _| Ok(VmState::Continue))?;
+    sum.call_with_deadline::<i32>((1, 2), Instant::now() + Duration::from_secs(1))?;
+    assert_eq!(lua.hook_info().map(|t| t.every_line), Some(true));
+
+    // The hook is restored even if the function panics
+    let panic = lua.create_function(|_, ()| -> Result<()> { panic!("test panic") })?;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        panic.call_with_deadline::<()>((), Instant::now() + Duration::from_secs(1))
+    }));
+    assert!(result.is_err());
+    assert_eq!(lua.hook_info().map(|t| t.every_line), Some(true));
+    lua.remove_hook();
+
+    Ok(())
+}
+
+#[test]
+fn test_yielding_function() -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    let lua = Lua::new();
+
+    let spin = lua.create_yielding_function(|_, yp, n: Option<u32>| {
+        let mut i = 0;
+        loop {
+            yp.check()?;
+            i += 1;
+            if Some(i) == n {
+                return Ok(i);
+            }
+        }
+    })?;
+
+    // Without a hook, checks always pass
+    assert_eq!(spin.call::<u32>(1000)?, 1000);
+
+    // Rust code is stopped by a deadline
+    let start = Instant::now();
+    match spin.call_with_deadline::<()>((), start + Duration::from_millis(50)) {
+        Err(Error::Timeout) => {}
+        r => panic!("expected `Timeout` error, got {r:?}"),
+    }
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    lua.set_deadline(Instant::now() + Duration::from_millis(20))?;
+    lua.globals().set("spin", spin)?;
+    let err = lua.load("spin()").exec().unwrap_err();
+    assert!(err.to_string().contains("deadline exceeded"), "{err}");
+    lua.clear_deadline()?;
+
+    // Hooks without instruction count triggers are not invoked
+    lua.set_hook(HookTriggers::EVERY_LINE, |_, _| Err(Error::runtime("line hook")))?;
+    let count = lua.globals().get::<Function>("spin")?.call::<u32>(10)?;
+    assert_eq!(count, 10);
+    lua.remove_hook();
+
+    Ok(())
+}
@@ -214,6 +214,46 @@ fn test_vector_metatable() -> Result<()> {
     Ok(())
 }
 
+#[cfg(not(feature = "luau-vector4"))]
+#[test]
+fn test_vector_custom_ctor() -> Result<()> {
+    let lua = Lua::new();
+
+    let vector_mt = lua
+        .load(
+            r#"
+            {
+                __index = {
+                    scale = function(v, k)
+                        return vector.create(v.x * k, v.y * k, v.z * k)
+                    end
+                }
+            }
+    "#,
+        )
+        .eval::<Table>()?;
+    lua.set_type_metatable::<Vector>(Some(vector_mt));
+    lua.load("vec3 = vector.create").exec()?;
+
+    let compiler = Compiler::new().set_vector_ctor("vec3").set_vector_type("vec3");
+    let v: Vector = lua
+        .load(
+            r#"
+            local function make(x: number): vec3
+                return vec3(x, x + 1, x + 2)
+            end
+            local v = vec3(1, 2, 3):scale(2)
+            assert(v.x == 2 and v.y == 4 and v.z == 6)
+            return make(1) + v
+        "#,
+        )
+        .set_compiler(compiler)
+        .eval()?;
+    assert_eq!(v, [3.0, 6.0, 9.0]);
+
+    Ok(())
+}
+
 #[test]
 fn test_readonly_table() -> Result<()> {
     let lua = Lua::new();
@@ -239,6 +279,7 @@ fn test_readonly_table() -> Result<()> {
     check_readonly_error(t.pop::<Value>());
     check_readonly_error(t.raw_push("value"));
     check_readonly_error(t.raw_pop::<Value>());
+    check_readonly_error(t.replace_metatable(None));
 
     // Special case
     match catch_unwind(AssertUnwindSafe(|| t.set_metatable(None))) {
@@ -67,6 +67,32 @@ fn test_memory_limit_thread() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_allocator_info() -> Result<()> {
+    let lua = Lua::new();
+
+    let Some(info) = lua.allocator_info() else {
+        // LuaJIT may not allow custom allocators
+        if !cfg!(feature = "luajit") {
+            panic!("allocator info must be available");
+        }
+        assert!(matches!(
+            lua.set_memory_limit(0),
+            Err(Error::MemoryControlNotAvailable)
+        ));
+        return Ok(());
+    };
+    assert!(info.used_memory > 0);
+    assert_eq!(info.memory_limit, None);
+
+    lua.set_memory_limit(1 << 20)?;
+    let info = lua.allocator_info().unwrap();
+    assert_eq!(info.memory_limit, Some(1 << 20));
+    assert_eq!(info.used_memory, lua.used_memory());
+
+    Ok(())
+}
+
 #[test]
 fn test_gc_control() -> Result<()> {
     let lua = Lua::new();
@@ -76,8 +102,29 @@ fn test_gc_control() -> Result<()> {
     {
         assert_eq!(lua.gc_gen(0, 0), GCMode::Incremental);
         assert_eq!(lua.gc_inc(0, 0, 0), GCMode::Generational);
+        assert_eq!(lua.gc_set_mode(GCMode::Generational), GCMode::Incremental);
+        assert_eq!(lua.gc_set_mode(GCMode::Generational), GCMode::Generational);
+        assert_eq!(lua.gc_set_mode(GCMode::Incremental), GCMode::Generational);
     }
 
+    assert_eq!(lua.gc_set_mode(GCMode::Incremental), GCMode::Incremental);
+
+    // Switching the mode resets the collector parameters to defaults
+    #[cfg(feature = "lua54")]
+    let default_step_multiplier = 100;
+    #[cfg(not(feature = "lua54"))]
+    let default_step_multiplier = 200;
+    lua.gc_inc(400, 800, 0);
+    assert_eq!(lua.gc_set_mode(GCMode::Incremental), GCMode::Incremental);
+    assert_eq!(lua.gc_set_pause(400), 200);
+    assert_eq!(lua.gc_set_step_multiplier(800), default_step_multiplier);
+    lua.gc_set_mode(GCMode::Incremental);
+    assert_eq!(lua.gc_set_pause(200), 200);
+    assert_eq!(
+        lua.gc_set_step_multiplier(default_step_multiplier),
+        default_step_multiplier
+    );
+
     #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52", feature = "luau"))]
     {
         assert!(lua.gc_is_running());
@@ -104,6 +151,39 @@ fn test_gc_control() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_gc_state() -> Result<()> {
+    let lua = Lua::new();
+
+    let state = lua.gc_state();
+    assert_eq!(state.mode, GCMode::Incremental);
+    assert_eq!(state.cycles, 0);
+    assert!(state.used_memory > 0);
+    #[cfg(any(feature = "lua54", feature = "lua53", feature = "lua52", feature = "luau"))]
+    {
+        assert_eq!(state.is_running, Some(true));
+        lua.gc_stop();
+        assert_eq!(lua.gc_state().is_running, Some(false));
+        lua.gc_restart();
+    }
+    #[cfg(any(feature = "lua51", feature = "luajit"))]
+    assert_eq!(state.is_running, None);
+
+    lua.gc_collect()?;
+    lua.gc_collect()?;
+    assert_eq!(lua.gc_state().cycles, 2);
+
+    #[cfg(feature = "lua54")]
+    {
+        lua.gc_gen(0, 0);
+        assert_eq!(lua.gc_state().mode, GCMode::Generational);
+        lua.gc_inc(0, 0, 0);
+        assert_eq!(lua.gc_state().mode, GCMode::Incremental);
+    }
+
+    Ok(())
+}
+
 #[cfg(any(feature = "lua53", feature = "lua52"))]
 #[test]
 fn test_gc_error() {
@@ -131,3 +211,80 @@ fn test_gc_error() {
         Ok(()) => panic!("__gc error did not result in error"),
     }
 }
+
+#[test]
+fn test_shrink_ref_stack() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua.create_table()?;
+    t.set("x", 1)?;
+
+    // A handle at the top of the stack prevents shrinking
+    let mut tables = (0..5000)
+        .map(|_| lua.create_table())
+        .collect::<Result<Vec<_>>>()?;
+    let last = tables.pop().unwrap();
+    drop(tables);
+    assert_eq!(lua.shrink_ref_stack(), 0);
+
+    drop(last);
+    assert!(lua.shrink_ref_stack() > 4000);
+    assert_eq!(lua.shrink_ref_stack(), 0);
+
+    // Existing handles are still valid and the stack can grow again
+    assert_eq!(t.get::<i32>("x")?, 1);
+    let tables = (0..5000)
+        .map(|_| lua.create_table())
+        .collect::<Result<Vec<_>>>()?;
+    tables[4999].set("y", 2)?;
+    assert_eq!(tables[4999].get::<i32>("y")?, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_gc_count() -> Result<()> {
+    let lua = Lua::new();
+    lua.gc_collect()?;
+    let before = lua.gc_count();
+    assert!(before > 0);
+
+    let table = lua.create_sequence_from(0..100_000)?;
+    let grown = lua.gc_count();
+    assert!(grown > before + 100_000, "{grown} <= {before}");
+
+    drop(table);
+    lua.gc_collect()?;
+    lua.gc_collect()?;
+    assert!(lua.gc_count() < grown);
+
+    Ok(())
+}
+
+#[test]
+fn test_peak_memory() -> Result<()> {
+    let lua = Lua::new();
+    lua.gc_collect()?;
+    lua.reset_peak_memory();
+    let base = lua.used_memory();
+    assert_eq!(lua.peak_memory(), Some(base));
+
+    let table = lua.create_sequence_from(0..100_000)?;
+    let peak = lua.peak_memory().unwrap();
+    assert!(
+        peak >= lua.used_memory() && peak > base + 100_000,
+        "{peak} <= {base}"
+    );
+
+    drop(table);
+    lua.gc_collect()?;
+    lua.gc_collect()?;
+    assert!(lua.used_memory() < peak);
+    // Peak stays at (or above) the high-water mark until reset
+    assert!(lua.peak_memory().unwrap() >= peak);
+
+    lua.reset_peak_memory();
+    assert!(lua.peak_memory().unwrap() < peak);
+
+    Ok(())
+}
@@ -28,6 +28,22 @@ fn test_module_multi() -> Result<()> {
     .exec()
 }
 
+#[test]
+fn test_module_non_table() -> Result<()> {
+    let lua = make_lua()?;
+    lua.load(
+        r#"
+        local mod = require("test_module")
+        local greet = require("test_module.function")
+        assert(greet("world") == "hello, world")
+        local ud = require("test_module.userdata")
+        assert(mod.check_userdata(ud) == 321)
+        assert(require("test_module.userdata") == ud)
+    "#,
+    )
+    .exec()
+}
+
 #[test]
 fn test_module_error() -> Result<()> {
     let lua = make_lua()?;
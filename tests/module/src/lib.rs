@@ -45,6 +45,16 @@ fn test_module_new_vm(lua: &Lua) -> LuaResult<LuaTable> {
     Ok(exports)
 }
 
+#[mlua::lua_module]
+fn test_module_function(lua: &Lua) -> LuaResult<LuaFunction> {
+    lua.create_function(|_, name: String| Ok(format!("hello, {name}")))
+}
+
+#[mlua::lua_module]
+fn test_module_userdata(lua: &Lua) -> LuaResult<LuaAnyUserData> {
+    lua.create_userdata(MyUserData(321))
+}
+
 #[mlua::lua_module]
 fn test_module_error(_: &Lua) -> LuaResult<LuaTable> {
     Err("custom module error".into_lua_err())
@@ -1,4 +1,7 @@
-use mlua::{Error, ExternalError, Integer, IntoLuaMulti, Lua, MultiValue, Result, String, Value, Variadic};
+use mlua::{
+    Error, ExternalError, FixedArgs, Function, Integer, IntoLuaMulti, Lua, MultiValue, Result, Spread,
+    String, Value, Variadic,
+};
 
 #[test]
 fn test_result_conversions() -> Result<()> {
@@ -72,6 +75,80 @@ fn test_multivalue() {
     let _multi2 = MultiValue::from_vec(vec);
 }
 
+#[test]
+fn test_spread() -> Result<()> {
+    let lua = Lua::new();
+
+    let f = lua.create_function(|_, n: usize| Ok(Spread((1..=n).collect::<Vec<_>>())))?;
+    lua.globals().set("f", f)?;
+    assert_eq!(lua.load("select('#', f(3))").eval::<i64>()?, 3);
+    assert_eq!(lua.load("{f(3)}").eval::<Vec<i64>>()?, [1, 2, 3]);
+    assert_eq!(lua.load("select('#', f(0))").eval::<i64>()?, 0);
+
+    // Plain vector is returned as a table
+    let g = lua.create_function(|_, ()| Ok(vec![1, 2, 3]))?;
+    assert_eq!(g.call::<MultiValue>(())?.len(), 1);
+
+    // Vectors larger than the limit cannot be spread
+    let n = Spread::<()>::MAX_VALUES;
+    assert_eq!(lua.load("return select('#', f(...))").call::<usize>(n)?, n);
+    let err = lua.load("f(...)").call::<()>(n + 1).unwrap_err();
+    assert!(err.to_string().contains("too many values to spread"), "{err}");
+
+    Ok(())
+}
+
+#[test]
+fn test_multivalue_vec_roundtrip() -> Result<()> {
+    let lua = Lua::new();
+
+    // Pass a large number of values through a Lua function and back
+    let values = (0..1000).map(Value::Integer).collect::<Vec<_>>();
+    let identity = lua.load("function(...) return ... end").eval::<Function>()?;
+    let result = identity.call::<MultiValue>(MultiValue::from_vec(values.clone()))?;
+    assert_eq!(result.into_vec(), values);
+
+    Ok(())
+}
+
+#[test]
+fn test_multivalue_pack() -> Result<()> {
+    let lua = Lua::new();
+
+    let multi = MultiValue::pack(["a", "b"], &lua)?;
+    assert_eq!(multi.len(), 2);
+    assert_eq!(multi[1].as_str().unwrap(), "b");
+
+    let multi = mlua::multi![&lua; 1, "two", None::<bool>, 4.5,]?;
+    assert_eq!(multi.len(), 4);
+    assert_eq!(multi[0], Value::Integer(1));
+    assert_eq!(multi[1].as_str().unwrap(), "two");
+    assert_eq!(multi[2], Value::Nil);
+    assert_eq!(multi[3], Value::Number(4.5));
+
+    assert!(mlua::multi![&lua;]?.is_empty());
+
+    // Return heterogeneous values from a function
+    let f = lua.create_function(|lua, n: i64| {
+        let mut multi = mlua::multi![lua; "count", n]?;
+        multi.extend(MultiValue::pack(1..=n, lua)?);
+        Ok(multi)
+    })?;
+    let (name, n, rest): (String, i64, Variadic<i64>) = f.call(3)?;
+    assert_eq!(name, "count");
+    assert_eq!(n, 3);
+    assert_eq!(rest.as_slice(), &[1, 2, 3]);
+
+    // `?` inside the values applies to the enclosing function
+    fn pack_opt(lua: &Lua, n: Option<i64>) -> Option<usize> {
+        Some(mlua::multi![lua; n?, "two"].ok()?.len())
+    }
+    assert_eq!(pack_opt(&lua, Some(1)), Some(2));
+    assert_eq!(pack_opt(&lua, None), None);
+
+    Ok(())
+}
+
 #[test]
 fn test_variadic() {
     let mut var = Variadic::with_capacity(3);
@@ -83,3 +160,30 @@ fn test_variadic() {
     let var2 = Variadic::from(vec);
     assert_eq!(var2.as_slice(), &[1, 2, 3]);
 }
+
+#[test]
+fn test_fixed_args() -> Result<()> {
+    let lua = Lua::new();
+
+    let add = lua.create_function(|_, FixedArgs((a, b)): FixedArgs<2, (i64, i64)>| Ok(a + b))?;
+    assert_eq!(add.call::<i64>((1, 2))?, 3);
+    for args in [
+        MultiValue::from_vec(vec![Value::Integer(1)]),
+        (1, 2, 3).into_lua_multi(&lua)?,
+    ] {
+        match add.call::<i64>(args) {
+            Err(Error::CallbackError { cause, .. }) => match cause.as_ref() {
+                Error::RuntimeError(msg) => assert!(msg.starts_with("expected 2 arguments, got")),
+                err => panic!("expected RuntimeError, got {err:?}"),
+            },
+            r => panic!("expected CallbackError, got {r:?}"),
+        }
+    }
+
+    // Default (untyped) form, nil arguments are counted
+    let count = lua.create_function(|_, args: FixedArgs<3>| Ok(args.len()))?;
+    assert_eq!(count.call::<usize>((1, Value::Nil, 3))?, 3);
+    assert!(count.call::<usize>(()).is_err());
+
+    Ok(())
+}
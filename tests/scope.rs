@@ -331,6 +331,53 @@ fn test_scope_userdata_drop() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_scope_nested() -> Result<()> {
+    let lua = Lua::new();
+
+    struct MyUserData<'a>(&'a Cell<i64>, #[allow(unused)] Rc<()>);
+
+    impl UserData for MyUserData<'_> {
+        fn register(reg: &mut UserDataRegistry<Self>) {
+            reg.add_method("inc", |_, data, ()| {
+                data.0.set(data.0.get() + 1);
+                Ok(())
+            });
+        }
+    }
+
+    let (outer_i, outer_rc) = (Cell::new(0), Rc::new(()));
+    let (inner_i, inner_rc) = (Cell::new(0), Rc::new(()));
+    lua.scope(|outer| {
+        let outer_ud = outer.create_userdata(MyUserData(&outer_i, outer_rc.clone()))?;
+        lua.globals().set("outer_ud", outer_ud)?;
+
+        lua.scope(|inner| {
+            let inner_ud = inner.create_userdata(MyUserData(&inner_i, inner_rc.clone()))?;
+            lua.globals().set("inner_ud", inner_ud)?;
+            lua.load("outer_ud:inc(); inner_ud:inc()").exec()?;
+            assert_eq!(Rc::strong_count(&inner_rc), 2);
+            Ok(())
+        })?;
+
+        // Inner scope values must be invalidated as soon as the inner scope ends
+        assert_eq!(Rc::strong_count(&inner_rc), 1);
+        assert!(lua.load("inner_ud:inc()").exec().is_err());
+
+        // Outer scope values are still valid
+        assert_eq!(Rc::strong_count(&outer_rc), 2);
+        lua.load("outer_ud:inc()").exec()?;
+        Ok(())
+    })?;
+
+    assert_eq!(Rc::strong_count(&outer_rc), 1);
+    assert!(lua.load("outer_ud:inc()").exec().is_err());
+    assert_eq!(outer_i.get(), 2);
+    assert_eq!(inner_i.get(), 1);
+
+    Ok(())
+}
+
 #[test]
 fn test_scope_userdata_ref() -> Result<()> {
     let lua = Lua::new();
@@ -278,6 +278,39 @@ fn test_to_value_struct() -> LuaResult<()> {
     .exec()
 }
 
+#[test]
+fn test_create_table_ser() -> LuaResult<()> {
+    let lua = Lua::new();
+
+    #[derive(Serialize)]
+    struct Test {
+        name: String,
+        list: Vec<i32>,
+    }
+
+    let test = Test {
+        name: "alex".to_string(),
+        list: vec![1, 2, 3],
+    };
+    let table = lua.create_table_ser(&test)?;
+    assert_eq!(table.get::<String>("name")?, "alex");
+    assert_eq!(table.get::<Vec<i32>>("list")?, vec![1, 2, 3]);
+
+    let seq = lua.create_table_ser(&[4, 5])?;
+    assert_eq!(
+        seq.sequence_values::<i32>().collect::<LuaResult<Vec<_>>>()?,
+        vec![4, 5]
+    );
+
+    // Scalars cannot be converted to a table
+    match lua.create_table_ser(&123) {
+        Err(Error::FromLuaConversionError { to, .. }) => assert_eq!(to, "table"),
+        r => panic!("expected FromLuaConversionError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_to_value_enum() -> LuaResult<()> {
     let lua = Lua::new();
@@ -683,6 +716,88 @@ fn test_from_value_sorted() -> Result<(), Box<dyn StdError>> {
     .exec()
     .unwrap();
 
+    // Keys are visited in sorted order
+    struct KeyOrder(Vec<String>);
+
+    impl<'de> Deserialize<'de> for KeyOrder {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct Visitor;
+
+            impl<'de> serde::de::Visitor<'de> for Visitor {
+                type Value = KeyOrder;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("a map")
+                }
+
+                fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<KeyOrder, A::Error> {
+                    let mut keys = Vec::new();
+                    while let Some((key, _)) = map.next_entry::<String, serde::de::IgnoredAny>()? {
+                        keys.push(key);
+                    }
+                    Ok(KeyOrder(keys))
+                }
+            }
+
+            deserializer.deserialize_map(Visitor)
+        }
+    }
+
+    let value = lua.load("{d = 1, a = 2, c = 3, b = 4}").eval::<Value>()?;
+    let order: KeyOrder = lua.from_value_with(value, DeserializeOptions::new().sort_keys(true))?;
+    assert_eq!(order.0, vec!["a", "b", "c", "d"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_from_value_empty_tables() -> Result<(), Box<dyn StdError>> {
+    use mlua::serde::de::EmptyTableEncoding;
+
+    let lua = Lua::new();
+
+    let to_json = |encoding| -> Result<serde_json::Value, Box<dyn StdError>> {
+        let value = lua.load("{}").eval::<Value>()?;
+        let options = DeserializeOptions::new().encode_empty_tables_as(encoding);
+        Ok(lua.from_value_with(value, options)?)
+    };
+    assert_eq!(to_json(EmptyTableEncoding::Object)?, serde_json::json!({}));
+    assert_eq!(to_json(EmptyTableEncoding::Array)?, serde_json::json!([]));
+    assert_eq!(to_json(EmptyTableEncoding::Null)?, serde_json::Value::Null);
+
+    // Default is an empty object
+    let value = lua.load("{}").eval::<Value>()?;
+    assert_eq!(lua.from_value::<serde_json::Value>(value)?, serde_json::json!({}));
+
+    // Tables with a known shape are not affected
+    let options = DeserializeOptions::new().encode_empty_tables_as(EmptyTableEncoding::Null);
+    let value = Value::Table(lua.create_sequence_from::<i32>([])?);
+    value
+        .as_table()
+        .unwrap()
+        .set_metatable(Some(lua.array_metatable()));
+    assert_eq!(
+        lua.from_value_with::<serde_json::Value>(value, options)?,
+        serde_json::json!([])
+    );
+    let value = lua.load("{a = {}}").eval::<Value>()?;
+    let json: serde_json::Value = lua.from_value_with(value, options)?;
+    assert_eq!(json, serde_json::json!({"a": null}));
+
+    Ok(())
+}
+
+#[test]
+fn test_from_value_large_sequence() -> Result<(), Box<dyn StdError>> {
+    let lua = Lua::new();
+
+    let value = lua
+        .load("local t = {} for i = 1, 1000000 do t[i] = i / 2 end return t")
+        .eval::<Value>()?;
+    let vec: Vec<f64> = lua.from_value(value)?;
+    assert_eq!(vec.len(), 1000000);
+    assert!(vec.iter().enumerate().all(|(i, &v)| v == (i + 1) as f64 / 2.0));
+
     Ok(())
 }
 
@@ -745,3 +860,80 @@ fn test_buffer_from_value() -> LuaResult<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_value() -> Result<(), Box<dyn StdError>> {
+    let lua = Lua::new();
+
+    let doc: toml::Value = toml::from_str(
+        r#"
+        name = "mlua"
+        version = 1
+        ratio = 0.5
+        released = 2024-01-02T03:04:05Z
+        tags = ["lua", "rust"]
+        "#,
+    )?;
+    let value = lua.value_from_toml(&doc)?;
+    lua.globals().set("doc", &value)?;
+    lua.load(
+        r#"
+        assert(doc.name == "mlua")
+        assert(doc.version == 1)
+        assert(doc.ratio == 0.5)
+        assert(doc.released == "2024-01-02T03:04:05Z")
+        assert(#doc.tags == 2 and doc.tags[2] == "rust")
+        assert(getmetatable(doc.tags) ~= nil)
+    "#,
+    )
+    .exec()?;
+
+    // Round trip (datetime becomes a string)
+    let back = lua.value_to_toml(&value)?;
+    assert_eq!(back["name"].as_str(), Some("mlua"));
+    assert_eq!(back["released"].as_str(), Some("2024-01-02T03:04:05Z"));
+    assert_eq!(back["tags"].as_array().map(|a| a.len()), Some(2));
+
+    // Empty array keeps being an array
+    let empty = lua.value_from_toml(&toml::Value::Array(Vec::new()))?;
+    assert!(lua.value_to_toml(&empty)?.is_array());
+
+    Ok(())
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_value() -> Result<(), Box<dyn StdError>> {
+    let lua = Lua::new();
+
+    let doc: serde_yaml::Value = serde_yaml::from_str(
+        r#"
+        name: mlua
+        version: 1
+        optional: ~
+        list: [1, 2, 3]
+        tagged: !Point 1
+        "#,
+    )?;
+    let value = lua.value_from_yaml(&doc)?;
+    lua.globals().set("doc", &value)?;
+    lua.globals().set("null", lua.null())?;
+    lua.load(
+        r#"
+        assert(doc.name == "mlua")
+        assert(doc.version == 1)
+        assert(doc.optional == null)
+        assert(#doc.list == 3 and doc.list[3] == 3)
+        assert(doc.tagged == "!Point 1")
+    "#,
+    )
+    .exec()?;
+
+    let back = lua.value_to_yaml(&value)?;
+    assert_eq!(back["name"].as_str(), Some("mlua"));
+    assert!(back["optional"].is_null());
+    assert_eq!(back["list"].as_sequence().map(|s| s.len()), Some(3));
+
+    Ok(())
+}
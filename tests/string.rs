@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 use std::collections::HashSet;
 
-use mlua::{Lua, Result, String};
+use mlua::{Lua, LuaOptions, Result, StdLib, String};
 
 #[test]
 fn test_string_compare() {
@@ -157,3 +157,34 @@ fn test_bytes_into_iter() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_string_lib_methods() -> Result<()> {
+    let lua = Lua::new();
+
+    let s = lua.create_string("Hello, World")?;
+    assert_eq!(s.upper()?, "HELLO, WORLD");
+    assert_eq!(s.lower()?, "hello, world");
+    assert_eq!(s.byte_at(1)?, Some(b'H'));
+    assert_eq!(s.byte_at(-1)?, Some(b'd'));
+    assert_eq!(s.byte_at(100)?, None);
+
+    assert_eq!(s.find("o, W")?, Some((5, 8)));
+    assert_eq!(s.find("%d+")?, None);
+    assert!(s.find("[").is_err());
+
+    assert_eq!(s.match_pattern("(%a+), (%a+)")?.unwrap(), ["Hello", "World"]);
+    assert_eq!(s.match_pattern("W%a+")?.unwrap(), ["World"]);
+    assert_eq!(s.match_pattern("()o")?.unwrap(), ["5"]);
+    assert_eq!(s.match_pattern("%d")?, None);
+
+    // Without the `string` library
+    let lua = Lua::new_with(StdLib::NONE, LuaOptions::default())?;
+    let s = lua.create_string("abc")?;
+    match s.upper() {
+        Err(err) => assert!(err.to_string().contains("string library is not loaded")),
+        r => panic!("expected error, got {r:?}"),
+    }
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use mlua::{Error, Lua, ObjectLike, Result, Table, Value};
+use std::collections::HashMap;
+
+use mlua::{Error, FromLua, Function, IntoLua, Lua, ObjectLike, Result, Table, Value};
 
 #[test]
 fn test_globals_set_get() -> Result<()> {
@@ -15,6 +17,29 @@ fn test_globals_set_get() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_globals_identity() -> Result<()> {
+    let lua = Lua::new();
+
+    let globals = lua.globals();
+    assert_eq!(globals, lua.globals());
+    assert_eq!(lua.load("_G").eval::<Table>()?, globals);
+
+    // The environment of the current thread can be replaced in Lua 5.1
+    #[cfg(any(feature = "lua51", feature = "luajit"))]
+    {
+        let f = lua.create_function(|lua, ()| Ok(lua.globals()))?;
+        lua.globals().set("f", f)?;
+        let env = lua
+            .load("local env = setmetatable({}, {__index = _G}); setfenv(0, env); return env, f()")
+            .eval::<(Table, Table)>()?;
+        assert_eq!(env.0, env.1);
+        assert_eq!(lua.globals(), env.0);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_table() -> Result<()> {
     let lua = Lua::new();
@@ -130,6 +155,28 @@ fn test_table_insert_remove() -> Result<()> {
     // Wrong index, tables are 1-indexed
     assert!(table4.raw_insert(0, "123").is_err());
 
+    // Head, middle and tail of a 5-element sequence, bypassing metamethods
+    let seq = lua.create_sequence_from([1, 2, 3, 4, 5])?;
+    let mt = lua.create_table()?;
+    mt.set(
+        "__newindex",
+        lua.create_function(|_, ()| Err::<(), _>(Error::runtime("__newindex called")))?,
+    )?;
+    seq.set_metatable(Some(mt));
+    seq.raw_insert(1, 0)?;
+    seq.raw_insert(4, 25)?;
+    seq.raw_insert(8, 6)?;
+    assert_eq!(
+        seq.sequence_values().collect::<Result<Vec<i64>>>()?,
+        vec![0, 1, 2, 25, 3, 4, 5, 6]
+    );
+    assert!(seq.raw_insert(10, 8).is_err());
+    seq.raw_remove(4)?;
+    assert_eq!(
+        seq.sequence_values().collect::<Result<Vec<i64>>>()?,
+        vec![0, 1, 2, 3, 4, 5, 6]
+    );
+
     Ok(())
 }
 
@@ -260,6 +307,40 @@ fn test_table_for_each() -> Result<()> {
     })?;
     assert_eq!(i, 5);
 
+    // Sum of values (numeric keys must not be converted in place)
+    let table = lua.create_table()?;
+    for i in 1..=100 {
+        table.set(i, i)?;
+        table.set(format!("k{i}"), i)?;
+    }
+    let mut sum = 0;
+    table.for_each::<Value, i64>(|_, v| {
+        sum += v;
+        Ok(())
+    })?;
+    assert_eq!(sum, 2 * 5050);
+
+    // Iteration stops on the first error
+    let mut stopped = false;
+    let result = table.for_each::<Value, i64>(|k, _| {
+        assert!(!stopped, "closure called after error");
+        match k.as_str().as_deref() {
+            Some("k50") => {
+                stopped = true;
+                Err(Error::runtime("stop"))
+            }
+            _ => Ok(()),
+        }
+    });
+    assert!(matches!(result, Err(Error::RuntimeError(msg)) if msg == "stop"));
+    assert!(stopped);
+    let mut after = 0;
+    table.for_each::<Value, i64>(|_, _| {
+        after += 1;
+        Ok(())
+    })?;
+    assert_eq!(after, 200);
+
     Ok(())
 }
 
@@ -304,6 +385,22 @@ fn test_metatable() -> Result<()> {
     table.set_metatable(None);
     assert_eq!(table.get::<Value>("any_key")?, Value::Nil);
 
+    // Replace and return the previous metatable
+    let metatable = lua.create_table_from([("__index", lua.create_table_from([("k", 1)])?)])?;
+    assert!(table.replace_metatable(Some(metatable.clone()))?.is_none());
+    assert_eq!(table.get::<i32>("k")?, 1);
+    let previous = table.replace_metatable(Some(lua.create_table()?))?;
+    assert_eq!(previous.as_ref(), Some(&metatable));
+    assert_eq!(table.get::<Value>("k")?, Value::Nil);
+    table.replace_metatable(previous)?;
+    assert_eq!(table.get::<i32>("k")?, 1);
+    assert_eq!(table.replace_metatable(None)?, Some(metatable));
+
+    // Protected metatable
+    table.set_metatable(Some(lua.create_table_from([("__metatable", false)])?));
+    let err = table.replace_metatable(None).unwrap_err();
+    assert!(err.to_string().contains("protected metatable"), "{err}");
+
     Ok(())
 }
 
@@ -352,6 +449,260 @@ fn test_table_pointer() -> Result<()> {
     assert_eq!(table1.to_pointer(), table1.clone().to_pointer());
     assert_ne!(table1.to_pointer(), table2.to_pointer());
 
+    // Tables can be used as hash set elements (by identity, ignoring `__eq`)
+    let mt = lua.create_table()?;
+    mt.set("__eq", lua.create_function(|_, _: (Table, Table)| Ok(true))?)?;
+    table1.set_metatable(Some(mt.clone()));
+    table2.set_metatable(Some(mt));
+    assert!(table1.equals(&table2)?);
+    let set = std::collections::HashSet::from([table1.clone(), table1.clone(), table2]);
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&table1));
+
+    Ok(())
+}
+
+#[test]
+fn test_table_set_checked() -> Result<()> {
+    let lua = Lua::new();
+
+    let table = lua.create_table()?;
+    table.set_checked("a", 1)?;
+    assert_eq!(table.get::<i32>("a")?, 1);
+
+    // Metatable without `__newindex` is fine
+    table.set_metatable(Some(lua.create_table_from([("__index", lua.create_table()?)])?));
+    table.set_checked("b", 2)?;
+    assert_eq!(table.raw_get::<i32>("b")?, 2);
+
+    let mt = lua.create_table()?;
+    mt.set("__newindex", lua.create_function(|_, ()| Ok(()))?)?;
+    table.set_metatable(Some(mt));
+    match table.set_checked("c", 3) {
+        Err(Error::RuntimeError(msg)) => assert_eq!(msg, "table has a __newindex metamethod"),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+    assert!(table.raw_get::<Option<i32>>("c")?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_table_get_or_create_table() -> Result<()> {
+    let lua = Lua::new();
+
+    let root = lua.create_table()?;
+    let c = root
+        .get_or_create_table("a")?
+        .get_or_create_table("b")?
+        .get_or_create_table("c")?;
+    c.set("value", 1)?;
+
+    // Existing tables are reused
+    let b = root.get_or_create_table("a")?.get_or_create_table("b")?;
+    assert_eq!(b.get::<Table>("c")?, c);
+
+    lua.globals().set("root", &root)?;
+    assert_eq!(lua.load("root.a.b.c.value").eval::<i32>()?, 1);
+
+    root.set("x", 123)?;
+    match root.get_or_create_table("x") {
+        Err(Error::RuntimeError(msg)) => assert!(msg.contains("integer")),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_table_get_or_insert_with() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua.create_table()?;
+
+    // Insert path
+    assert_eq!(t.get_or_insert_with("a", || 1)?, 1);
+    assert_eq!(t.raw_get::<i32>("a")?, 1);
+
+    // Hit path: `f` is not called
+    assert_eq!(t.get_or_insert_with("a", || -> i32 { unreachable!() })?, 1);
+    assert!(t.get_or_insert_with::<String>("a", || unreachable!()).is_ok());
+
+    // The produced value fails to convert: nothing is inserted
+    struct Invalid;
+    impl IntoLua for Invalid {
+        fn into_lua(self, _: &Lua) -> Result<Value> {
+            Err(Error::runtime("invalid value"))
+        }
+    }
+    impl FromLua for Invalid {
+        fn from_lua(_: Value, _: &Lua) -> Result<Self> {
+            Ok(Invalid)
+        }
+    }
+    assert!(t.get_or_insert_with("b", || Invalid).is_err());
+    assert_eq!(t.raw_get::<Value>("b")?, Value::Nil);
+
+    // Metamethods are not invoked
+    let mt = lua.create_table()?;
+    mt.set("__index", lua.create_function(|_, ()| Ok(123))?)?;
+    t.set_metatable(Some(mt));
+    assert_eq!(t.get_or_insert_with("c", || 2)?, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_table_replace_function() -> Result<()> {
+    let lua = Lua::new();
+
+    let module = lua
+        .load("return { greet = function() return 'v1' end }")
+        .eval::<Table>()?;
+    let handlers = lua.create_table()?;
+    handlers.set("on_greet", module.get::<Function>("greet")?)?;
+
+    let new = lua.load("return function() return 'v2' end").eval::<Function>()?;
+    let old = module.replace_function("greet", new.clone())?.unwrap();
+    assert_eq!(module.get::<Function>("greet")?.call::<String>(())?, "v2");
+
+    // Update the references that still point to the old version
+    let on_greet = handlers.get::<Function>("on_greet")?;
+    assert!(on_greet.ptr_eq(&old));
+    handlers.replace_function("on_greet", new.clone())?;
+    assert!(handlers.get::<Function>("on_greet")?.ptr_eq(&new));
+
+    assert!(module.replace_function("missing", new.clone())?.is_none());
+    module.set("data", 1)?;
+    assert!(module.replace_function("data", new).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_table_builder() -> Result<()> {
+    let lua = Lua::new();
+
+    let mt = lua.create_table()?;
+    mt.set(
+        "__index",
+        lua.create_function(|_, (_, key): (Table, String)| Ok(format!("<{key}>")))?,
+    )?;
+    let obj = lua
+        .table_builder()
+        .field("name", "counter")
+        .field(1, "first")
+        .field("count", 10)
+        .method("add", |_, this: Table, n: i64| {
+            let count = this.get::<i64>("count")? + n;
+            this.set("count", count)?;
+            Ok(count)
+        })
+        .metatable(mt.clone())
+        .build()?;
+
+    assert_eq!(obj.get::<String>("name")?, "counter");
+    assert_eq!(obj.get::<String>(1)?, "first");
+    assert_eq!(obj.metatable(), Some(mt));
+    lua.globals().set("obj", &obj)?;
+    assert_eq!(lua.load("obj:add(5)").eval::<i64>()?, 15);
+    assert_eq!(lua.load("obj.missing").eval::<String>()?, "<missing>");
+
+    // Conversion errors are reported by `build`
+    struct Invalid;
+    impl mlua::IntoLua for Invalid {
+        fn into_lua(self, _: &Lua) -> Result<Value> {
+            Err(Error::runtime("invalid value"))
+        }
+    }
+    let res = lua.table_builder().field("a", Invalid).field("b", 2).build();
+    assert!(matches!(res, Err(Error::RuntimeError(msg)) if msg == "invalid value"));
+
+    Ok(())
+}
+
+#[test]
+fn test_table_diff() -> Result<()> {
+    let lua = Lua::new();
+
+    let old = lua
+        .load("{ host = 'localhost', port = 80, debug = true, nested = {}, [1] = 'a' }")
+        .eval::<Table>()?;
+    let new = lua.create_table()?;
+    new.set("host", "localhost")?;
+    new.set("port", 8080)?;
+    new.set("nested", old.get::<Table>("nested")?)?;
+    new.set(1, "a")?;
+    new.set("timeout", 30)?;
+
+    let keys = |keys: &[Value]| keys.iter().map(|k| k.to_string()).collect::<Result<Vec<_>>>();
+    let diff = old.diff(&new)?;
+    assert_eq!(keys(&diff.added)?, ["timeout"]);
+    assert_eq!(keys(&diff.removed)?, ["debug"]);
+    assert_eq!(keys(&diff.changed)?, ["port"]);
+    assert!(!diff.is_empty());
+
+    assert!(old.diff(&old)?.is_empty());
+
+    // Nested tables are compared by identity
+    new.set("nested", lua.create_table()?)?;
+    assert_eq!(old.diff(&new)?.changed.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_table_numeric_slice() -> Result<()> {
+    let lua = Lua::new();
+
+    let numbers = [1.5, 2.0, -3.25];
+    let t = lua.create_sequence_from_slice(&numbers)?;
+    assert_eq!(t.raw_len(), 3);
+    assert_eq!(t.get::<f64>(3)?, -3.25);
+
+    let mut out = vec![0.0f64];
+    t.read_numeric_slice(&mut out)?;
+    assert_eq!(out, [0.0, 1.5, 2.0, -3.25]);
+
+    let ints: Vec<mlua::Integer> = (1..=1000).collect();
+    let t = lua.create_sequence_from_slice(&ints)?;
+    assert_eq!(lua.load("local t = ...; return t[#t]").call::<i64>(&t)?, 1000);
+    let mut out = Vec::<mlua::Integer>::new();
+    t.read_numeric_slice(&mut out)?;
+    assert_eq!(out, ints);
+
+    // Non-numeric elements
+    let t = lua.load("{1, 2, 'x'}").eval::<Table>()?;
+    let mut out = vec![0.0f64];
+    match t.read_numeric_slice::<f64>(&mut out) {
+        Err(Error::FromLuaConversionError { from: "string", .. }) => {}
+        r => panic!("expected FromLuaConversionError, got {r:?}"),
+    }
+    // Output is not modified on error
+    assert_eq!(out, [0.0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_table_pairs_sorted() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua.load("{c = 3, a = 1, b = 2, d = 4}").eval::<Table>()?;
+    let pairs = t.pairs_sorted::<String, i32>()?;
+    assert_eq!(
+        pairs,
+        [("a".into(), 1), ("b".into(), 2), ("c".into(), 3), ("d".into(), 4)]
+    );
+
+    let t = lua.load("{[10] = 'x', [2] = 'y', [-1] = 'z'}").eval::<Table>()?;
+    let pairs = t.pairs_sorted::<i64, String>()?;
+    assert_eq!(pairs, [(-1, "z".into()), (2, "y".into()), (10, "x".into())]);
+
+    // Mixed key types
+    let t = lua.load("{1, 2, a = 3}").eval::<Table>()?;
+    assert!(t.pairs_sorted::<i64, i64>().is_err());
+
     Ok(())
 }
 
@@ -385,6 +736,7 @@ fn test_table_error() -> Result<()> {
     assert!(bad_table.raw_set(1, 1).is_ok());
     assert!(bad_table.raw_get::<i32>(1).is_ok());
     assert_eq!(bad_table.raw_len(), 1);
+    assert_eq!(bad_table.len_usize()?, 1);
 
     Ok(())
 }
@@ -473,3 +825,219 @@ fn test_table_object_like() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "macros")]
+#[test]
+fn test_table_into_struct() -> Result<()> {
+    let lua = Lua::new();
+
+    #[derive(Debug, PartialEq, mlua::FromLua)]
+    #[mlua(from_table)]
+    struct Server {
+        host: String,
+        port: u16,
+        tags: Vec<String>,
+        timeout: Option<f64>,
+    }
+
+    #[derive(Debug, PartialEq, mlua::FromLua)]
+    #[mlua(from_table)]
+    struct Config {
+        name: String,
+        server: Server,
+    }
+
+    let table: Table = lua
+        .load(r#"{name = "app", server = {host = "localhost", port = 8080, tags = {"a", "b"}}}"#)
+        .eval()?;
+    let config: Config = table.into_struct()?;
+    assert_eq!(
+        config,
+        Config {
+            name: "app".into(),
+            server: Server {
+                host: "localhost".into(),
+                port: 8080,
+                tags: vec!["a".into(), "b".into()],
+                timeout: None,
+            },
+        }
+    );
+
+    // Error includes the field name
+    let table: Table = lua
+        .load(r#"{name = "app", server = {host = "localhost"}}"#)
+        .eval()?;
+    let err = table.into_struct::<Config>().unwrap_err().to_string();
+    assert!(err.contains("field 'server' of Config"), "{err}");
+    assert!(err.contains("field 'port' of Server"), "{err}");
+
+    // Non-table value
+    let err = lua.unpack::<Config>(Value::Integer(1)).unwrap_err();
+    assert!(matches!(err, Error::FromLuaConversionError { .. }));
+
+    Ok(())
+}
+
+#[test]
+fn test_table_deep_clone() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua
+        .load(
+            r#"
+        local shared = {value = 1}
+        local t = {a = {shared = shared}, b = {shared = shared}, f = print, [{}] = "key"}
+        t.self = t
+        return setmetatable(t, {__index = {x = 1}})
+    "#,
+        )
+        .eval::<Table>()?;
+    let copy = t.deep_clone()?;
+    assert_ne!(copy, t);
+
+    // Self-referential table points to the copy
+    assert_eq!(copy.get::<Table>("self")?, copy);
+
+    // Diamond-shaped shared subtable is copied once
+    let shared_a = copy.get::<Table>("a")?.get::<Table>("shared")?;
+    let shared_b = copy.get::<Table>("b")?.get::<Table>("shared")?;
+    assert_eq!(shared_a, shared_b);
+    assert_ne!(shared_a, t.get::<Table>("a")?.get::<Table>("shared")?);
+    shared_a.set("value", 2)?;
+    assert_eq!(
+        t.get::<Table>("a")?.get::<Table>("shared")?.get::<i32>("value")?,
+        1
+    );
+
+    // Functions and metatables are shared
+    assert_eq!(copy.get::<Function>("f")?, t.get::<Function>("f")?);
+    assert_eq!(copy.metatable(), t.metatable());
+    assert_eq!(copy.get::<i32>("x")?, 1);
+
+    // Table keys are copied
+    let keys = copy.pairs::<Value, Value>().collect::<Result<Vec<_>>>()?;
+    let (key, _) = keys
+        .iter()
+        .find(|(_, v)| v.as_str().as_deref() == Some("key"))
+        .unwrap();
+    assert!(key.is_table());
+    assert!(!t.contains_key(key.clone())?);
+
+    Ok(())
+}
+
+#[test]
+fn test_create_table_from_with_capacity() -> Result<()> {
+    let lua = Lua::new();
+
+    let map: HashMap<String, usize> = (0..10_000).map(|i| (format!("key{i}"), i)).collect();
+    // `filter` reports zero as the lower bound of the size hint
+    let iter = map.iter().filter(|_| true).map(|(k, &v)| (k.as_str(), v));
+    let t = lua.create_table_from_with_capacity(0, map.len(), iter)?;
+    assert_eq!(t.pairs::<String, usize>().count(), 10_000);
+    for (k, v) in &map {
+        assert_eq!(t.get::<usize>(k.as_str())?, *v);
+    }
+
+    // Sequence part
+    let t = lua.create_table_from_with_capacity(3, 0, (1..=3).map(|i| (i, i * 10)))?;
+    assert_eq!(t.raw_len(), 3);
+    assert_eq!(t.get::<i32>(3)?, 30);
+
+    Ok(())
+}
+
+#[test]
+fn test_try_create_table_from() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua.try_create_table_from([("a", 1), ("b", 2)])?;
+    assert_eq!(t.get::<i32>("a")?, 1);
+    assert_eq!(t.get::<i32>("b")?, 2);
+
+    // Value that is parsed as an integer during conversion
+    struct Num(&'static str);
+    impl IntoLua for Num {
+        fn into_lua(self, _: &Lua) -> Result<Value> {
+            self.0.parse().map(Value::Integer).map_err(Error::external)
+        }
+    }
+
+    let entries = ["a", "b", "c", "d"]
+        .into_iter()
+        .zip(["1", "2", "x", "4"].map(Num));
+    let err = lua.try_create_table_from(entries).unwrap_err().to_string();
+    assert!(err.contains("at index 2"), "{err}");
+    assert!(err.contains(r#"key "c""#), "{err}");
+
+    Ok(())
+}
+
+#[test]
+fn test_table_sequence_view() -> Result<()> {
+    let lua = Lua::new();
+
+    let table: Table = lua.load("{1, 2, 3, key = 'x'}").eval()?;
+    let seq = table.sequence::<i64>();
+    assert_eq!(seq.len(), 3);
+    assert!(!seq.is_empty());
+    assert_eq!(seq.get(0)?, Some(1));
+    assert_eq!(seq.get(2)?, Some(3));
+    assert_eq!(seq.get(3)?, None);
+    assert_eq!(seq.iter().collect::<Result<Vec<_>>>()?, vec![1, 2, 3]);
+
+    // The view reflects changes to the table
+    table.raw_push(4)?;
+    assert_eq!(seq.len(), 4);
+    assert_eq!(seq.get(3)?, Some(4));
+
+    // Conversion errors are reported, not hidden
+    table.raw_set(1, "not a number")?;
+    assert!(seq.get(0).is_err());
+
+    let empty = lua.create_table()?;
+    let seq = empty.sequence::<Value>();
+    assert!(seq.is_empty());
+    assert_eq!(seq.get(0)?, None);
+    assert_eq!(seq.iter().count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_table_scalar_getters() -> Result<()> {
+    let lua = Lua::new();
+
+    let t = lua
+        .load(r#"{i = 3, f = 1.5, whole = 2.0, b = false, s = "5", [1] = 10}"#)
+        .eval::<Table>()?;
+    assert_eq!(t.get_integer("i")?, Some(3));
+    assert_eq!(t.get_integer("whole")?, Some(2));
+    assert_eq!(t.get_integer("f")?, None);
+    assert_eq!(t.get_integer("s")?, None);
+    assert_eq!(t.get_integer("missing")?, None);
+    assert_eq!(t.get_integer(1)?, Some(10));
+    assert_eq!(t.get_number("f")?, Some(1.5));
+    assert_eq!(t.get_number("i")?, Some(3.0));
+    assert_eq!(t.get_number("b")?, None);
+    assert_eq!(t.get_boolean("b")?, Some(false));
+    assert_eq!(t.get_boolean("i")?, None);
+
+    // Metamethods are not invoked
+    let mt = lua.create_table_from([("__index", lua.create_table_from([("x", 1)])?)])?;
+    t.set_metatable(Some(mt));
+    assert_eq!(t.get_integer("x")?, None);
+
+    // Parity with the generic path
+    let seq = lua.create_sequence_from(1..=10_000)?;
+    let (mut fast, mut generic) = (0, 0);
+    for i in 1..=seq.raw_len() {
+        fast += seq.get_integer(i)?.unwrap();
+        generic += seq.get::<i64>(i)?;
+    }
+    assert_eq!(fast, generic);
+    assert_eq!(fast, 50_005_000);
+
+    Ok(())
+}
@@ -8,8 +8,8 @@ use std::sync::Arc;
 use std::{error, f32, f64, fmt};
 
 use mlua::{
-    ChunkMode, Error, ExternalError, Function, Lua, LuaOptions, Nil, Result, StdLib, String, Table, UserData,
-    Value, Variadic,
+    ChunkMode, Error, ExternalError, FromLua, Function, Lua, LuaOptions, Nil, RegistryStats, Result, StdLib,
+    String, Table, UserData, Value, Variadic,
 };
 
 #[cfg(not(feature = "luau"))]
@@ -66,6 +66,71 @@ fn test_safety() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_open_library_by_name() -> Result<()> {
+    let lua = Lua::new_with(StdLib::NONE, LuaOptions::default())?;
+    assert!(lua.globals().get::<Option<Table>>("string")?.is_none());
+
+    lua.open_library_by_name("string")?;
+    lua.open_library_by_name("math")?;
+    assert_eq!(lua.load("string.rep('a', 3)").eval::<StdString>()?, "aaa");
+    assert_eq!(lua.load("math.abs(-1)").eval::<i32>()?, 1);
+
+    match lua.open_library_by_name("nonexistent") {
+        Err(Error::RuntimeError(msg)) => assert!(msg.contains("unknown standard library")),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
+    #[cfg(not(feature = "luau"))]
+    match lua.open_library_by_name("debug") {
+        Err(Error::SafetyError(_)) => {}
+        r => panic!("expected SafetyError, got {r:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_open_library_into() -> Result<()> {
+    let lua = Lua::new_with(StdLib::NONE, LuaOptions::default())?;
+
+    let os = lua.open_library_into(StdLib::OS)?;
+    assert!(lua.globals().get::<Option<Table>>("os")?.is_none());
+    lua.globals().set("clock", os.get::<Function>("clock")?)?;
+    assert!(lua.load("clock()").eval::<f64>()? >= 0.0);
+
+    // Multiple flags are rejected
+    match lua.open_library_into(StdLib::OS | StdLib::MATH) {
+        Err(Error::RuntimeError(msg)) => assert!(msg.contains("standalone library")),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+
+    #[cfg(not(feature = "luau"))]
+    match lua.open_library_into(StdLib::DEBUG) {
+        Err(Error::SafetyError(_)) => {}
+        r => panic!("expected SafetyError, got {r:?}"),
+    }
+    #[cfg(not(feature = "luau"))]
+    match lua.open_library_into(StdLib::PACKAGE) {
+        Err(Error::SafetyError(_)) => {}
+        r => panic!("expected SafetyError, got {r:?}"),
+    }
+
+    // Already loaded libraries are copied rather than shared with the global
+    let lua = Lua::new();
+    let string = lua.open_library_into(StdLib::STRING)?;
+    let global_string = lua.globals().get::<Table>("string")?;
+    assert_ne!(string, global_string);
+    assert_eq!(
+        string.get::<Function>("len")?,
+        global_string.get::<Function>("len")?
+    );
+    string.set("len", Nil)?;
+    assert!(global_string.contains_key("len")?);
+
+    Ok(())
+}
+
 #[test]
 fn test_load() -> Result<()> {
     let lua = Lua::new();
@@ -704,6 +769,62 @@ fn test_registry_value() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_typed_registry_value() -> Result<()> {
+    let lua = Lua::new();
+
+    let key = lua.create_typed_registry_value("hello".to_string())?;
+    let value = lua.typed_registry_value(&key)?;
+    assert_eq!(value, "hello");
+    assert_eq!(lua.registry_value::<String>(key.as_key())?, "hello");
+
+    // Ownership checks still apply
+    let lua2 = Lua::new();
+    assert!(matches!(
+        lua2.typed_registry_value(&key),
+        Err(Error::MismatchedRegistryKey)
+    ));
+
+    lua.remove_registry_value(key.into_key())?;
+
+    Ok(())
+}
+
+#[test]
+fn test_registry_stats() -> Result<()> {
+    let lua = Lua::new();
+    assert_eq!(lua.registry_stats(), RegistryStats::default());
+
+    let keys = (0..10)
+        .map(|i| lua.create_registry_value(i))
+        .collect::<Result<Vec<_>>>()?;
+    let nil_key = lua.create_registry_value(Nil)?;
+    let stats = lua.registry_stats();
+    assert_eq!((stats.live, stats.pending_free, stats.peak), (10, 0, 10));
+
+    let mut keys = keys.into_iter();
+    lua.remove_registry_value(keys.next().unwrap())?;
+    lua.remove_registry_value(nil_key)?;
+    drop(keys.by_ref().take(4).collect::<Vec<_>>());
+    let stats = lua.registry_stats();
+    assert_eq!((stats.live, stats.pending_free, stats.peak), (5, 4, 10));
+
+    // Dropped slots are reused
+    let _key = lua.create_registry_value("reused")?;
+    let stats = lua.registry_stats();
+    assert_eq!((stats.live, stats.pending_free, stats.peak), (6, 3, 10));
+
+    lua.expire_registry_values();
+    let stats = lua.registry_stats();
+    assert_eq!((stats.live, stats.pending_free, stats.peak), (6, 0, 10));
+
+    drop(keys);
+    lua.expire_registry_values();
+    assert_eq!(lua.registry_stats().live, 1);
+
+    Ok(())
+}
+
 #[test]
 fn test_drop_registry_value() -> Result<()> {
     struct MyUserdata(#[allow(unused)] Arc<()>);
@@ -816,6 +937,74 @@ fn test_registry_value_reuse() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_registry_value_cached() -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CONVERSIONS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Config(i64);
+
+    impl FromLua for Config {
+        fn from_lua(value: Value, lua: &Lua) -> Result<Self> {
+            CONVERSIONS.fetch_add(1, Ordering::Relaxed);
+            Ok(Config(i64::from_lua(value, lua)?))
+        }
+    }
+
+    let lua = Lua::new();
+
+    let mut key = lua.create_registry_value(1)?;
+    assert_eq!(lua.registry_value_cached::<Config>(&key)?, Config(1));
+    assert_eq!(lua.registry_value_cached::<Config>(&key)?, Config(1));
+    assert_eq!(CONVERSIONS.load(Ordering::Relaxed), 1);
+    // Different target type is cached separately
+    assert_eq!(lua.registry_value_cached::<i64>(&key)?, 1);
+
+    // Replacing the value invalidates the cache
+    lua.replace_registry_value(&mut key, 2)?;
+    assert_eq!(lua.registry_value_cached::<Config>(&key)?, Config(2));
+    assert_eq!(lua.registry_value_cached::<Config>(&key)?, Config(2));
+    assert_eq!(CONVERSIONS.load(Ordering::Relaxed), 2);
+
+    // Reused slots do not return stale values
+    drop(key);
+    let key2 = lua.create_registry_value(3)?;
+    assert_eq!(lua.registry_value_cached::<Config>(&key2)?, Config(3));
+    assert_eq!(CONVERSIONS.load(Ordering::Relaxed), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_persistent_function() -> Result<()> {
+    let lua = Lua::new();
+
+    // Survives dropping all handles
+    let f = lua.create_persistent_function("plugin.version", |_, ()| Ok(1))?;
+    drop(f);
+    lua.gc_collect()?;
+    let v1 = lua.persistent_function("plugin.version")?.unwrap();
+    assert_eq!(v1.call::<i32>(())?, 1);
+
+    // Replaced on reload, old handles remain valid
+    lua.create_persistent_function("plugin.version", |_, ()| Ok(2))?;
+    lua.expire_registry_values();
+    lua.gc_collect()?;
+    let v2 = lua.persistent_function("plugin.version")?.unwrap();
+    assert_eq!(v2.call::<i32>(())?, 2);
+    assert_eq!(v1.call::<i32>(())?, 1);
+
+    assert!(lua.persistent_function("plugin.missing")?.is_none());
+    assert!(lua.remove_persistent_function("plugin.version")?);
+    assert!(!lua.remove_persistent_function("plugin.version")?);
+    assert!(lua.persistent_function("plugin.version")?.is_none());
+    assert_eq!(v2.call::<i32>(())?, 2);
+
+    Ok(())
+}
+
 #[test]
 fn test_application_data() -> Result<()> {
     let lua = Lua::new();
@@ -874,6 +1063,60 @@ fn test_application_data() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_reserve_stack() -> Result<()> {
+    let lua = Lua::new();
+
+    let n: i64 = unsafe {
+        lua.exec_raw((), |state| {
+            lua.reserve_stack(200).unwrap();
+            for i in 1..=200 {
+                ffi::lua_pushinteger(state, i);
+            }
+            let mut sum = 0;
+            for i in 1..=200 {
+                sum += ffi::lua_tointeger(state, i);
+            }
+            ffi::lua_settop(state, 0);
+            ffi::lua_pushinteger(state, sum);
+        })
+    }?;
+    assert_eq!(n, 20100);
+
+    assert!(matches!(lua.reserve_stack(1_000_000), Err(Error::StackError)));
+
+    Ok(())
+}
+
+#[test]
+fn test_app_data_keyed() -> Result<()> {
+    let lua = Lua::new();
+
+    lua.set_app_data(0u32);
+    assert_eq!(lua.set_app_data_keyed("a", 1u32), None);
+    assert_eq!(lua.set_app_data_keyed("b", 2u32), None);
+    assert_eq!(lua.set_app_data_keyed("a", 10u32), Some(1));
+
+    assert_eq!(*lua.app_data_ref::<u32>().unwrap(), 0);
+    assert_eq!(*lua.app_data_ref_keyed::<u32>("a").unwrap(), 10);
+    assert!(lua.app_data_ref_keyed::<u32>("c").is_none());
+    assert!(lua.app_data_ref_keyed::<i32>("a").is_none());
+
+    {
+        let _a = lua.app_data_mut_keyed::<u32>("a").unwrap();
+        // Different keys can be borrowed independently
+        *lua.app_data_mut_keyed::<u32>("b").unwrap() += 1;
+    }
+    assert_eq!(*lua.app_data_ref_keyed::<u32>("b").unwrap(), 3);
+
+    assert_eq!(lua.remove_app_data_keyed::<u32>("a"), Some(10));
+    assert!(lua.app_data_ref_keyed::<u32>("a").is_none());
+    assert_eq!(lua.remove_app_data::<u32>(), Some(0));
+    assert_eq!(*lua.app_data_ref_keyed::<u32>("b").unwrap(), 3);
+
+    Ok(())
+}
+
 #[test]
 fn test_rust_function() -> Result<()> {
     let lua = Lua::new();
@@ -914,6 +1157,37 @@ fn test_c_function() -> Result<()> {
     func.call::<()>(())?;
     assert_eq!(lua.globals().get::<bool>("c_function")?, true);
 
+    unsafe extern "C-unwind" fn other_function(_: *mut mlua::lua_State) -> std::os::raw::c_int {
+        0
+    }
+
+    assert!(func.is_c_function(c_function));
+    assert!(!func.is_c_function(other_function));
+    let lua_func = lua.load("function() end").eval::<Function>()?;
+    assert!(!lua_func.is_c_function(c_function));
+    let rust_func = lua.create_function(|_, ()| Ok(()))?;
+    assert!(!rust_func.is_c_function(c_function));
+
+    Ok(())
+}
+
+#[test]
+fn test_c_closure() -> Result<()> {
+    let lua = Lua::new();
+
+    unsafe extern "C-unwind" fn c_closure(state: *mut mlua::lua_State) -> std::os::raw::c_int {
+        ffi::lua_pushvalue(state, ffi::lua_upvalueindex(1));
+        ffi::lua_pushvalue(state, ffi::lua_upvalueindex(2));
+        2
+    }
+
+    let func = unsafe { lua.create_closure(c_closure, ("hello", 123))? };
+    assert_eq!(func.call::<(String, i32)>(())?.1, 123);
+    assert_eq!(func.call::<(StdString, i32)>(())?.0, "hello");
+
+    let too_many = (0..1000).collect::<Variadic<i32>>();
+    assert!(unsafe { lua.create_closure(c_closure, too_many) }.is_err());
+
     Ok(())
 }
 
@@ -1161,6 +1435,192 @@ fn test_load_from_function() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_build_module() -> Result<()> {
+    let lua = Lua::new();
+
+    // Lua loader without return value: the environment becomes the module
+    let loader = lua
+        .load(
+            r#"
+            local modname = ...
+            version = "1.0"
+            function greet(name) return "hello " .. name .. " from " .. _NAME end
+            assert(modname == _NAME and _M.version == "1.0")
+        "#,
+        )
+        .into_function()?;
+    let module = lua.build_module("greeter", loader.clone())?;
+    let module = module.as_table().unwrap();
+    assert_eq!(module.get::<String>("version")?, "1.0");
+    assert_eq!(
+        module.get::<Function>("greet")?.call::<String>("bob")?,
+        "hello bob from greeter"
+    );
+    // Globals are not polluted
+    assert_eq!(lua.globals().get::<Value>("version")?, Value::Nil);
+    assert_eq!(lua.load("require('greeter').version").eval::<String>()?, "1.0");
+
+    // Cached modules are returned without calling the loader
+    let module2 = lua.build_module(
+        "greeter",
+        lua.create_function(|_, ()| -> Result<()> { unreachable!() })?,
+    )?;
+    assert_eq!(module2.as_table(), Some(module));
+
+    // Rust loader receives the environment table
+    let loader = lua.create_function(|_, (modname, env): (String, Table)| {
+        assert_eq!(env.get::<String>("_NAME")?, modname);
+        Ok(123)
+    })?;
+    assert_eq!(lua.build_module("number", loader)?, Value::Integer(123));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(any(feature = "luau", target_arch = "wasm32")))]
+fn test_reload_module() -> Result<()> {
+    let lua = Lua::new();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let module_path = temp_dir.path().join("reloadable.lua");
+    std::fs::write(&module_path, "loads = (loads or 0) + 1; return 1").unwrap();
+    let package: Table = lua.globals().get("package")?;
+    package.set(
+        "path",
+        temp_dir.path().join("?.lua").to_string_lossy().to_string(),
+    )?;
+
+    assert_eq!(lua.load("require('reloadable')").eval::<i32>()?, 1);
+    std::fs::write(&module_path, "loads = (loads or 0) + 1; return 2").unwrap();
+    // `require` returns cached value
+    assert_eq!(lua.load("require('reloadable')").eval::<i32>()?, 1);
+    assert_eq!(lua.unpack::<i32>(lua.reload_module("reloadable")?)?, 2);
+    assert_eq!(lua.load("require('reloadable')").eval::<i32>()?, 2);
+    // The module is loaded exactly once per `require`/`reload_module`
+    assert_eq!(lua.globals().get::<i32>("loads")?, 2);
+
+    // Failed reload keeps the previously loaded module
+    std::fs::write(&module_path, "return (").unwrap();
+    assert!(lua.reload_module("reloadable").is_err());
+    assert_eq!(lua.load("require('reloadable')").eval::<i32>()?, 2);
+
+    // Missing module
+    let err = lua.reload_module("nonexistent").unwrap_err().to_string();
+    assert!(err.contains("module 'nonexistent' not found"), "{err}");
+    assert!(err.contains("nonexistent.lua"), "{err}");
+
+    // Rust loaders from custom searchers can be reloaded
+    let searchers_key = match cfg!(any(feature = "lua51", feature = "luajit")) {
+        true => "loaders",
+        false => "searchers",
+    };
+    let searchers: Table = package.get(searchers_key)?;
+    let loader = lua.create_function(|_, ()| Ok(3))?;
+    let searcher = lua.create_function(move |_, name: String| match name.to_str()? {
+        s if s == "custom" => Ok(Value::Function(loader.clone())),
+        _ => Ok(Value::Nil),
+    })?;
+    searchers.raw_push(searcher)?;
+    assert_eq!(lua.load("require('custom')").eval::<i32>()?, 3);
+    assert_eq!(lua.unpack::<i32>(lua.reload_module("custom")?)?, 3);
+
+    // Modules provided by binary (C) libraries cannot be reloaded
+    let lua = unsafe { Lua::unsafe_new() };
+    let package: Table = lua.globals().get("package")?;
+    let searchers: Table = package.get(searchers_key)?;
+    let loader = lua.create_function(|_, ()| Ok(3))?;
+    // Simulate a C library found by the `package.cpath` searcher
+    let searcher = lua.create_function(move |_, name: String| match name.to_str()? {
+        s if s == "native" => Ok(Value::Function(loader.clone())),
+        _ => Ok(Value::Nil),
+    })?;
+    searchers.raw_set(3, searcher)?;
+    assert_eq!(lua.load("require('native')").eval::<i32>()?, 3);
+    let err = lua.reload_module("native").unwrap_err().to_string();
+    assert!(
+        err.contains("cannot reload module 'native' from a binary library"),
+        "{err}"
+    );
+    assert_eq!(lua.load("require('native')").eval::<i32>()?, 3);
+    // Original searchers are restored
+    assert_eq!(package.get::<Table>(searchers_key)?, searchers);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "luau"))]
+#[test]
+fn test_relative_require() -> Result<()> {
+    let lua = Lua::new();
+    lua.enable_relative_require()?;
+    lua.enable_relative_require()?;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let app_dir = temp_dir.path().join("app");
+    std::fs::create_dir_all(app_dir.join("util")).unwrap();
+    std::fs::write(
+        app_dir.join("main.lua"),
+        "local util = require('./util'); return util.name .. '+' .. util.helper",
+    )
+    .unwrap();
+    std::fs::write(
+        app_dir.join("util").join("init.lua"),
+        "return { name = 'util', helper = require('../shared') }",
+    )
+    .unwrap();
+    std::fs::write(app_dir.join("shared.lua"), "return 'shared'").unwrap();
+
+    let result: StdString = lua.load(app_dir.join("main.lua")).eval()?;
+    assert_eq!(result, "util+shared");
+
+    // Missing module
+    std::fs::write(
+        app_dir.join("bad.lua"),
+        "local m = require('./missing'); return m",
+    )
+    .unwrap();
+    let err = lua.load(app_dir.join("bad.lua")).exec().unwrap_err().to_string();
+    assert!(err.contains("missing.lua"), "{err}");
+
+    // Relative names outside of a file chunk
+    assert!(lua.load("require('./other')").exec().is_err());
+
+    // Same relative name in different directories refers to different modules
+    for dir in ["a", "b"] {
+        std::fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+        std::fs::write(temp_dir.path().join(dir).join("x.lua"), format!("return '{dir}'")).unwrap();
+        std::fs::write(
+            temp_dir.path().join(dir).join("main.lua"),
+            "local x = require('./x'); return x",
+        )
+        .unwrap();
+    }
+    let a: StdString = lua.load(temp_dir.path().join("a").join("main.lua")).eval()?;
+    let b: StdString = lua.load(temp_dir.path().join("b").join("main.lua")).eval()?;
+    assert_eq!((a.as_str(), b.as_str()), ("a", "b"));
+
+    // Different names referring to the same file load it once
+    std::fs::write(
+        temp_dir.path().join("a").join("again.lua"),
+        "local x = require('../b/x'); return x",
+    )
+    .unwrap();
+    let b_path = temp_dir.path().join("b").join("x.lua");
+    let loaded = lua.load("package.loaded").eval::<mlua::Table>()?;
+    loaded.set(b_path.display().to_string(), "cached")?;
+    let again: StdString = lua.load(temp_dir.path().join("a").join("again.lua")).eval()?;
+    assert_eq!(again, "cached");
+
+    // `..` above the current directory is kept
+    let chunk = lua.load("local m = require('../missing_up'); return m");
+    let err = chunk.set_name("@main.lua").exec().unwrap_err().to_string();
+    assert!(err.contains("no file '../missing_up.lua'"), "{err}");
+
+    Ok(())
+}
+
 #[test]
 fn test_inspect_stack() -> Result<()> {
     let lua = Lua::new();
@@ -1292,6 +1752,31 @@ fn test_warnings() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_warn_handler() -> Result<()> {
+    let lua = Lua::new();
+    let messages = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let messages2 = messages.clone();
+    lua.set_warn_handler(move |_, msg, incomplete| {
+        messages2.lock().unwrap().push((msg.to_string(), incomplete));
+        Ok(())
+    })?;
+    lua.load(r#"warn("script warning")"#).exec()?;
+    lua.load(r#"warn("multi", "part")"#).exec()?;
+
+    assert_eq!(
+        *messages.lock().unwrap(),
+        vec![
+            ("script warning".to_string(), false),
+            ("multi".to_string(), true),
+            ("part".to_string(), false),
+        ]
+    );
+
+    Ok(())
+}
+
 #[test]
 #[cfg(feature = "luajit")]
 fn test_luajit_cdata() -> Result<()> {
@@ -1378,6 +1863,40 @@ fn test_exec_raw() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_stack_readers() -> Result<()> {
+    let lua = Lua::new();
+
+    let mut results = None;
+    let mut is_number = false;
+    unsafe {
+        lua.exec_raw::<()>(("42", 1.5, true), |state| {
+            results = Some((
+                lua.stack_to_integer(1),
+                lua.stack_to_number(-2),
+                lua.stack_to_integer(3),
+                lua.stack_to_number(3),
+                lua.stack_to_string(2),
+                lua.stack_to_string(3),
+            ));
+            // The original value must not be converted to a string
+            is_number = ffi::lua_type(state, 2) == ffi::LUA_TNUMBER;
+            ffi::lua_settop(state, 0);
+        })?;
+    }
+
+    let (int1, num2, int3, num3, str2, str3) = results.unwrap();
+    assert_eq!(int1, Some(42));
+    assert_eq!(num2, Some(1.5));
+    assert_eq!(int3, None);
+    assert_eq!(num3, None);
+    assert_eq!(str2?.unwrap(), "1.5");
+    assert!(str3?.is_none());
+    assert!(is_number);
+
+    Ok(())
+}
+
 #[test]
 fn test_gc_drop_ref_thread() -> Result<()> {
     let lua = Lua::new();
@@ -1395,3 +1914,33 @@ fn test_gc_drop_ref_thread() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_raise_error() -> Result<()> {
+    let lua = Lua::new();
+
+    let check = lua.create_function(|lua, n: i64| {
+        if n < 0 {
+            return Err(lua.raise_error(format_args!("negative number {n}")));
+        }
+        Ok(n)
+    })?;
+    lua.globals().set("check", check)?;
+
+    let chunk = lua.load("local x = 1\ncheck(-5)").set_name("=test");
+    match chunk.exec() {
+        Err(Error::CallbackError { cause, .. }) => match cause.as_ref() {
+            Error::RuntimeError(msg) => assert_eq!(msg, "test:2: negative number -5"),
+            err => panic!("expected `RuntimeError`, got {err:?}"),
+        },
+        r => panic!("expected `CallbackError`, got {r:?}"),
+    }
+
+    // Outside of Lua code there is no position to report
+    match lua.raise_error(format_args!("plain")) {
+        Error::RuntimeError(msg) => assert_eq!(msg, "plain"),
+        err => panic!("expected `RuntimeError`, got {err:?}"),
+    }
+
+    Ok(())
+}
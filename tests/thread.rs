@@ -1,6 +1,6 @@
 use std::panic::catch_unwind;
 
-use mlua::{Error, Function, Lua, Result, Thread, ThreadStatus};
+use mlua::{Error, Function, Lua, Result, Thread, ThreadStatus, Value};
 
 #[test]
 fn test_thread() -> Result<()> {
@@ -106,6 +106,171 @@ fn test_thread() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_thread_resume_status() -> Result<()> {
+    let lua = Lua::new();
+
+    let thread = lua.create_thread(
+        lua.load("function(a) local b = coroutine.yield(a, a * 2); return b .. '!' end")
+            .eval()?,
+    )?;
+
+    let (status, (x, y)) = thread.resume_status::<(i32, i32)>(5)?;
+    assert_eq!((status, x, y), (ThreadStatus::Resumable, 5, 10));
+    let (status, s) = thread.resume_status::<String>("done")?;
+    assert_eq!((status, s.as_str()), (ThreadStatus::Finished, "done!"));
+    assert!(matches!(
+        thread.resume_status::<()>(()),
+        Err(Error::CoroutineUnresumable)
+    ));
+
+    let thread = lua.create_thread(
+        lua.load("function() coroutine.yield(1); coroutine.yield(2); return 3 end")
+            .eval()?,
+    )?;
+    let statuses = (0..3)
+        .map(|_| thread.resume_status::<i32>(()))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(
+        statuses,
+        [
+            (ThreadStatus::Resumable, 1),
+            (ThreadStatus::Resumable, 2),
+            (ThreadStatus::Finished, 3)
+        ]
+    );
+
+    let thread = lua.create_thread(lua.load("function() error('boom') end").eval()?)?;
+    assert!(thread.resume_status::<()>(()).is_err());
+    assert_eq!(thread.status(), ThreadStatus::Error);
+
+    Ok(())
+}
+
+#[test]
+fn test_thread_resume_multi() -> Result<()> {
+    let lua = Lua::new();
+
+    let thread: Thread = lua
+        .load(
+            r#"
+            coroutine.create(function(...)
+                local n = select('#', ...)
+                coroutine.yield()
+                coroutine.yield(1, nil, nil)
+                return n, "done"
+            end)
+        "#,
+        )
+        .eval()?;
+
+    assert_eq!(thread.resume_multi((1, 2, 3))?.len(), 0);
+    let values = thread.resume_multi(())?;
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0], Value::Integer(1));
+    assert!(values[1].is_nil() && values[2].is_nil());
+    let values = thread.resume_multi(())?;
+    assert_eq!(
+        values.into_vec(),
+        vec![Value::Integer(3), Value::String(lua.create_string("done")?)]
+    );
+    assert_eq!(thread.status(), ThreadStatus::Finished);
+
+    Ok(())
+}
+
+#[test]
+fn test_thread_inspect_stack() -> Result<()> {
+    let lua = Lua::new();
+
+    let thread: Thread = lua
+        .load(
+            r#"
+            coroutine.create(function()
+                local x = 1
+                coroutine.yield(x)
+                return x
+            end)
+        "#,
+        )
+        .set_name("inspect")
+        .eval()?;
+
+    // Not started yet: no frames
+    assert!(thread.inspect_stack(0).is_none());
+
+    thread.resume::<()>(())?;
+    // Skip the `coroutine.yield` frame (if any) and find the suspended Lua function
+    let debug = (0..2)
+        .filter_map(|level| thread.inspect_stack(level))
+        .find(|debug| debug.source().what == "Lua")
+        .expect("suspended Lua function frame");
+    assert_eq!(debug.curr_line(), 4);
+    assert_eq!(debug.source().short_src.as_deref(), Some("[string \"inspect\"]"));
+    drop(debug);
+
+    // Main thread is unaffected
+    assert!(lua.inspect_stack(0).is_none());
+
+    thread.resume::<()>(())?;
+    assert_eq!(thread.status(), ThreadStatus::Finished);
+    assert!(thread.inspect_stack(0).is_none());
+
+    // Running thread
+    let running = lua.create_function(|lua, ()| Ok(lua.current_thread().inspect_stack(0).is_none()))?;
+    let co = lua.create_thread(running)?;
+    assert!(co.resume::<bool>(())?);
+
+    Ok(())
+}
+
+#[test]
+fn test_thread_pool() -> Result<()> {
+    let lua = Lua::new();
+
+    let pool = lua.thread_pool(2);
+    let func: Function = lua
+        .load("function(x) local y = coroutine.yield(x); return x + y end")
+        .eval()?;
+
+    let (t1, t2, t3) = (
+        pool.acquire(func.clone())?,
+        pool.acquire(func.clone())?,
+        pool.acquire(func.clone())?,
+    );
+    let ptrs = [t1.to_pointer(), t2.to_pointer(), t3.to_pointer()];
+    assert_eq!(t1.resume::<i32>(1)?, 1);
+    assert_eq!(t1.resume::<i32>(2)?, 3);
+    assert_eq!(t2.resume::<i32>(10)?, 10);
+    drop((t1, t2, t3));
+    // Only `size` threads are kept (yielded threads can be reset only in Lua 5.4)
+    assert_eq!(pool.idle(), 2);
+
+    // Threads are reused
+    let t = pool.acquire(func.clone())?;
+    assert!(ptrs.contains(&t.to_pointer()));
+    assert_eq!(t.status(), ThreadStatus::Resumable);
+    assert_eq!(t.resume::<i32>(5)?, 5);
+    assert_eq!(t.resume::<i32>(5)?, 10);
+
+    // Detached threads are not returned to the pool
+    let detached = pool.acquire(func)?.into_inner();
+    drop(t);
+    assert_eq!(pool.idle(), 1);
+    assert_eq!(detached.status(), ThreadStatus::Resumable);
+
+    // Functions from a different Lua instance are rejected
+    let lua2 = Lua::new();
+    let func2 = lua2.create_function(|_, ()| Ok(()))?;
+    match pool.acquire(func2) {
+        Err(Error::RuntimeError(msg)) => assert_eq!(msg, "function belongs to a different Lua instance"),
+        r => panic!("expected RuntimeError, got {r:?}"),
+    }
+    assert_eq!(pool.idle(), 1);
+
+    Ok(())
+}
+
 #[test]
 fn test_thread_reset() -> Result<()> {
     use mlua::{AnyUserData, UserData};
@@ -1,9 +1,7 @@
 use std::collections::HashMap;
 use std::string::String as StdString;
-use std::sync::Arc;
-
-#[cfg(feature = "lua54")]
 use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
 
 use mlua::{
     AnyUserData, Error, ExternalError, Function, Lua, MetaMethod, Nil, ObjectLike, Result, String, UserData,
@@ -84,6 +82,262 @@ fn test_methods() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_method_ref() -> Result<()> {
+    struct MyUserData {
+        name: StdString,
+        data: Vec<u8>,
+    }
+
+    impl UserData for MyUserData {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_method_ref("name", |_, this, ()| Ok(this.name.as_str()));
+            methods.add_method_ref("slice", |_, this, (i, j): (usize, usize)| {
+                this.data.get(i..j).ok_or_else(|| "out of range".into_lua_err())
+            });
+        }
+    }
+
+    let lua = Lua::new();
+    let ud = lua.create_userdata(MyUserData {
+        name: "hello".into(),
+        data: vec![1, 2, 3, 255],
+    })?;
+    lua.globals().set("ud", ud)?;
+    lua.load(
+        r#"
+        assert(ud:name() == "hello")
+        assert(ud:slice(1, 4) == "\2\3\255")
+        assert(not pcall(ud.slice, ud, 3, 5))
+    "#,
+    )
+    .exec()
+}
+
+#[test]
+fn test_method_replace() -> Result<()> {
+    enum Door {
+        Open(u32),
+        Closed(u32),
+    }
+
+    impl UserData for Door {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_method("state", |_, this, ()| {
+                Ok(match this {
+                    Door::Open(n) => format!("open {n}"),
+                    Door::Closed(n) => format!("closed {n}"),
+                })
+            });
+            methods.add_method_replace("toggle", |_, this, ()| match this {
+                Door::Open(n) => Ok((Door::Closed(n + 1), "closed")),
+                Door::Closed(n) => Ok((Door::Open(n + 1), "opened")),
+            });
+            methods.add_method_replace("smash", |_, _, ()| Err::<(Door, ()), _>("broken".into_lua_err()));
+            methods.add_method_replace("reenter", |_, this, f: Function| {
+                // The userdata cannot be accessed while the method is running
+                let reentered = f.call::<()>(()).is_ok();
+                Ok((this, reentered))
+            });
+        }
+    }
+
+    let lua = Lua::new();
+    let door = lua.create_userdata(Door::Closed(0))?;
+    lua.globals().set("door", &door)?;
+    lua.load(
+        r#"
+        assert(door:toggle() == "opened")
+        assert(door:state() == "open 1")
+        assert(door:toggle() == "closed")
+        assert(door:state() == "closed 2")
+        assert(door:reenter(function() door:state() end) == false)
+        assert(door:state() == "closed 2")
+    "#,
+    )
+    .exec()?;
+
+    // Failed transition leaves the userdata destructed
+    assert!(lua.load("door:smash()").exec().is_err());
+    match door.borrow::<Door>() {
+        Err(Error::UserDataDestructed) => {}
+        r => panic!("expected `UserDataDestructed` error, got {:?}", r.map(|_| ())),
+    }
+
+    // The consumed value is dropped exactly once
+    struct Token(#[allow(unused)] Arc<()>);
+    impl UserData for Token {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_method_replace("fail", |_, _, ()| Err::<(Token, ()), _>("failed".into_lua_err()));
+        }
+    }
+    let rc = Arc::new(());
+    let token = lua.create_userdata(Token(rc.clone()))?;
+    assert!(token.call_method::<()>("fail", ()).is_err());
+    assert_eq!(Arc::strong_count(&rc), 1);
+    drop(token);
+    lua.gc_collect()?;
+    assert_eq!(Arc::strong_count(&rc), 1);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "luau"))]
+#[test]
+fn test_userdata_finalizer_priority() -> Result<()> {
+    use std::sync::Mutex;
+
+    type Log = Arc<Mutex<Vec<&'static str>>>;
+
+    struct Writer(Log);
+    struct File(Log);
+    struct Other(Log);
+
+    impl Drop for Writer {
+        fn drop(&mut self) {
+            self.0.lock().unwrap().push("writer");
+        }
+    }
+    impl Drop for File {
+        fn drop(&mut self) {
+            self.0.lock().unwrap().push("file");
+        }
+    }
+    impl Drop for Other {
+        fn drop(&mut self) {
+            self.0.lock().unwrap().push("other");
+        }
+    }
+
+    impl UserData for Writer {
+        fn register(registry: &mut mlua::UserDataRegistry<Self>) {
+            registry.set_finalizer_priority(10);
+        }
+    }
+    impl UserData for Other {}
+
+    let log = Log::default();
+    let lua = Lua::new();
+    lua.register_userdata_type::<File>(|reg| reg.set_finalizer_priority(1))?;
+
+    let list = lua.create_table()?;
+    for _ in 0..10 {
+        list.raw_push(lua.create_any_userdata(File(log.clone()))?)?;
+        list.raw_push(lua.create_userdata(Other(log.clone()))?)?;
+        list.raw_push(lua.create_userdata(Writer(log.clone()))?)?;
+    }
+    lua.globals().set("list", list)?;
+
+    // Userdata destructed earlier is not finalized again
+    let writer = lua.create_userdata(Writer(log.clone()))?;
+    drop(writer.take::<Writer>()?);
+    assert_eq!(*log.lock().unwrap(), ["writer"]);
+    log.lock().unwrap().clear();
+
+    drop(writer);
+    drop(lua);
+    let log = log.lock().unwrap();
+    assert_eq!(log.len(), 30);
+    assert!(log[..10].iter().all(|&s| s == "writer"));
+    assert!(log[10..20].iter().all(|&s| s == "file"));
+    assert!(log[20..].iter().all(|&s| s == "other"));
+
+    Ok(())
+}
+
+#[test]
+fn test_userdata_borrow_scoped() -> Result<()> {
+    struct MyUserData(i64);
+
+    impl UserData for MyUserData {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_method_mut("inc", |_, data, ()| {
+                data.0 += 1;
+                Ok(())
+            });
+        }
+    }
+
+    let lua = Lua::new();
+    let ud = lua.create_userdata(MyUserData(1))?;
+    let inc = lua.load("function(ud) ud:inc() end").eval::<Function>()?;
+
+    // Borrow is released before re-entering Lua
+    let value = ud.borrow_scoped::<MyUserData, _>(|data| data.0)?;
+    inc.call::<()>(&ud)?;
+    assert_eq!(ud.borrow_scoped::<MyUserData, _>(|data| data.0)?, value + 1);
+
+    ud.borrow_mut_scoped::<MyUserData, _>(|data| data.0 = 10)?;
+    inc.call::<()>(&ud)?;
+    assert_eq!(ud.borrow::<MyUserData>()?.0, 11);
+
+    // Holding a borrow inside the closure still conflicts with mutable access
+    let result = ud.borrow_scoped::<MyUserData, _>(|_| inc.call::<()>(&ud))?;
+    assert!(result.is_err());
+
+    // Type mismatch
+    assert!(matches!(
+        ud.borrow_scoped::<StdString, _>(|_| ()),
+        Err(Error::UserDataTypeMismatch)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_userdata_cached_field() -> Result<()> {
+    struct MyUserData(i64, Arc<AtomicI64>);
+
+    impl UserData for MyUserData {
+        fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+            fields.add_field_method_get_cached("value", |_, this| {
+                this.1.fetch_add(1, Ordering::Relaxed);
+                Ok(this.0)
+            });
+            fields.add_field_method_get_cached("none", |_, this| {
+                this.1.fetch_add(1, Ordering::Relaxed);
+                Ok(Nil)
+            });
+        }
+    }
+
+    let lua = Lua::new();
+    let calls = Arc::new(AtomicI64::new(0));
+    let ud1 = lua.create_userdata(MyUserData(1, calls.clone()))?;
+    let ud2 = lua.create_userdata(MyUserData(2, calls.clone()))?;
+
+    let sum = lua
+        .load("function(ud1, ud2) local s = 0; for i = 1, 10 do s = s + ud1.value + ud2.value end; return s end")
+        .eval::<Function>()?;
+    assert_eq!(sum.call::<i64>((&ud1, &ud2))?, 30);
+    // The getter is called once per instance
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+    // `nil` is not cached
+    lua.load("local ud = ...; assert(ud.none == nil and ud.none == nil)")
+        .call::<()>(&ud1)?;
+    assert_eq!(calls.load(Ordering::Relaxed), 4);
+
+    // User values are not affected
+    ud1.set_user_value("hello")?;
+    ud1.set_named_user_value("name", "world")?;
+    assert_eq!(lua.load("local ud = ...; return ud.value").call::<i64>(&ud1)?, 1);
+    assert_eq!(ud1.user_value::<StdString>()?, "hello");
+    assert_eq!(ud1.named_user_value::<StdString>("name")?, "world");
+
+    // Cached values are not returned for destructed userdata
+    let _ = ud2.take::<MyUserData>()?;
+    ud1.destroy()?;
+    for ud in [&ud1, &ud2] {
+        match lua.load("local ud = ...; return ud.value").call::<i64>(ud) {
+            Err(Error::CallbackError { ref cause, .. }) if matches!(**cause, Error::UserDataDestructed) => {}
+            r => panic!("expected `UserDataDestructed` error, got {r:?}"),
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_method_variadic() -> Result<()> {
     struct MyUserData(i64);
@@ -197,6 +451,13 @@ fn test_metamethods() -> Result<()> {
     assert!(userdata2 != userdata3); // because references are differ
     assert!(userdata2.equals(&userdata3)?);
 
+    let ud2: AnyUserData = globals.get("userdata2")?;
+    let ud3: AnyUserData = globals.get("userdata3")?;
+    assert!(ud2.ptr_eq(&globals.get("userdata2")?));
+    assert!(!ud2.ptr_eq(&ud3));
+    assert!(ud2.equals(&ud3)?);
+    assert!(!ud2.equals(&globals.get("userdata1")?)?);
+
     let userdata1: AnyUserData = globals.get("userdata1")?;
     assert!(userdata1.metatable()?.contains(MetaMethod::Add)?);
     assert!(userdata1.metatable()?.contains(MetaMethod::Sub)?);
@@ -334,6 +595,10 @@ fn test_userdata_take() -> Result<()> {
             Err(Error::UserDataDestructed) => {}
             r => panic!("expected `UserDataDestructed` error, got {:?}", r),
         }
+        match userdata.borrow_mut::<MyUserdata>() {
+            Err(Error::UserDataDestructed) => {}
+            r => panic!("expected `UserDataDestructed` error, got {:?}", r),
+        }
         match lua.load("userdata:num()").exec() {
             Err(Error::CallbackError { ref cause, .. }) => match cause.as_ref() {
                 Error::UserDataDestructed => {}
@@ -855,6 +1120,11 @@ fn test_userdata_pointer() -> Result<()> {
     // Different userdata objects with the same value should have different pointers
     assert_ne!(ud1.to_pointer(), ud2.to_pointer());
 
+    // Userdata can be used as hash map keys (by identity)
+    let map = HashMap::from([(ud1.clone(), 1), (ud2.clone(), 2)]);
+    assert_eq!(map[&ud1], 1);
+    assert_eq!(map[&ud2], 2);
+
     Ok(())
 }
 
@@ -1066,3 +1336,37 @@ fn test_userdata_wrappers() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_userdata_proxy_field_functions() -> Result<()> {
+    struct Version(StdString);
+    struct Config;
+
+    impl UserData for Config {
+        fn add_fields<F: UserDataFields<Self>>(fields: &mut F) {
+            fields.add_field_function_get("version", |lua, _| {
+                Ok(lua.app_data_ref::<Version>().map(|v| v.0.clone()))
+            });
+            fields.add_field_function_set("version", |lua, _, v: StdString| {
+                lua.set_app_data(Version(v));
+                Ok(())
+            });
+        }
+    }
+
+    let lua = Lua::new();
+    lua.set_app_data(Version("1.0".into()));
+    lua.globals().set("Config", lua.create_proxy::<Config>()?)?;
+
+    lua.load(
+        r#"
+        assert(Config.version == "1.0")
+        Config.version = "2.0"
+        assert(Config.version == "2.0")
+    "#,
+    )
+    .exec()?;
+    assert_eq!(lua.app_data_ref::<Version>().unwrap().0, "2.0");
+
+    Ok(())
+}
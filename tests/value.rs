@@ -3,7 +3,7 @@ use std::os::raw::c_void;
 use std::ptr;
 use std::string::String as StdString;
 
-use mlua::{Error, LightUserData, Lua, MultiValue, Result, UserData, UserDataMethods, Value};
+use mlua::{Error, LightUserData, Lua, MetaMethod, MultiValue, Result, UserData, UserDataMethods, Value};
 
 #[test]
 fn test_value_eq() -> Result<()> {
@@ -121,6 +121,11 @@ fn test_value_to_pointer() -> Result<()> {
     assert!(null.to_pointer().is_null());
     assert!(!ud.to_pointer().is_null());
 
+    // Handles to the same table share the pointer, distinct tables differ
+    let table2: Value = globals.get("table")?;
+    assert_eq!(table.to_pointer(), table2.to_pointer());
+    assert_ne!(table.to_pointer(), Value::Table(lua.create_table()?).to_pointer());
+
     Ok(())
 }
 
@@ -209,6 +214,58 @@ fn test_value_to_string() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_value_get_metamethod() -> Result<()> {
+    struct MyUserData;
+
+    impl UserData for MyUserData {
+        fn add_methods<M: UserDataMethods<Self>>(methods: &mut M) {
+            methods.add_meta_method(MetaMethod::ToString, |_, _, ()| Ok("MyUserData"));
+        }
+    }
+
+    let lua = Lua::new();
+
+    let ud = Value::UserData(lua.create_userdata(MyUserData)?);
+    let tostring = ud.get_metamethod(&lua, MetaMethod::ToString)?.unwrap();
+    assert_eq!(
+        tostring.as_function().unwrap().call::<StdString>(&ud)?,
+        "MyUserData"
+    );
+    assert!(ud.get_metamethod(&lua, MetaMethod::Call)?.is_none());
+
+    // Tables without metatable
+    let t = Value::Table(lua.create_table()?);
+    assert!(t.get_metamethod(&lua, MetaMethod::Index)?.is_none());
+
+    // Metatable fields are read raw
+    let t = lua
+        .load("setmetatable({}, setmetatable({__call = 1}, {__index = function() return 2 end}))")
+        .eval::<Value>()?;
+    assert_eq!(t.get_metamethod(&lua, MetaMethod::Call)?, Some(Value::Integer(1)));
+    assert!(t.get_metamethod(&lua, MetaMethod::Len)?.is_none());
+
+    // Strings have the `string` library as `__index`
+    let s = Value::String(lua.create_string("abc")?);
+    assert!(s.get_metamethod(&lua, MetaMethod::Index)?.unwrap().is_table());
+
+    // Values of other types use the metatable of their type
+    assert!(Value::Integer(1).get_metamethod(&lua, MetaMethod::Add)?.is_none());
+    assert!(Value::Nil.get_metamethod(&lua, MetaMethod::Index)?.is_none());
+    #[cfg(not(feature = "luau"))]
+    {
+        let mt = lua.create_table_from([("__call", 1)])?;
+        lua.set_type_metatable::<bool>(Some(mt.clone()));
+        lua.set_type_metatable::<mlua::Number>(Some(mt));
+        let call = Value::Boolean(true).get_metamethod(&lua, MetaMethod::Call)?;
+        assert_eq!(call, Some(Value::Integer(1)));
+        let call = Value::Number(1.5).get_metamethod(&lua, MetaMethod::Call)?;
+        assert_eq!(call, Some(Value::Integer(1)));
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_debug_format() -> Result<()> {
     let lua = Lua::new();
@@ -318,3 +375,30 @@ fn test_value_exhaustive_match() {
         Value::Other(_) => {}
     }
 }
+
+#[test]
+fn test_value_clone_into() -> Result<()> {
+    let lua = Lua::new();
+    let table = Value::Table(lua.create_table_from([("a", 1)])?);
+
+    // Another handle to the same main state
+    let lua2 = unsafe {
+        let mut state = ptr::null_mut();
+        lua.exec_raw::<()>((), |s| state = s)?;
+        Lua::init_from_ptr(state)
+    };
+    let cloned = table.clone_into(&lua2)?;
+    assert_eq!(cloned.to_pointer(), table.to_pointer());
+    lua2.globals().set("t", cloned)?;
+    assert_eq!(lua2.load("t.a").eval::<i32>()?, 1);
+
+    // Primitive values are simply copied
+    assert_eq!(Value::Integer(5).clone_into(&lua2)?, Value::Integer(5));
+
+    // Different main state
+    let other = Lua::new();
+    let err = table.clone_into(&other).unwrap_err();
+    assert!(err.to_string().contains("different main Lua state"), "{err}");
+
+    Ok(())
+}